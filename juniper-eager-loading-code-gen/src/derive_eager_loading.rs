@@ -6,6 +6,7 @@ use heck::{CamelCase, SnakeCase};
 use lazy_static::lazy_static;
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
 use syn::{
     parse_macro_input, DeriveInput, GenericArgument, Ident, NestedMeta, PathArguments, Type,
@@ -13,12 +14,22 @@ use syn::{
 
 pub fn gen_tokens(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
+
+    if let Err(err) = ensure_struct_with_named_fields(&ast) {
+        return err.to_compile_error().into();
+    }
+
     let args = match DeriveArgs::from_derive_input(&ast) {
         Ok(args) => args,
-        Err(err) => panic!("{}", err),
+        Err(err) => return err.write_errors().into(),
     };
 
     let out = DeriveData::new(ast, args);
+
+    if let Err(err) = out.validate_fields() {
+        return err.write_errors().into();
+    }
+
     let tokens = out.build_derive_output();
 
     derive_macro_called();
@@ -26,8 +37,26 @@ pub fn gen_tokens(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     tokens.into()
 }
 
+fn ensure_struct_with_named_fields(ast: &DeriveInput) -> syn::Result<()> {
+    use syn::{Data, Fields};
+
+    match &ast.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(_) => Ok(()),
+            Fields::Unit | Fields::Unnamed(_) => Err(syn::Error::new_spanned(
+                &ast.ident,
+                "`#[derive(EagerLoading)]` only supports structs with named fields",
+            )),
+        },
+        Data::Enum(_) | Data::Union(_) => Err(syn::Error::new_spanned(
+            &ast.ident,
+            "`#[derive(EagerLoading)]` can only be derived on structs",
+        )),
+    }
+}
+
 lazy_static! {
-    static ref FIRST_DERIVE_CALL: AtomicBool = { AtomicBool::new(true) };
+    static ref FIRST_DERIVE_CALL: AtomicBool = AtomicBool::new(true);
 }
 
 fn derive_macro_called() {
@@ -59,12 +88,83 @@ impl DeriveData {
         }
 
         self.gen_graphql_node_for_model();
+        self.gen_prime_cache();
+        self.gen_error_conversion();
+        self.gen_loading_plan();
         self.gen_eager_load_children_of_type();
         self.gen_eager_load_all_children();
 
+        if self.args.print() || self.args.emit_to().is_some() {
+            let source = pretty_print(&self.tokens);
+
+            if self.args.print() {
+                eprintln!("{}", source);
+            }
+
+            if let Some(path) = self.args.emit_to() {
+                if let Err(err) = std::fs::write(path, &source) {
+                    panic!("Failed to write generated code to `{}`: {}", path, err);
+                }
+            }
+        }
+
         self.tokens
     }
 
+    fn gen_prime_cache(&mut self) {
+        let struct_name = self.struct_name();
+        let model = self.model();
+        let id = self.id();
+
+        // On a generic struct the model type mentions the parameters, so the bounds the cache
+        // needs can't be assumed — spell them out. On concrete structs they always hold, and
+        // adding them would just make every model a required part of the public signature.
+        let mut generics = self.input.generics.clone();
+        if !generics.params.is_empty() {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(syn::parse_quote!(
+                    #model: 'static + std::clone::Clone + juniper_eager_loading::MaybeSend
+                ));
+        }
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        self.tokens.extend(quote! {
+            impl #impl_generics #struct_name #ty_generics #where_clause {
+                /// Pre-populate an eager loading cache with these models, keyed by their ids,
+                /// so loaders that consult the cache issue no query for rows the resolver
+                /// already fetched — through a join, say.
+                pub fn prime_cache(
+                    models: &[#model],
+                    cache: &mut juniper_eager_loading::Cache<#id>,
+                ) {
+                    cache.prime(models.iter().cloned(), |model| {
+                        std::clone::Clone::clone(&model.id)
+                    });
+                }
+            }
+        });
+    }
+
+    fn gen_error_conversion(&mut self) {
+        // Opt-in, because multiple derived structs usually share one error type and a `From`
+        // impl can only be emitted once for it.
+        let variant = match self.args.error_variant() {
+            Some(variant) => variant,
+            None => return,
+        };
+        let error = self.error();
+
+        self.tokens.extend(quote! {
+            impl std::convert::From<juniper_eager_loading::Error> for #error {
+                fn from(error: juniper_eager_loading::Error) -> Self {
+                    #error::#variant(error)
+                }
+            }
+        });
+    }
+
     fn gen_impl_of_marker_trait(&mut self) {
         self.tokens.extend(quote! {
             impl<'a, T> juniper_eager_loading::GenericQueryTrail<T, juniper_from_schema::Walked>
@@ -81,18 +181,67 @@ impl DeriveData {
         let connection = self.connection();
         let error = self.error();
 
-        let field_setters = self.struct_fields().map(|field| {
+        let field_setters = self
+            .struct_fields()
+            .map(|field| {
+                let ident = &field.ident;
+
+                if field_is_skipped(field) {
+                    return quote! { #ident: std::default::Default::default() };
+                }
+
+                match association_type(&field.ty) {
+                    // Edges remember which type and field they sit in so errors (and the
+                    // strict not-loaded panics) can name their location.
+                    Some(AssociationType::HasOne) => {
+                        quote! { #ident: juniper_eager_loading::HasOne::new_for_field(stringify!(#struct_name), stringify!(#ident)) }
+                    }
+                    Some(AssociationType::OptionHasOne) => {
+                        quote! { #ident: juniper_eager_loading::OptionHasOne::new_for_field(stringify!(#struct_name), stringify!(#ident)) }
+                    }
+                    Some(AssociationType::HasMany) => {
+                        quote! { #ident: juniper_eager_loading::HasMany::new_for_field(stringify!(#struct_name), stringify!(#ident)) }
+                    }
+                    Some(AssociationType::HasManyThrough) => {
+                        quote! { #ident: juniper_eager_loading::HasManyThrough::new_for_field(stringify!(#struct_name), stringify!(#ident)) }
+                    }
+                    None => quote! { #ident: std::clone::Clone::clone(model) },
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let owned_field_setters = self.struct_fields().map(|field| {
             let ident = &field.ident;
 
-            if is_association_field(&field.ty) {
-                quote! { #ident: Default::default() }
-            } else {
-                quote! { #ident: std::clone::Clone::clone(model) }
+            if field_is_skipped(field) {
+                return quote! { #ident: std::default::Default::default() };
+            }
+
+            match association_type(&field.ty) {
+                Some(AssociationType::HasOne) => {
+                    quote! { #ident: juniper_eager_loading::HasOne::new_for_field(stringify!(#struct_name), stringify!(#ident)) }
+                }
+                Some(AssociationType::OptionHasOne) => {
+                    quote! { #ident: juniper_eager_loading::OptionHasOne::new_for_field(stringify!(#struct_name), stringify!(#ident)) }
+                }
+                Some(AssociationType::HasMany) => {
+                    quote! { #ident: juniper_eager_loading::HasMany::new_for_field(stringify!(#struct_name), stringify!(#ident)) }
+                }
+                Some(AssociationType::HasManyThrough) => {
+                    quote! { #ident: juniper_eager_loading::HasManyThrough::new_for_field(stringify!(#struct_name), stringify!(#ident)) }
+                }
+                // The one difference from `new_from_model`: the model is moved in, not cloned.
+                None => quote! { #ident: model },
             }
         });
 
+        let (impl_generics, ty_generics, where_clause) = self.input.generics.split_for_impl();
+
         self.tokens.extend(quote! {
-            impl juniper_eager_loading::GraphqlNodeForModel for #struct_name {
+            impl #impl_generics juniper_eager_loading::GraphqlNodeForModel
+                for #struct_name #ty_generics
+            #where_clause
+            {
                 type Model = #model;
                 type Id = #id;
                 type Connection = #connection;
@@ -103,10 +252,137 @@ impl DeriveData {
                         #(#field_setters),*
                     }
                 }
+
+                fn new_from_owned_model(model: Self::Model) -> Self {
+                    Self {
+                        #(#owned_field_setters),*
+                    }
+                }
+            }
+        });
+    }
+
+    fn gen_loading_plan(&mut self) {
+        let struct_name = self.struct_name();
+
+        let edges = self
+            .struct_fields()
+            .filter_map(|field| self.loading_plan_edge_for_field(field))
+            .collect::<Vec<_>>();
+
+        let trail_edges = self
+            .struct_fields()
+            .filter_map(|field| self.loading_plan_trail_edge_for_field(field))
+            .collect::<Vec<_>>();
+
+        let (impl_generics, ty_generics, where_clause) = self.input.generics.split_for_impl();
+
+        self.tokens.extend(quote! {
+            impl #impl_generics juniper_eager_loading::LoadingPlanned
+                for #struct_name #ty_generics
+            #where_clause
+            {
+                #[allow(unused_mut, unused_variables)]
+                fn loading_plan_into(
+                    visited: &mut Vec<&'static str>,
+                ) -> juniper_eager_loading::LoadingPlan {
+                    let mut plan = juniper_eager_loading::LoadingPlan::new(stringify!(#struct_name));
+                    visited.push(stringify!(#struct_name));
+                    #(#edges)*
+                    visited.pop();
+                    plan
+                }
+            }
+
+            impl #impl_generics #struct_name #ty_generics #where_clause {
+                /// The loading plan for this type, annotated with which branches the given
+                /// query trail actually selects.
+                ///
+                /// Unselected branches aren't followed, so no cycle detection is needed: the
+                /// recursion is bounded by the depth of the query.
+                #[allow(unused_mut, unused_variables)]
+                pub fn loading_plan_for_trail<'a>(
+                    trail: &QueryTrail<'a, Self, juniper_from_schema::Walked>,
+                ) -> juniper_eager_loading::LoadingPlan {
+                    let mut plan = juniper_eager_loading::LoadingPlan::new(stringify!(#struct_name));
+                    #(#trail_edges)*
+                    plan
+                }
             }
         });
     }
 
+    fn loading_plan_edge_for_field(&self, field: &syn::Field) -> Option<TokenStream> {
+        let (args, data) = self.parse_field_args(field)?;
+        if args.skip {
+            return None;
+        }
+
+        let inner_type = &data.inner_type;
+        let field_ident = &field.ident;
+        let edge_kind = self.association_type_tokens(field)?;
+        let foreign_key_field = &data.foreign_key_field;
+
+        Some(quote! {
+            plan.edges.push(juniper_eager_loading::LoadingPlanEdge {
+                field_name: stringify!(#field_ident),
+                edge_kind: #edge_kind,
+                child_type: stringify!(#inner_type),
+                foreign_key: Some(stringify!(#foreign_key_field)),
+                selected: None,
+                child: if visited.contains(&stringify!(#inner_type)) {
+                    None
+                } else {
+                    Some(
+                        <#inner_type as juniper_eager_loading::LoadingPlanned>::loading_plan_into(
+                            visited,
+                        ),
+                    )
+                },
+            });
+        })
+    }
+
+    fn loading_plan_trail_edge_for_field(&self, field: &syn::Field) -> Option<TokenStream> {
+        let (args, data) = self.parse_field_args(field)?;
+        if args.skip {
+            return None;
+        }
+
+        let inner_type = &data.inner_type;
+        let field_ident = &field.ident;
+        let field_name = self.graphql_field_name(field)?;
+        let edge_kind = self.association_type_tokens(field)?;
+        let foreign_key_field = &data.foreign_key_field;
+
+        Some(quote! {
+            let walked = trail.#field_name().walk();
+            plan.edges.push(juniper_eager_loading::LoadingPlanEdge {
+                field_name: stringify!(#field_ident),
+                edge_kind: #edge_kind,
+                child_type: stringify!(#inner_type),
+                foreign_key: Some(stringify!(#foreign_key_field)),
+                selected: Some(walked.is_some()),
+                child: walked.map(|child_trail| {
+                    #inner_type::loading_plan_for_trail(&child_trail)
+                }),
+            });
+        })
+    }
+
+    fn association_type_tokens(&self, field: &syn::Field) -> Option<TokenStream> {
+        Some(match association_type(&field.ty)? {
+            AssociationType::HasOne => quote! { juniper_eager_loading::AssociationType::HasOne },
+            AssociationType::OptionHasOne => {
+                quote! { juniper_eager_loading::AssociationType::OptionHasOne }
+            }
+            AssociationType::HasMany => quote! { juniper_eager_loading::AssociationType::HasMany },
+            AssociationType::HasManyThrough => {
+                quote! { juniper_eager_loading::AssociationType::HasManyThrough }
+            }
+        })
+    }
+
     fn gen_eager_load_children_of_type(&mut self) {
         let impls = self
             .struct_fields()
@@ -125,28 +401,42 @@ impl DeriveData {
         let child_ids_impl = self.child_ids_impl(&data);
         let load_children_impl = self.load_children_impl(&data);
         let is_child_of_impl = self.is_child_of_impl(&data);
+        let match_hash_impl = self.match_hash_impl(&data);
         let loaded_or_failed_child_impl = self.loaded_or_failed_child_impl(&data);
         let assert_loaded_otherwise_failed_impl = self.assert_loaded_otherwise_failed_impl(&data);
+        let on_missing_children_impl = self.on_missing_children_impl(&data);
+        let order_children_impl = self.order_children_impl(&data);
+        let pagination_impl = self.pagination_impl(&data);
 
-        let context = self.field_context_name(&field);
+        let context = self.field_context_name(field);
+
+        let children_of_type_trait = self.children_of_type_trait();
+
+        let impl_generics = self.impl_generics_with_trail_lifetime();
+        let ty_generics = self.ty_generics();
+        let where_clause = self.where_clause();
 
         let full_output = quote! {
             #[allow(missing_doc, dead_code)]
             struct #context;
 
-            impl<'a> EagerLoadChildrenOfType<
+            impl #impl_generics #children_of_type_trait<
                 #inner_type,
                 QueryTrail<'a, #inner_type, juniper_from_schema::Walked>,
                 #context,
                 #join_model_impl,
-            > for #struct_name {
+            > for #struct_name #ty_generics #where_clause {
                 type ChildId = #child_id;
 
                 #child_ids_impl
                 #load_children_impl
                 #is_child_of_impl
+                #match_hash_impl
                 #loaded_or_failed_child_impl
                 #assert_loaded_otherwise_failed_impl
+                #on_missing_children_impl
+                #order_children_impl
+                #pagination_impl
             }
         };
 
@@ -161,36 +451,76 @@ impl DeriveData {
         }
     }
 
+    /// Report every invalid field attribute with a span before code generation starts, so a
+    /// misused derive produces readable compile errors instead of panics or unrelated
+    /// trait-bound failures in the generated code.
+    fn validate_fields(&self) -> Result<(), darling::Error> {
+        let mut errors = Vec::new();
+
+        for field in self.struct_fields() {
+            if let Err(err) = self.try_parse_field_args(field) {
+                errors.push(err);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(darling::Error::multiple(errors))
+        }
+    }
+
     fn parse_field_args(&self, field: &syn::Field) -> Option<(FieldArgs, FieldDeriveData)> {
-        let inner_type = get_type_from_association(&field.ty)?;
-        let association_type = association_type(&field.ty)?;
+        // `validate_fields` has already reported any errors here, so by the time code
+        // generation runs this can't fail.
+        self.try_parse_field_args(field)
+            .expect("field args are validated before code generation")
+    }
+
+    fn try_parse_field_args(
+        &self,
+        field: &syn::Field,
+    ) -> Result<Option<(FieldArgs, FieldDeriveData)>, darling::Error> {
+        use std::convert::TryFrom;
+
+        let (inner_type, association_type) = match (
+            get_type_from_association(&field.ty),
+            association_type(&field.ty),
+        ) {
+            (Some(inner_type), Some(association_type)) => (inner_type, association_type),
+            _ => {
+                if let Some(attr) = association_attribute(field) {
+                    return Err(darling::Error::custom(
+                        "this attribute expects a field of type `HasOne<_>`, \
+                         `OptionHasOne<_>`, `HasMany<_>`, or `HasManyThrough<_>`",
+                    )
+                    .with_span(attr));
+                }
+                return Ok(None);
+            }
+        };
 
         let args = match association_type {
             AssociationType::HasOne => {
-                let args = parse_field_args::<HasOne>(&field)
-                    .unwrap_or_else(|e| panic!("{}", e))
-                    .has_one;
-                FieldArgs::from(args)
+                FieldArgs::try_from(parse_field_args::<HasOne>(field)?.has_one)
             }
             AssociationType::OptionHasOne => {
-                let args = parse_field_args::<OptionHasOne>(&field)
-                    .unwrap_or_else(|e| panic!("{}", e))
-                    .option_has_one;
-                FieldArgs::from(args)
+                FieldArgs::try_from(parse_field_args::<OptionHasOne>(field)?.option_has_one)
             }
             AssociationType::HasMany => {
-                let args = parse_field_args::<HasMany>(&field)
-                    .unwrap_or_else(|e| panic!("{}", e))
-                    .has_many;
-                FieldArgs::from(args)
+                FieldArgs::try_from(parse_field_args::<HasMany>(field)?.has_many)
             }
             AssociationType::HasManyThrough => {
-                let args = parse_field_args::<HasManyThrough>(&field)
-                    .unwrap_or_else(|e| panic!("{}", e))
-                    .has_many_through;
-                FieldArgs::from(args)
+                FieldArgs::try_from(parse_field_args::<HasManyThrough>(field)?.has_many_through)
             }
         };
+        let args = args.map_err(|err| {
+            if err.has_span() {
+                err
+            } else {
+                err.with_span(field)
+            }
+        })?;
 
         let field_name = field.ident.as_ref().unwrap_or_else(|| {
             panic!("Found `juniper_eager_loading::HasOne` field without a name")
@@ -198,7 +528,13 @@ impl DeriveData {
 
         let foreign_key_field_default = match association_type {
             AssociationType::HasMany | AssociationType::HasManyThrough => self.struct_name(),
-            AssociationType::HasOne | AssociationType::OptionHasOne => &field_name,
+            AssociationType::HasOne | AssociationType::OptionHasOne => field_name,
+        };
+
+        let inner_type = if args.shared {
+            shared_child_type(inner_type)
+        } else {
+            inner_type
         };
 
         let data = FieldDeriveData {
@@ -206,16 +542,22 @@ impl DeriveData {
             inner_type: inner_type.clone(),
             root_model_field: self.root_model_field().clone(),
             join_model: args.join_model(),
-            model_field: args.model_field(&inner_type),
+            model_field: args.model_field(inner_type),
             join_model_field: args.join_model_field(),
             foreign_key_field: args.foreign_key_field(foreign_key_field_default),
             foreign_key_optional: args.foreign_key_optional,
-            field_root_model_field: args.root_model_field(&field_name),
+            field_root_model_field: args.root_model_field(field_name),
             association_type,
+            ids_field: args.ids_field(),
             predicate_method: args.predicate_method(),
+            order_by_method: args.order_by_method(),
+            paginate_with: args.paginate_with(),
+            shared: args.shared,
+            on_missing_error: args.on_missing_error(),
+            on_missing_null: args.on_missing_null(),
         };
 
-        Some((args, data))
+        Ok(Some((args, data)))
     }
 
     fn join_model_impl(&self, data: &FieldDeriveData) -> TokenStream {
@@ -247,6 +589,21 @@ impl DeriveData {
                     Ok(juniper_eager_loading::LoadResult::Ids(ids))
                 }
             }
+            AssociationType::HasMany if data.ids_field.is_some() => {
+                // Array foreign key: the parent row carries a column of child ids, so the
+                // association loads through the id pathway like `has_one` does — the ids are
+                // collected off the parents and the children fetched by id in one query.
+                let ids_field = &data.ids_field;
+
+                quote! {
+                    let ids = models
+                        .iter()
+                        .map(|model| model.#ids_field.clone())
+                        .collect::<Vec<_>>();
+                    let ids = juniper_eager_loading::unique(ids);
+                    Ok(juniper_eager_loading::LoadResult::Ids(ids))
+                }
+            }
             AssociationType::HasMany => {
                 let filter = if let Some(predicate_method) = &data.predicate_method {
                     quote! {
@@ -259,12 +616,14 @@ impl DeriveData {
                     quote! {}
                 };
 
+                let load_child_models = self.load_models_call(
+                    quote! { <#inner_type as juniper_eager_loading::GraphqlNodeForModel>::Model },
+                    quote! { Self::Model },
+                    quote! { &models },
+                );
+
                 quote! {
-                    let child_models = <
-                        <#inner_type as juniper_eager_loading::GraphqlNodeForModel>::Model
-                        as
-                        juniper_eager_loading::LoadFrom<Self::Model>
-                    >::load(&models, db)?;
+                    let child_models = #load_child_models;
 
                     #filter
 
@@ -288,20 +647,23 @@ impl DeriveData {
                     quote! {}
                 };
 
+                let load_join_models = self.load_models_call(
+                    quote! { #join_model },
+                    quote! { Self::Model },
+                    quote! { &models },
+                );
+                let load_child_models = self.load_models_call(
+                    quote! { <#inner_type as juniper_eager_loading::GraphqlNodeForModel>::Model },
+                    quote! { #join_model },
+                    quote! { &join_models },
+                );
+
                 quote! {
-                    let join_models = <
-                        #join_model
-                        as
-                        juniper_eager_loading::LoadFrom<Self::Model>
-                    >::load(&models, db)?;
+                    let join_models = #load_join_models;
 
                     #filter
 
-                    let child_models = <
-                        <#inner_type as juniper_eager_loading::GraphqlNodeForModel>::Model
-                        as
-                        juniper_eager_loading::LoadFrom<#join_model>
-                    >::load(&join_models, db)?;
+                    let child_models = #load_child_models;
 
                     let mut child_and_join_model_pairs = Vec::new();
                     for join_model in join_models {
@@ -321,9 +683,11 @@ impl DeriveData {
             }
         };
 
+        let asyncness = self.asyncness();
+
         quote! {
             #[allow(unused_variables)]
-            fn child_ids(
+            #asyncness fn child_ids(
                 models: &[Self::Model],
                 db: &Self::Connection,
             ) -> Result<
@@ -335,25 +699,88 @@ impl DeriveData {
         }
     }
 
+    /// The expression loading models in derived code: `LoadFrom` for the sync impls,
+    /// `load_from_pool` when the derive is in async mode.
+    fn load_models_call(
+        &self,
+        model: TokenStream,
+        load_from: TokenStream,
+        args: TokenStream,
+    ) -> TokenStream {
+        if self.args.is_async() {
+            quote! {
+                juniper_eager_loading::load_from_pool::<#model, _>(#args, db).await?
+            }
+        } else {
+            quote! {
+                <#model as juniper_eager_loading::LoadFrom<#load_from>>::load(#args, db)?
+            }
+        }
+    }
+
+    fn asyncness(&self) -> TokenStream {
+        if self.args.is_async() {
+            quote! { async }
+        } else {
+            quote! {}
+        }
+    }
+
+    fn children_of_type_trait(&self) -> TokenStream {
+        if self.args.is_async() {
+            quote! { juniper_eager_loading::AsyncEagerLoadChildrenOfType }
+        } else {
+            quote! { EagerLoadChildrenOfType }
+        }
+    }
+
     fn load_children_impl(&self, data: &FieldDeriveData) -> TokenStream {
         let normalize_ids = self.normalize_ids(data);
+        // Once null foreign keys are filtered out (and ids flattened) nothing may be left —
+        // a table where most rows have a NULL FK shouldn't cost a query at all.
+        let skip_empty = match data.association_type {
+            AssociationType::HasOne => quote! {},
+            AssociationType::OptionHasOne
+            | AssociationType::HasMany
+            | AssociationType::HasManyThrough => quote! {
+                if ids.is_empty() {
+                    return Ok(Vec::new());
+                }
+            },
+        };
         let inner_type = &data.inner_type;
         let child_id_type = quote! {
             <#inner_type as juniper_eager_loading::GraphqlNodeForModel>::Id
         };
 
-        quote! {
-            fn load_children(
-                ids: &[Self::ChildId],
-                db: &Self::Connection,
-            ) -> Result<Vec<<#inner_type as juniper_eager_loading::GraphqlNodeForModel>::Model>, Self::Error> {
-                #normalize_ids
+        let asyncness = self.asyncness();
+        let load = if self.args.is_async() {
+            quote! {
+                juniper_eager_loading::load_from_pool::<
+                    <#inner_type as juniper_eager_loading::GraphqlNodeForModel>::Model,
+                    _,
+                >(&ids, db)
+                .await
+            }
+        } else {
+            quote! {
                 <
                     <#inner_type as juniper_eager_loading::GraphqlNodeForModel>::Model
                     as
                     juniper_eager_loading::LoadFrom<#child_id_type>
                 >::load(&ids, db)
             }
+        };
+
+        quote! {
+            #asyncness fn load_children(
+                ids: &[Self::ChildId],
+                db: &Self::Connection,
+            ) -> Result<Vec<<#inner_type as juniper_eager_loading::GraphqlNodeForModel>::Model>, Self::Error> {
+                #normalize_ids
+                #skip_empty
+                #load
+            }
         }
     }
 
@@ -402,7 +829,13 @@ impl DeriveData {
                 }
             }
             AssociationType::HasMany => {
-                if data.foreign_key_optional {
+                if let Some(ids_field) = &data.ids_field {
+                    quote! {
+                        node.#root_model_field
+                            .#ids_field
+                            .contains(&(child.0).#field_root_model_field.id)
+                    }
+                } else if data.foreign_key_optional {
                     quote! {
                         Some(node.#root_model_field.id) ==
                             (child.0).#field_root_model_field.#foreign_key_field
@@ -432,6 +865,93 @@ impl DeriveData {
         }
     }
 
+    fn match_hash_impl(&self, data: &FieldDeriveData) -> TokenStream {
+        let root_model_field = &data.root_model_field;
+        let foreign_key_field = &data.foreign_key_field;
+        let field_root_model_field = &data.field_root_model_field;
+        let inner_type = &data.inner_type;
+        let join_model = &data.join_model;
+
+        // An array foreign key gives each parent several match keys, which the one-hash-per-
+        // parent hooks can't express. Leave the defaults in place so matching falls back to
+        // the pairwise scan for that association.
+        if data.ids_field.is_some() {
+            return quote! {};
+        }
+
+        // Both sides hash the same key `is_child_of` compares, so the hash buckets are an
+        // exact prefilter for it. Optional foreign keys hash the inner id; `None` means "no
+        // key" and matches nothing, just like the equality in `is_child_of`.
+        let (parent_hash, child_hash) = match data.association_type {
+            AssociationType::HasOne => (
+                quote! {
+                    Some(juniper_eager_loading::match_hash(
+                        &node.#root_model_field.#foreign_key_field,
+                    ))
+                },
+                quote! {
+                    Some(juniper_eager_loading::match_hash(
+                        &(child.0).#field_root_model_field.id,
+                    ))
+                },
+            ),
+            AssociationType::OptionHasOne => (
+                quote! {
+                    node.#root_model_field
+                        .#foreign_key_field
+                        .as_ref()
+                        .map(juniper_eager_loading::match_hash)
+                },
+                quote! {
+                    Some(juniper_eager_loading::match_hash(
+                        &(child.0).#field_root_model_field.id,
+                    ))
+                },
+            ),
+            AssociationType::HasMany => {
+                let child_hash = if data.foreign_key_optional {
+                    quote! {
+                        (child.0)
+                            .#field_root_model_field
+                            .#foreign_key_field
+                            .as_ref()
+                            .map(juniper_eager_loading::match_hash)
+                    }
+                } else {
+                    quote! {
+                        Some(juniper_eager_loading::match_hash(
+                            &(child.0).#field_root_model_field.#foreign_key_field,
+                        ))
+                    }
+                };
+                (
+                    quote! {
+                        Some(juniper_eager_loading::match_hash(&node.#root_model_field.id))
+                    },
+                    child_hash,
+                )
+            }
+            AssociationType::HasManyThrough => (
+                quote! {
+                    Some(juniper_eager_loading::match_hash(&node.#root_model_field.id))
+                },
+                quote! {
+                    Some(juniper_eager_loading::match_hash(&child.1.#foreign_key_field))
+                },
+            ),
+        };
+
+        quote! {
+            fn parent_match_hash(node: &Self) -> Option<u64> {
+                #parent_hash
+            }
+
+            fn child_match_hash(child: &(#inner_type, &#join_model)) -> Option<u64> {
+                #child_hash
+            }
+        }
+    }
+
     fn child_id(&self, data: &FieldDeriveData) -> TokenStream {
         let inner_type = &data.inner_type;
         let child_id_type = quote! {
@@ -458,9 +978,21 @@ impl DeriveData {
         let field_name = &data.field_name;
         let inner_type = &data.inner_type;
 
-        quote! {
-            fn loaded_child(node: &mut Self, child: #inner_type) {
-                node.#field_name.loaded(child)
+        if data.shared {
+            quote! {
+                fn loaded_child(node: &mut Self, child: #inner_type) {
+                    node.#field_name.loaded(std::sync::Arc::new(child))
+                }
+
+                fn loaded_shared_child(node: &mut Self, child: std::sync::Arc<#inner_type>) {
+                    node.#field_name.loaded(child)
+                }
+            }
+        } else {
+            quote! {
+                fn loaded_child(node: &mut Self, child: #inner_type) {
+                    node.#field_name.loaded(child)
+                }
             }
         }
     }
@@ -468,9 +1000,187 @@ impl DeriveData {
     fn assert_loaded_otherwise_failed_impl(&self, data: &FieldDeriveData) -> TokenStream {
         let field_name = &data.field_name;
 
+        match data.association_type {
+            AssociationType::HasOne => {
+                let root_model_field = &data.root_model_field;
+                let foreign_key_field = &data.foreign_key_field;
+                let inner_type = &data.inner_type;
+
+                quote! {
+                    fn assert_loaded_otherwise_failed(node: &mut Self) {
+                        let Self { #field_name, #root_model_field, .. } = node;
+                        #field_name.assert_loaded_otherwise_failed_with(|| {
+                            juniper_eager_loading::LoadFailedDetails::new(
+                                stringify!(#inner_type),
+                                &#root_model_field.id,
+                                &#root_model_field.#foreign_key_field,
+                            )
+                        });
+                    }
+                }
+            }
+            // `on_missing = "null"`: rows behind the foreign key legitimately disappear, so a
+            // child that wasn't found resolves to `None` like a null foreign key does.
+            AssociationType::OptionHasOne if data.on_missing_null => {
+                quote! {
+                    fn assert_loaded_otherwise_failed(node: &mut Self) {
+                        node.#field_name.loaded_none();
+                    }
+                }
+            }
+            AssociationType::OptionHasOne => {
+                let root_model_field = &data.root_model_field;
+                let foreign_key_field = &data.foreign_key_field;
+                let inner_type = &data.inner_type;
+
+                // A null foreign key is a legitimate `None`; a foreign key pointing at a row
+                // that wasn't found is a failed load and shouldn't be masked as null. The
+                // details get the unwrapped key so the error message names the id itself, not
+                // the `Option` around it.
+                quote! {
+                    fn assert_loaded_otherwise_failed(node: &mut Self) {
+                        let Self { #field_name, #root_model_field, .. } = node;
+                        if let Some(child_id) = &#root_model_field.#foreign_key_field {
+                            #field_name.assert_loaded_otherwise_failed_with(|| {
+                                juniper_eager_loading::LoadFailedDetails::new(
+                                    stringify!(#inner_type),
+                                    &#root_model_field.id,
+                                    child_id,
+                                )
+                            });
+                        } else {
+                            #field_name.loaded_none();
+                        }
+                    }
+                }
+            }
+            AssociationType::HasMany if data.ids_field.is_some() => {
+                let root_model_field = &data.root_model_field;
+                let field_root_model_field = &data.field_root_model_field;
+                let ids_field = &data.ids_field;
+
+                // Children came back in loader order; the parent's id array is the order the
+                // association means, so restore it per parent.
+                quote! {
+                    fn assert_loaded_otherwise_failed(node: &mut Self) {
+                        let Self { #field_name, #root_model_field, .. } = node;
+                        #field_name.assert_loaded_otherwise_failed();
+                        let ids = &#root_model_field.#ids_field;
+                        #field_name.sort_by_key(|child| {
+                            ids.iter().position(|id| id == &child.#field_root_model_field.id)
+                        });
+                    }
+                }
+            }
+            AssociationType::HasMany | AssociationType::HasManyThrough => {
+                quote! {
+                    fn assert_loaded_otherwise_failed(node: &mut Self) {
+                        node.#field_name.assert_loaded_otherwise_failed();
+                    }
+                }
+            }
+        }
+    }
+
+    fn on_missing_children_impl(&self, data: &FieldDeriveData) -> TokenStream {
+        if !data.on_missing_error {
+            // The permissive default from the trait keeps today's behavior for each
+            // association kind.
+            return quote! {};
+        }
+
+        let foreign_key_field = &data.foreign_key_field;
+        let inner_type = &data.inner_type;
+
+        let collect_ids = match data.association_type {
+            AssociationType::HasOne => {
+                quote! {
+                    let ids = unmatched_models
+                        .iter()
+                        .map(|model| format!("{:?}", model.#foreign_key_field))
+                        .collect::<Vec<_>>();
+                }
+            }
+            AssociationType::OptionHasOne => {
+                // A `None` foreign key isn't dangling, it just means there is no child.
+                quote! {
+                    let ids = unmatched_models
+                        .iter()
+                        .filter_map(|model| model.#foreign_key_field.as_ref())
+                        .map(|id| format!("{:?}", id))
+                        .collect::<Vec<_>>();
+                }
+            }
+            AssociationType::HasMany | AssociationType::HasManyThrough => {
+                // No child pointed back at these parents, so the ids that went unanswered
+                // are the parents' own.
+                quote! {
+                    let ids = unmatched_models
+                        .iter()
+                        .map(|model| format!("{:?}", model.id))
+                        .collect::<Vec<_>>();
+                }
+            }
+        };
+
+        quote! {
+            fn on_missing_children(
+                unmatched_models: &[&Self::Model],
+            ) -> Result<(), Self::Error> {
+                #collect_ids
+
+                if ids.is_empty() {
+                    return Ok(());
+                }
+
+                Err(juniper_eager_loading::Error::MissingChildren(
+                    juniper_eager_loading::MissingChildren::new(stringify!(#inner_type), ids),
+                )
+                .into())
+            }
+        }
+    }
+
+    fn order_children_impl(&self, data: &FieldDeriveData) -> TokenStream {
+        let order_by_method = if let Some(method) = &data.order_by_method {
+            method
+        } else {
+            // The permissive default from the trait keeps whatever order the loader returned.
+            return quote! {};
+        };
+
+        let inner_type = &data.inner_type;
+        let join_model = self.join_model_impl(data);
+
         quote! {
-            fn assert_loaded_otherwise_failed(node: &mut Self) {
-                node.#field_name.assert_loaded_otherwise_failed();
+            fn order_children(
+                child_models: &mut Vec<(
+                    <#inner_type as juniper_eager_loading::GraphqlNodeForModel>::Model,
+                    #join_model,
+                )>,
+            ) {
+                child_models.sort_by(|a, b| {
+                    a.0.#order_by_method().cmp(&b.0.#order_by_method())
+                });
+            }
+        }
+    }
+
+    fn pagination_impl(&self, data: &FieldDeriveData) -> TokenStream {
+        let paginate_with = if let Some(path) = &data.paginate_with {
+            path
+        } else {
+            // The permissive default from the trait attaches every matching child.
+            return quote! {};
+        };
+
+        let inner_type = &data.inner_type;
+
+        quote! {
+            fn pagination(
+                trail: &QueryTrail<'a, #inner_type, juniper_from_schema::Walked>,
+            ) -> Option<juniper_eager_loading::Pagination> {
+                #paginate_with(trail)
             }
         }
     }
@@ -478,66 +1188,370 @@ impl DeriveData {
     fn gen_eager_load_all_children(&mut self) {
         let struct_name = self.struct_name();
 
-        let eager_load_children_calls = self
+        let fields = self.struct_fields().collect::<Vec<_>>();
+
+        // Indices of the `has_one`/`option_has_one` fields, grouped by child type in field
+        // order. A group of two or more gets one combined block that unions the foreign keys
+        // and loads the children in a single batch; every other field keeps the per-field
+        // path.
+        let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+        for (idx, field) in fields.iter().enumerate() {
+            if let Some(key) = self.shared_load_group_key(field) {
+                if let Some((_, idxs)) = groups.iter_mut().find(|(k, _)| *k == key) {
+                    idxs.push(idx);
+                } else {
+                    groups.push((key, vec![idx]));
+                }
+            }
+        }
+
+        let mut group_at_first_member: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut grouped_fields: HashSet<usize> = HashSet::new();
+        for (_, idxs) in groups {
+            if idxs.len() >= 2 {
+                grouped_fields.extend(idxs.iter().copied());
+                group_at_first_member.insert(idxs[0], idxs);
+            }
+        }
+
+        let mut eager_load_children_calls = Vec::new();
+        for (idx, field) in fields.iter().enumerate() {
+            if let Some(members) = group_at_first_member.get(&idx) {
+                let members = members.iter().map(|&i| fields[i]).collect::<Vec<_>>();
+                eager_load_children_calls.push(self.gen_shared_load_group(&members));
+            } else if !grouped_fields.contains(&idx) {
+                if let Some(tokens) = self.gen_eager_load_all_children_for_field(field) {
+                    eager_load_children_calls.push(tokens);
+                }
+            }
+        }
+
+        let nested_selection_checks = self
             .struct_fields()
-            .filter_map(|field| self.gen_eager_load_all_children_for_field(field));
+            .filter_map(|field| {
+                let field_name = self.graphql_field_name(field)?;
+                Some(quote! { trail.#field_name().walk().is_some() })
+            })
+            .collect::<Vec<_>>();
+
+        let all_children_trait = if self.args.is_async() {
+            quote! { juniper_eager_loading::AsyncEagerLoadAllChildren }
+        } else {
+            quote! { juniper_eager_loading::EagerLoadAllChildren }
+        };
+        let asyncness = self.asyncness();
+        let awaitness = if self.args.is_async() {
+            quote! { .await }
+        } else {
+            quote! {}
+        };
+
+        let impl_generics = self.impl_generics_with_trail_lifetime();
+        let ty_generics = self.ty_generics();
+        let where_clause = self.where_clause();
 
         self.tokens.extend(quote! {
-            impl<'a> juniper_eager_loading::EagerLoadAllChildren<
+            impl #impl_generics #all_children_trait<
                 QueryTrail<'a, Self, juniper_from_schema::Walked>
-            > for #struct_name {
-                fn eager_load_all_children_for_each(
+            > for #struct_name #ty_generics #where_clause {
+                #asyncness fn eager_load_all_children_for_each(
                     nodes: &mut [Self],
                     models: &[Self::Model],
                     db: &Self::Connection,
                     trail: &QueryTrail<'a, Self, juniper_from_schema::Walked>,
+                ) -> Result<(), Self::Error> {
+                    Self::eager_load_all_children_for_each_with_stats(
+                        nodes,
+                        models,
+                        db,
+                        trail,
+                        &mut juniper_eager_loading::LoadStats::default(),
+                    )#awaitness
+                }
+
+                #asyncness fn eager_load_all_children_for_each_with_stats(
+                    nodes: &mut [Self],
+                    models: &[Self::Model],
+                    db: &Self::Connection,
+                    trail: &QueryTrail<'a, Self, juniper_from_schema::Walked>,
+                    stats: &mut juniper_eager_loading::LoadStats,
                 ) -> Result<(), Self::Error> {
                     #(#eager_load_children_calls)*
 
                     Ok(())
                 }
+
+                fn has_nested_selections(
+                    trail: &QueryTrail<'a, Self, juniper_from_schema::Walked>,
+                ) -> bool {
+                    #(#nested_selection_checks ||)* false
+                }
             }
         });
     }
 
     fn gen_eager_load_all_children_for_field(&self, field: &syn::Field) -> Option<TokenStream> {
-        let inner_type = get_type_from_association(&field.ty)?;
-
-        let (args, _data) = self.parse_field_args(field)?;
-
-        let field_name = args
-            .graphql_field()
-            .clone()
-            .map(|ident| {
-                let ident = ident.to_string().to_snake_case();
-                Ident::new(&ident, Span::call_site())
-            })
-            .unwrap_or_else(|| {
-                field.ident.clone().unwrap_or_else(|| {
-                    panic!("Found `juniper_eager_loading::HasOne` field without a name")
-                })
-            });
+        let (_args, data) = self.parse_field_args(field)?;
+        let inner_type = &data.inner_type;
+        let field_name = self.graphql_field_name(field)?;
+        let context = self.field_context_name(field);
+        let mark_not_requested = self.mark_not_requested_tokens(field);
 
-        let context = self.field_context_name(&field);
+        let children_of_type_trait = self.children_of_type_trait();
+        let awaitness = if self.args.is_async() {
+            quote! { .await }
+        } else {
+            quote! {}
+        };
 
         Some(quote! {
-            if let Some(trail) = trail.#field_name().walk() {
-                EagerLoadChildrenOfType::<#inner_type, _, #context, _>::eager_load_children(
+            let walked = trail.#field_name().walk();
+            let selected = <Self as #children_of_type_trait<
+                #inner_type,
+                _,
+                #context,
+                _,
+            >>::association_selected(walked.as_ref());
+            // Descending requires both the hook's blessing and the walked trail, so an
+            // unselected association is never loaded no matter what the hook returns.
+            if let (true, Some(trail)) = (selected, walked) {
+                #children_of_type_trait::<#inner_type, _, #context, _>::eager_load_children_with_stats(
                     nodes,
                     models,
                     db,
                     &trail,
-                )?;
+                    stats,
+                )#awaitness?;
             }
+            #mark_not_requested
         })
     }
 
+    /// The group key for associations that can share one load: `has_one` and `option_has_one`
+    /// edges load by foreign key, so edges to the same child type can union their keys.
+    /// `skip`ped fields have hand-written implementations the derive can't reason about, and
+    /// the list associations load through the child table's foreign key (or the join table),
+    /// where there's nothing to union.
+    fn shared_load_group_key(&self, field: &syn::Field) -> Option<String> {
+        let (args, data) = self.parse_field_args(field)?;
+
+        if args.skip {
+            return None;
+        }
+
+        match data.association_type {
+            AssociationType::HasOne | AssociationType::OptionHasOne => {
+                let inner_type = &data.inner_type;
+                Some(quote! { #inner_type }.to_string())
+            }
+            AssociationType::HasMany | AssociationType::HasManyThrough => None,
+        }
+    }
+
+    /// `HasOne` edges record when they were skipped on purpose, so touching them later gives
+    /// a "field wasn't selected" error rather than a generic "not loaded" one.
+    fn mark_not_requested_tokens(&self, field: &syn::Field) -> TokenStream {
+        match association_type(&field.ty) {
+            Some(AssociationType::HasOne) => {
+                let field_ident = &field.ident;
+                quote! {
+                    else {
+                        for node in nodes.iter_mut() {
+                            node.#field_ident.not_requested();
+                        }
+                    }
+                }
+            }
+            _ => quote! {},
+        }
+    }
+
+    /// One block handling several associations that target the same child type. When the
+    /// query selects more than one of them, their foreign keys are unioned and the children
+    /// loaded in a single batch, with each field then matching against the shared rows —
+    /// unioning over-fetches for each individual field, and the matching drops the surplus
+    /// exactly like it drops over-fetched loader results. With zero or one field selected
+    /// this falls back to the per-field path.
+    fn gen_shared_load_group(&self, fields: &[&syn::Field]) -> TokenStream {
+        let children_of_type_trait = self.children_of_type_trait();
+        let awaitness = if self.args.is_async() {
+            quote! { .await }
+        } else {
+            quote! {}
+        };
+
+        let (_, first_data) = self
+            .parse_field_args(fields[0])
+            .expect("grouped field without association args");
+        let inner_type = first_data.inner_type;
+
+        let mut walk_stmts = Vec::new();
+        let mut selected_idents = Vec::new();
+        let mut collect_stmts = Vec::new();
+        let mut load_calls = Vec::new();
+        let mut fallbacks = Vec::new();
+
+        for field in fields {
+            let (_, data) = self
+                .parse_field_args(field)
+                .expect("grouped field without association args");
+            let field_ident = field.ident.as_ref().expect("field without name");
+            let walked = Ident::new(&format!("walked_{}", field_ident), Span::call_site());
+            let selected = Ident::new(&format!("selected_{}", field_ident), Span::call_site());
+            let field_name = self
+                .graphql_field_name(field)
+                .expect("grouped field without a GraphQL field name");
+            let context = self.field_context_name(field);
+            let foreign_key_field = &data.foreign_key_field;
+            let mark_not_requested = self.mark_not_requested_tokens(field);
+
+            walk_stmts.push(quote! {
+                let #walked = trail.#field_name().walk();
+                let #selected = <Self as #children_of_type_trait<
+                    #inner_type,
+                    _,
+                    #context,
+                    _,
+                >>::association_selected(#walked.as_ref()) && #walked.is_some();
+            });
+
+            let push_ids = match data.association_type {
+                AssociationType::HasOne => quote! {
+                    shared_ids.push(std::clone::Clone::clone(&model.#foreign_key_field));
+                },
+                AssociationType::OptionHasOne => quote! {
+                    if let Some(id) = model.#foreign_key_field.as_ref() {
+                        shared_ids.push(std::clone::Clone::clone(id));
+                    }
+                },
+                AssociationType::HasMany | AssociationType::HasManyThrough => {
+                    panic!("only id-loading associations can share a load")
+                }
+            };
+            collect_stmts.push(quote! {
+                if #selected {
+                    for model in models {
+                        #push_ids
+                    }
+                }
+            });
+
+            load_calls.push(quote! {
+                if let (true, Some(trail)) = (#selected, #walked) {
+                    let association_stats = shared_batch_stats.take().unwrap_or_else(|| {
+                        juniper_eager_loading::AssociationLoadStats {
+                            child_type: std::any::type_name::<#inner_type>(),
+                            ..std::default::Default::default()
+                        }
+                    });
+                    #children_of_type_trait::<#inner_type, _, #context, _>::eager_load_children_from_models(
+                        nodes,
+                        models,
+                        db,
+                        &trail,
+                        stats,
+                        association_stats,
+                        shared_models
+                            .iter()
+                            .cloned()
+                            .map(|model| (model, ()))
+                            .collect(),
+                    )#awaitness?;
+                }
+                #mark_not_requested
+            });
+
+            fallbacks.push(
+                self.gen_eager_load_all_children_for_field(field)
+                    .expect("grouped field without a per-field path"),
+            );
+
+            selected_idents.push(selected);
+        }
+
+        let load = self.load_models_call(
+            quote! { <#inner_type as juniper_eager_loading::GraphqlNodeForModel>::Model },
+            quote! { <#inner_type as juniper_eager_loading::GraphqlNodeForModel>::Id },
+            quote! { &shared_ids },
+        );
+
+        quote! {
+            {
+                #(#walk_stmts)*
+
+                let shared_selected_count =
+                    [#(#selected_idents),*].iter().filter(|selected| **selected).count();
+
+                if shared_selected_count >= 2 {
+                    let mut shared_ids = Vec::new();
+                    #(#collect_stmts)*
+                    let shared_ids = juniper_eager_loading::unique(shared_ids);
+                    let shared_models = if shared_ids.is_empty() {
+                        Vec::new()
+                    } else {
+                        #load
+                    };
+                    // The one batch is attributed to the first selected field's stats
+                    // entry; the other fields record their pass without a batch.
+                    let mut shared_batch_stats = Some(juniper_eager_loading::AssociationLoadStats {
+                        child_type: std::any::type_name::<#inner_type>(),
+                        batches_issued: if shared_ids.is_empty() { 0 } else { 1 },
+                        ids_requested: shared_ids.len(),
+                        rows_loaded: 0,
+                    });
+                    #(#load_calls)*
+                } else {
+                    #(#fallbacks)*
+                }
+            }
+        }
+    }
+
+    /// The name of the field on the `QueryTrail`, i.e. the GraphQL field name for the
+    /// association. `None` for fields that aren't associations.
+    fn graphql_field_name(&self, field: &syn::Field) -> Option<Ident> {
+        let (args, _data) = self.parse_field_args(field)?;
+
+        Some(
+            args.graphql_field()
+                .clone()
+                .map(|ident| {
+                    let ident = ident.to_string().to_snake_case();
+                    Ident::new(&ident, Span::call_site())
+                })
+                .unwrap_or_else(|| {
+                    field.ident.clone().unwrap_or_else(|| {
+                        panic!("Found `juniper_eager_loading::HasOne` field without a name")
+                    })
+                }),
+        )
+    }
+
     fn struct_name(&self) -> &syn::Ident {
         &self.input.ident
     }
 
+    /// The struct's generics merged with the `'a` lifetime the generated impls use for the
+    /// query trail, ready for the `impl<...>` position (bounds kept, defaults stripped).
+    fn impl_generics_with_trail_lifetime(&self) -> TokenStream {
+        let mut generics = self.input.generics.clone();
+        generics.params.insert(0, syn::parse_quote!('a));
+        let (impl_generics, _, _) = generics.split_for_impl();
+        quote! { #impl_generics }
+    }
+
+    fn ty_generics(&self) -> TokenStream {
+        let (_, ty_generics, _) = self.input.generics.split_for_impl();
+        quote! { #ty_generics }
+    }
+
+    fn where_clause(&self) -> TokenStream {
+        let (_, _, where_clause) = self.input.generics.split_for_impl();
+        quote! { #where_clause }
+    }
+
     fn model(&self) -> TokenStream {
-        self.args.model(&self.struct_name())
+        self.args.model(self.struct_name())
     }
 
     fn id(&self) -> TokenStream {
@@ -553,10 +1567,10 @@ impl DeriveData {
     }
 
     fn root_model_field(&self) -> TokenStream {
-        self.args.root_model_field(&self.struct_name())
+        self.args.root_model_field(self.struct_name())
     }
 
-    fn struct_fields(&self) -> syn::punctuated::Iter<syn::Field> {
+    fn struct_fields(&self) -> syn::punctuated::Iter<'_, syn::Field> {
         use syn::{Data, Fields};
 
         match &self.input.data {
@@ -594,18 +1608,55 @@ macro_rules! if_let_or_none {
     };
 }
 
+/// Run the generated code through `rustfmt` so `#[eager_loading(print)]` and `emit_to` produce
+/// something readable. Falls back to the unformatted token stream when `rustfmt` isn't
+/// installed or rejects the input.
+fn pretty_print(tokens: &TokenStream) -> String {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let source = tokens.to_string();
+
+    let child = Command::new("rustfmt")
+        .arg("--edition=2018")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(_) => return source,
+    };
+
+    if let Some(stdin) = child.stdin.as_mut() {
+        if stdin.write_all(source.as_bytes()).is_err() {
+            return source;
+        }
+    }
+
+    match child.wait_with_output() {
+        Ok(output) if output.status.success() => String::from_utf8(output.stdout).unwrap_or(source),
+        _ => source,
+    }
+}
+
 fn get_type_from_association(ty: &syn::Type) -> Option<&syn::Type> {
     if !is_association_field(ty) {
         return None;
     }
 
+    generic_type_argument(ty)
+}
+
+fn generic_type_argument(ty: &syn::Type) -> Option<&syn::Type> {
     let type_path = if_let_or_none!(Type::Path, ty);
     let path = &type_path.path;
     let segments = &path.segments;
-    let pair = if_let_or_none!(Some, segments.last());
+    let pair = segments.last()?;
     let segment = pair.value();
     let args = if_let_or_none!(PathArguments::AngleBracketed, &segment.arguments);
-    let pair = if_let_or_none!(Some, args.args.last());
+    let pair = args.args.last()?;
     let ty = if_let_or_none!(GenericArgument::Type, pair.value());
 
     Some(ty)
@@ -643,24 +1694,66 @@ fn is_association_field(ty: &syn::Type) -> bool {
     association_type(ty).is_some()
 }
 
+// For `shared` associations the field type wraps the child in an `Arc`, e.g.
+// `HasOne<Arc<Country>>`, but the eager loading impls are for the child type itself.
+fn shared_child_type(ty: &syn::Type) -> &syn::Type {
+    let is_arc = last_ident_in_type_segment(ty)
+        .map(|ident| ident == "Arc")
+        .unwrap_or(false);
+
+    if !is_arc {
+        panic!(
+            "`shared` associations must wrap the child type in an `Arc`, e.g. `HasOne<Arc<Country>>`"
+        );
+    }
+
+    generic_type_argument(ty)
+        .unwrap_or_else(|| panic!("`Arc` in a `shared` association is missing its type parameter"))
+}
+
 fn last_ident_in_type_segment(ty: &syn::Type) -> Option<&syn::Ident> {
     let type_path = if_let_or_none!(Type::Path, ty);
     let path = &type_path.path;
     let segments = &path.segments;
-    let pair = if_let_or_none!(Some, segments.last());
+    let pair = segments.last()?;
     let segment = pair.value();
     Some(&segment.ident)
 }
 
+// `#[eager_loading(skip)]` on a field marks a plain extra field — a computed value, say —
+// that the generated constructors should initialize with `Default::default()` instead of
+// from the model.
+fn field_is_skipped(field: &syn::Field) -> bool {
+    field.attrs.iter().any(|attr| {
+        if !attr.path.is_ident("eager_loading") {
+            return false;
+        }
+        match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => list.nested.iter().any(|nested| match nested {
+                NestedMeta::Meta(syn::Meta::Word(word)) => word == "skip",
+                _ => false,
+            }),
+            _ => false,
+        }
+    })
+}
+
+fn association_attribute(field: &syn::Field) -> Option<&syn::Attribute> {
+    field.attrs.iter().find(|attr| {
+        ["has_one", "option_has_one", "has_many", "has_many_through"]
+            .iter()
+            .any(|name| attr.path.is_ident(name))
+    })
+}
+
 fn parse_field_args<T: FromMeta>(field: &syn::Field) -> Result<T, darling::Error> {
-    let attrs = field
-        .attrs
-        .iter()
-        .map(|attr| {
-            let meta = attr.parse_meta().unwrap_or_else(|e| panic!("{}", e));
-            NestedMeta::from(meta)
-        })
-        .collect::<Vec<_>>();
+    let mut attrs = Vec::new();
+    for attr in &field.attrs {
+        let meta = attr
+            .parse_meta()
+            .map_err(|err| darling::Error::custom(err).with_span(attr))?;
+        attrs.push(NestedMeta::from(meta));
+    }
     FromMeta::from_list(attrs.as_slice())
 }
 
@@ -677,7 +1770,13 @@ struct FieldDeriveData {
     association_type: AssociationType,
     model_field: TokenStream,
     join_model_field: TokenStream,
+    ids_field: Option<Ident>,
     predicate_method: Option<Ident>,
+    order_by_method: Option<Ident>,
+    paginate_with: Option<syn::Path>,
+    shared: bool,
+    on_missing_error: bool,
+    on_missing_null: bool,
 }
 
 impl FieldDeriveData {
@@ -10,7 +10,7 @@ macro_rules! token_stream_getter {
             let value = &self.$name;
             quote! { #value }
         }
-    }
+    };
 }
 
 #[derive(FromDeriveInput, Debug)]
@@ -19,17 +19,41 @@ pub struct DeriveArgs {
     #[darling(default)]
     model: Option<syn::Path>,
     #[darling(default)]
-    id: Option<syn::Path>,
+    id: Option<IdType>,
     connection: syn::Path,
     error: syn::Path,
     #[darling(default)]
     root_model_field: Option<syn::Ident>,
+    #[darling(default)]
+    error_variant: Option<syn::Ident>,
+    #[darling(default, rename = "async")]
+    asynchronous: Option<()>,
+    #[darling(default)]
+    print: Option<()>,
+    #[darling(default)]
+    emit_to: Option<String>,
 }
 
 impl DeriveArgs {
     token_stream_getter!(connection);
     token_stream_getter!(error);
 
+    pub fn error_variant(&self) -> Option<syn::Ident> {
+        self.error_variant.clone()
+    }
+
+    pub fn is_async(&self) -> bool {
+        self.asynchronous.is_some()
+    }
+
+    pub fn print(&self) -> bool {
+        self.print.is_some()
+    }
+
+    pub fn emit_to(&self) -> Option<&str> {
+        self.emit_to.as_deref()
+    }
+
     pub fn model(&self, struct_name: &syn::Ident) -> TokenStream {
         if let Some(inner) = &self.model {
             quote! { #inner }
@@ -39,7 +63,7 @@ impl DeriveArgs {
     }
 
     pub fn id(&self) -> TokenStream {
-        if let Some(inner) = &self.id {
+        if let Some(IdType(inner)) = &self.id {
             quote! { #inner }
         } else {
             quote! { i32 }
@@ -57,6 +81,19 @@ impl DeriveArgs {
     }
 }
 
+/// An attribute value parsed as a full type rather than a path, so composite ids like
+/// `id = "(i32, i32)"` work where `syn::Path` wouldn't accept them.
+#[derive(Debug)]
+pub struct IdType(syn::Type);
+
+impl FromMeta for IdType {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        syn::parse_str(value)
+            .map(IdType)
+            .map_err(|_| darling::Error::unknown_value(value))
+    }
+}
+
 #[derive(FromMeta)]
 pub struct HasOne {
     pub has_one: HasOneInner,
@@ -73,6 +110,8 @@ pub struct HasOneInner {
     print: Option<()>,
     #[darling(default)]
     skip: Option<()>,
+    #[darling(default)]
+    shared: Option<()>,
     #[allow(dead_code)]
     #[darling(default)]
     default: (),
@@ -82,6 +121,8 @@ pub struct HasOneInner {
     root_model_field: Option<syn::Ident>,
     #[darling(default)]
     graphql_field: Option<syn::Ident>,
+    #[darling(default)]
+    on_missing: Option<syn::Ident>,
 }
 
 #[derive(FromMeta)]
@@ -95,6 +136,8 @@ pub struct HasManyInner {
     print: Option<()>,
     #[darling(default)]
     skip: Option<()>,
+    #[darling(default)]
+    shared: Option<()>,
 
     #[darling(default)]
     foreign_key_field: Option<syn::Ident>,
@@ -103,9 +146,17 @@ pub struct HasManyInner {
     #[darling(default)]
     root_model_field: Option<syn::Ident>,
     #[darling(default)]
+    ids_field: Option<syn::Ident>,
+    #[darling(default)]
     predicate_method: Option<syn::Ident>,
     #[darling(default)]
+    order_by_method: Option<syn::Ident>,
+    #[darling(default)]
+    paginate_with: Option<syn::Path>,
+    #[darling(default)]
     graphql_field: Option<syn::Ident>,
+    #[darling(default)]
+    on_missing: Option<syn::Ident>,
 }
 
 #[derive(FromMeta)]
@@ -119,6 +170,8 @@ pub struct HasManyThroughInner {
     print: Option<()>,
     #[darling(default)]
     skip: Option<()>,
+    #[darling(default)]
+    shared: Option<()>,
 
     #[darling(default)]
     join_model: Option<syn::Path>,
@@ -131,7 +184,13 @@ pub struct HasManyThroughInner {
     #[darling(default)]
     predicate_method: Option<syn::Ident>,
     #[darling(default)]
+    order_by_method: Option<syn::Ident>,
+    #[darling(default)]
+    paginate_with: Option<syn::Path>,
+    #[darling(default)]
     graphql_field: Option<syn::Ident>,
+    #[darling(default)]
+    on_missing: Option<syn::Ident>,
 }
 
 pub struct FieldArgs {
@@ -142,9 +201,14 @@ pub struct FieldArgs {
     pub join_model: Option<syn::Path>,
     pub skip: bool,
     pub print: bool,
+    pub shared: bool,
     root_model_field: Option<syn::Ident>,
+    ids_field: Option<syn::Ident>,
     predicate_method: Option<syn::Ident>,
+    order_by_method: Option<syn::Ident>,
+    paginate_with: Option<syn::Path>,
     graphql_field: Option<syn::Ident>,
+    on_missing: Option<syn::Ident>,
 }
 
 impl FieldArgs {
@@ -173,10 +237,31 @@ impl FieldArgs {
         &self.graphql_field
     }
 
+    pub fn ids_field(&self) -> Option<syn::Ident> {
+        self.ids_field.clone()
+    }
+
     pub fn predicate_method(&self) -> Option<syn::Ident> {
         self.predicate_method.clone()
     }
 
+    pub fn order_by_method(&self) -> Option<syn::Ident> {
+        self.order_by_method.clone()
+    }
+
+    pub fn paginate_with(&self) -> Option<syn::Path> {
+        self.paginate_with.clone()
+    }
+
+    pub fn on_missing_error(&self) -> bool {
+        // Invalid values are rejected with a spanned error when the args are built.
+        matches!(&self.on_missing, Some(policy) if policy == "error")
+    }
+
+    pub fn on_missing_null(&self) -> bool {
+        matches!(&self.on_missing, Some(policy) if policy == "null")
+    }
+
     pub fn join_model(&self) -> TokenStream {
         if let Some(inner) = &self.join_model {
             quote! { #inner }
@@ -222,61 +307,102 @@ fn type_to_string(ty: &syn::Type) -> String {
     tokenized.to_string()
 }
 
-impl From<HasOneInner> for FieldArgs {
-    fn from(inner: HasOneInner) -> Self {
-        Self {
+/// `skip` is the default behavior of the list associations; `null` is a separate policy for
+/// `option_has_one`.
+fn validated_on_missing(on_missing: Option<syn::Ident>) -> darling::Result<Option<syn::Ident>> {
+    if let Some(policy) = &on_missing {
+        if policy != "error" && policy != "null" && policy != "skip" {
+            return Err(darling::Error::custom(format!(
+                "Invalid value for `on_missing`: `{}`. Expected `error`, `null`, or `skip`",
+                policy
+            ))
+            .with_span(policy));
+        }
+    }
+
+    Ok(on_missing)
+}
+
+impl std::convert::TryFrom<HasOneInner> for FieldArgs {
+    type Error = darling::Error;
+
+    fn try_from(inner: HasOneInner) -> darling::Result<Self> {
+        Ok(Self {
             foreign_key_field: inner.foreign_key_field,
             foreign_key_optional: false,
             root_model_field: inner.root_model_field,
+            ids_field: None,
             join_model: None,
             model_field: None,
             join_model_field: None,
             skip: inner.skip.is_some(),
             print: inner.print.is_some(),
+            shared: inner.shared.is_some(),
             predicate_method: None,
+            order_by_method: None,
+            paginate_with: None,
             graphql_field: inner.graphql_field,
-        }
+            on_missing: validated_on_missing(inner.on_missing)?,
+        })
     }
 }
 
-impl From<HasManyInner> for FieldArgs {
-    fn from(inner: HasManyInner) -> Self {
+impl std::convert::TryFrom<HasManyInner> for FieldArgs {
+    type Error = darling::Error;
+
+    fn try_from(inner: HasManyInner) -> darling::Result<Self> {
         if inner.root_model_field.is_none() && inner.skip.is_none() {
-            panic!("For the attribute #[has_many(...)] you must provide either `root_model_field` or `skip`. Both were missing");
+            return Err(darling::Error::custom(
+                "For the attribute #[has_many(...)] you must provide either `root_model_field` or `skip`. Both were missing",
+            ));
         }
 
-        Self {
+        Ok(Self {
             foreign_key_field: inner.foreign_key_field,
             foreign_key_optional: inner.foreign_key_optional.is_some(),
             root_model_field: inner.root_model_field,
+            ids_field: inner.ids_field,
             join_model: None,
             model_field: None,
             join_model_field: None,
             skip: inner.skip.is_some(),
             print: inner.print.is_some(),
+            shared: inner.shared.is_some(),
             predicate_method: inner.predicate_method,
+            order_by_method: inner.order_by_method,
+            paginate_with: inner.paginate_with,
             graphql_field: inner.graphql_field,
-        }
+            on_missing: validated_on_missing(inner.on_missing)?,
+        })
     }
 }
 
-impl From<HasManyThroughInner> for FieldArgs {
-    fn from(inner: HasManyThroughInner) -> Self {
+impl std::convert::TryFrom<HasManyThroughInner> for FieldArgs {
+    type Error = darling::Error;
+
+    fn try_from(inner: HasManyThroughInner) -> darling::Result<Self> {
         if inner.join_model.is_none() && inner.skip.is_none() {
-            panic!("For the attribute #[has_many_through(...)] you must provide either `join_model` or `skip`. Both were missing");
+            return Err(darling::Error::custom(
+                "For the attribute #[has_many_through(...)] you must provide either `join_model` or `skip`. Both were missing",
+            ));
         }
 
-        Self {
+        Ok(Self {
             foreign_key_field: inner.foreign_key_field,
             foreign_key_optional: false,
             root_model_field: None,
+            ids_field: None,
             join_model: inner.join_model,
             model_field: inner.model_field,
             join_model_field: inner.join_model_field,
             skip: inner.skip.is_some(),
             print: inner.print.is_some(),
+            shared: inner.shared.is_some(),
             predicate_method: inner.predicate_method,
+            order_by_method: inner.order_by_method,
+            paginate_with: inner.paginate_with,
             graphql_field: inner.graphql_field,
-        }
+            on_missing: validated_on_missing(inner.on_missing)?,
+        })
     }
 }
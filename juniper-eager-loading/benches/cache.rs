@@ -0,0 +1,160 @@
+//! Measures repeated lookups of a cached 1k-element vec: cloning the whole vec out through the
+//! generic `get` versus borrowing it through the `get_vec` fast path.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use juniper_eager_loading::Cache;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Car {
+    pub id: i32,
+    pub user_id: i32,
+}
+
+fn cars() -> Vec<Car> {
+    (0..1_000).map(|id| Car { id, user_id: 1 }).collect()
+}
+
+fn bench_cached_vec_lookups(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cached 1k-element vec lookup");
+
+    let mut cloning = Cache::<i32>::new();
+    cloning.insert(1, cars());
+
+    group.bench_function("get (clones the vec)", |b| {
+        b.iter(|| cloning.get::<Vec<Car>>(1).unwrap())
+    });
+
+    let mut borrowing = Cache::<i32>::new();
+    borrowing.insert_vec(1, cars());
+
+    group.bench_function("get_vec (borrows the slice)", |b| {
+        b.iter(|| borrowing.get_vec::<Car>(1).unwrap().len())
+    });
+
+    group.finish();
+}
+
+fn bench_batch_probing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("probing 50k ids against a half-warm cache");
+    group.sample_size(10);
+
+    let ids = (0..50_000).collect::<Vec<i32>>();
+    let mut cache = Cache::<i32>::new();
+    for id in ids.iter().filter(|id| *id % 2 == 0) {
+        cache.insert(
+            *id,
+            Car {
+                id: *id,
+                user_id: 1,
+            },
+        );
+    }
+
+    group.bench_function("get per id", |b| {
+        b.iter(|| {
+            let mut found = Vec::new();
+            let mut missing = Vec::new();
+            for id in &ids {
+                match cache.get::<Car>(*id) {
+                    Some(car) => found.push(car),
+                    None => missing.push(*id),
+                }
+            }
+            (found, missing)
+        })
+    });
+
+    group.bench_function("get_many", |b| b.iter(|| cache.get_many::<Car>(&ids)));
+
+    group.finish();
+}
+
+fn bench_string_keys(c: &mut Criterion) {
+    let mut group = c.benchmark_group("100k string id cache");
+    group.sample_size(10);
+
+    let ids = (0..100_000)
+        .map(|id| format!("550e8400-e29b-41d4-a716-{:012}", id))
+        .collect::<Vec<_>>();
+
+    let mut plain = Cache::<String>::new();
+    for (idx, id) in ids.iter().enumerate() {
+        plain.insert(id.clone(), idx);
+    }
+
+    group.bench_function("Cache<String>", |b| {
+        b.iter(|| {
+            let mut found = 0;
+            for id in &ids {
+                if plain.get::<usize>(id.clone()).is_some() {
+                    found += 1;
+                }
+            }
+            found
+        })
+    });
+
+    group.bench_function("Cache<String> via get_by (no key clone)", |b| {
+        b.iter(|| {
+            let mut found = 0;
+            for id in &ids {
+                if plain.get_by::<usize, str>(id).is_some() {
+                    found += 1;
+                }
+            }
+            found
+        })
+    });
+
+    let mut interned = Cache::with_interner();
+    for (idx, id) in ids.iter().enumerate() {
+        interned.insert(id, idx);
+    }
+
+    group.bench_function("interned", |b| {
+        b.iter(|| {
+            let mut found = 0;
+            for id in &ids {
+                if interned.get::<usize>(id).is_some() {
+                    found += 1;
+                }
+            }
+            found
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_counter_overhead(c: &mut Criterion) {
+    let mut group = c.benchmark_group("counter overhead, 10M lookups");
+    group.sample_size(10);
+
+    let mut cache = Cache::<i32>::new();
+    for id in 0..16 {
+        cache.insert(id, Car { id, user_id: 1 });
+    }
+
+    group.bench_function("get", |b| {
+        b.iter(|| {
+            let mut found = 0_usize;
+            for i in 0..10_000_000 {
+                if cache.get::<Car>(i % 16).is_some() {
+                    found += 1;
+                }
+            }
+            found
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_cached_vec_lookups,
+    bench_batch_probing,
+    bench_string_keys,
+    bench_counter_overhead
+);
+criterion_main!(benches);
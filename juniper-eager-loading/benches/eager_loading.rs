@@ -0,0 +1,92 @@
+//! Criterion suite for the eager loading hot paths, built on the synthetic store in
+//! `benches/support`. Covers wide fan-out (every parent a distinct child), high fan-in (many
+//! parents sharing few children), deep three-level chains, and cache-warm versus cache-cold
+//! loads, parameterized over node counts.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use juniper_eager_loading::Cache;
+
+mod support;
+
+use support::{load_all_users, load_countries_through_cache, Db};
+
+fn bench_wide_fan_out(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wide fan-out has-one");
+    group.sample_size(10);
+
+    for users in &[1_000, 10_000] {
+        // Every user has its own country: as many children as parents.
+        let db = Db::synthetic(*users, *users, 10);
+
+        group.bench_with_input(BenchmarkId::from_parameter(users), users, |b, _| {
+            b.iter(|| load_all_users(&db))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_high_fan_in(c: &mut Criterion) {
+    let mut group = c.benchmark_group("high fan-in has-one");
+    group.sample_size(10);
+
+    for users in &[1_000, 10_000] {
+        // All the users share fifty countries.
+        let db = Db::synthetic(*users, 50, 10);
+
+        group.bench_with_input(BenchmarkId::from_parameter(users), users, |b, _| {
+            b.iter(|| load_all_users(&db))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_deep_chain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("three level chain");
+    group.sample_size(10);
+
+    for users in &[1_000, 10_000] {
+        let db = Db::synthetic(*users, 100, 10);
+
+        group.bench_with_input(BenchmarkId::from_parameter(users), users, |b, _| {
+            b.iter(|| load_all_users(&db))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_cache_warm_vs_cold(c: &mut Criterion) {
+    let mut group = c.benchmark_group("per-request cache");
+    group.sample_size(10);
+
+    let countries = 1_000;
+    let db = Db::synthetic(10, countries, 10);
+    let ids = (0..countries).collect::<Vec<_>>();
+
+    group.bench_function("cold", |b| {
+        b.iter(|| {
+            let mut cache = Cache::new();
+            load_countries_through_cache(&ids, &db, &mut cache)
+        })
+    });
+
+    group.bench_function("warm", |b| {
+        let mut cache = Cache::new();
+        load_countries_through_cache(&ids, &db, &mut cache);
+
+        b.iter(|| load_countries_through_cache(&ids, &db, &mut cache))
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_wide_fan_out,
+    bench_high_fan_in,
+    bench_deep_chain,
+    bench_cache_warm_vs_cold
+);
+criterion_main!(benches);
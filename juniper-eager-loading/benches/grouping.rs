@@ -0,0 +1,66 @@
+//! Measures assigning has-many children to their parents by scanning all the children once per
+//! parent versus grouping them by foreign key in one pass with
+//! [`group_children_by_key`](juniper_eager_loading::group_children_by_key).
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use juniper_eager_loading::group_children_by_key;
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+}
+
+fn bench_grouping(c: &mut Criterion) {
+    let parents = 2_000;
+    let children = 50_000;
+
+    let users = (0..parents)
+        .map(|id| models::User { id })
+        .collect::<Vec<_>>();
+    let cars = (0..children)
+        .map(|id| models::Car {
+            id,
+            user_id: id % parents,
+        })
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("has-many child assignment");
+    group.sample_size(10);
+
+    group.bench_function("scan per parent", |b| {
+        b.iter(|| {
+            users
+                .iter()
+                .map(|user| {
+                    cars.iter()
+                        .filter(|car| car.user_id == user.id)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.bench_function("group by foreign key", |b| {
+        b.iter(|| {
+            let mut groups = group_children_by_key(cars.clone(), |car| car.user_id);
+            users
+                .iter()
+                .map(|user| groups.remove(&user.id).unwrap_or_default())
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_grouping);
+criterion_main!(benches);
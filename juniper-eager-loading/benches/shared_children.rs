@@ -0,0 +1,234 @@
+//! Measures the cost of attaching children to parents with owned versus shared (`Arc`) edges.
+//!
+//! The interesting case is high fan-in: many parents pointing at few distinct, heavyweight
+//! children. With owned edges every parent clones its child; with shared edges the child is
+//! built once and reference counted.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use juniper_eager_loading::{prelude::*, unique, GenericQueryTrail, HasOne, LoadFrom, LoadResult};
+use juniper_from_schema::Walked;
+use std::sync::Arc;
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    // Deliberately heavy to clone so the per-parent clone shows up in the measurements.
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+        pub name: String,
+        pub cities: Vec<String>,
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    country: models::Country,
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+        }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+macro_rules! user_node {
+    ($name:ident, $edge:ty, $context:ident, $($loaded:item)+) => {
+        #[derive(Clone, Debug)]
+        pub struct $name {
+            user: models::User,
+            country: HasOne<$edge>,
+        }
+
+        impl GraphqlNodeForModel for $name {
+            type Model = models::User;
+            type Id = i32;
+            type Connection = Db;
+            type Error = Box<dyn std::error::Error>;
+
+            fn new_from_model(model: &Self::Model) -> Self {
+                Self {
+                    user: model.clone(),
+                    country: Default::default(),
+                }
+            }
+        }
+
+        pub struct $context;
+
+        impl EagerLoadChildrenOfType<Country, EverythingTrail, $context, ()> for $name {
+            type ChildId = i32;
+
+            fn child_ids(
+                models: &[Self::Model],
+                _db: &Self::Connection,
+            ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+                let ids = models
+                    .iter()
+                    .map(|model| model.country_id)
+                    .collect::<Vec<_>>();
+                Ok(LoadResult::Ids(unique(ids)))
+            }
+
+            fn load_children(
+                ids: &[Self::ChildId],
+                db: &Self::Connection,
+            ) -> Result<Vec<models::Country>, Self::Error> {
+                <models::Country as LoadFrom<i32>>::load(ids, db)
+            }
+
+            fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+                node.user.country_id == (child.0).country.id
+            }
+
+            $($loaded)+
+
+            fn assert_loaded_otherwise_failed(node: &mut Self) {
+                node.country.assert_loaded_otherwise_failed();
+            }
+        }
+
+        impl EagerLoadAllChildren<EverythingTrail> for $name {
+            fn eager_load_all_children_for_each(
+                nodes: &mut [Self],
+                models: &[Self::Model],
+                db: &Self::Connection,
+                trail: &EverythingTrail,
+            ) -> Result<(), Self::Error> {
+                EagerLoadChildrenOfType::<Country, _, $context, _>::eager_load_children(
+                    nodes, models, db, trail,
+                )?;
+                Ok(())
+            }
+        }
+    };
+}
+
+user_node! {
+    OwnedUser,
+    Country,
+    OwnedUserCountryContext,
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+}
+
+user_node! {
+    SharedUser,
+    Arc<Country>,
+    SharedUserCountryContext,
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(Arc::new(child))
+    }
+    fn loaded_shared_child(node: &mut Self, child: Arc<Country>) {
+        node.country.loaded(child)
+    }
+}
+
+fn db(distinct_children: i32) -> Db {
+    Db {
+        countries: (0..distinct_children)
+            .map(|id| models::Country {
+                id,
+                name: format!("country {}", id),
+                cities: (0..100).map(|city| format!("city {}", city)).collect(),
+            })
+            .collect(),
+    }
+}
+
+fn user_models(parents: i32, distinct_children: i32) -> Vec<models::User> {
+    (0..parents)
+        .map(|id| models::User {
+            id,
+            country_id: id % distinct_children,
+        })
+        .collect()
+}
+
+fn bench_attachment(c: &mut Criterion) {
+    let mut group = c.benchmark_group("high fan-in child attachment");
+    let distinct_children = 50;
+
+    for parents in &[1_000, 10_000] {
+        let db = db(distinct_children);
+        let users = user_models(*parents, distinct_children);
+
+        group.bench_with_input(BenchmarkId::new("owned", parents), parents, |b, _| {
+            b.iter(|| {
+                let mut nodes = OwnedUser::from_db_models(&users);
+                OwnedUser::eager_load_all_children_for_each(
+                    &mut nodes,
+                    &users,
+                    &db,
+                    &EverythingTrail,
+                )
+                .unwrap();
+                nodes
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("shared", parents), parents, |b, _| {
+            b.iter(|| {
+                let mut nodes = SharedUser::from_db_models(&users);
+                SharedUser::eager_load_all_children_for_each(
+                    &mut nodes,
+                    &users,
+                    &db,
+                    &EverythingTrail,
+                )
+                .unwrap();
+                nodes
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_attachment);
+criterion_main!(benches);
@@ -0,0 +1,287 @@
+//! A synthetic in-memory store built entirely on the public API, shared between the criterion
+//! benchmarks and the large-scale integration tests.
+//!
+//! The schema is a three level chain — users belong to countries, countries belong to
+//! continents — which is enough to exercise wide fan-out (every parent a distinct child), high
+//! fan-in (many parents sharing few children), and deep chains.
+
+use juniper_eager_loading::{
+    prelude::*, unique, Cache, GenericQueryTrail, HasOne, LoadFrom, LoadResult,
+};
+use juniper_from_schema::Walked;
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+        pub continent_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Continent {
+        pub id: i32,
+    }
+}
+
+pub struct Db {
+    pub users: Vec<models::User>,
+    pub countries: Vec<models::Country>,
+    pub continents: Vec<models::Continent>,
+}
+
+impl Db {
+    /// Build a store with `users` users spread over `countries` countries, which are in turn
+    /// spread over `continents` continents. Assignment is round-robin so the fan-in per child
+    /// is uniform.
+    pub fn synthetic(users: i32, countries: i32, continents: i32) -> Self {
+        Self {
+            users: (0..users)
+                .map(|id| models::User {
+                    id,
+                    country_id: id % countries,
+                })
+                .collect(),
+            countries: (0..countries)
+                .map(|id| models::Country {
+                    id,
+                    continent_id: id % continents,
+                })
+                .collect(),
+            continents: (0..continents).map(|id| models::Continent { id }).collect(),
+        }
+    }
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LoadFrom<i32> for models::Continent {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .continents
+            .iter()
+            .filter(|continent| ids.contains(&continent.id))
+            .cloned()
+            .collect())
+    }
+}
+
+// A stand-in for a walked `QueryTrail` that selects every association.
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    pub user: models::User,
+    pub country: HasOne<Country>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    pub country: models::Country,
+    pub continent: HasOne<Continent>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Continent {
+    pub continent: models::Continent,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+            continent: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Continent {
+    type Model = models::Continent;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            continent: model.clone(),
+        }
+    }
+}
+
+pub struct UserCountryContext;
+
+impl EagerLoadChildrenOfType<Country, EverythingTrail, UserCountryContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+        let ids = models
+            .iter()
+            .map(|model| model.country_id)
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Country>, Self::Error> {
+        <models::Country as LoadFrom<i32>>::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+        node.user.country_id == (child.0).country.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.country.assert_loaded_otherwise_failed();
+    }
+}
+
+pub struct CountryContinentContext;
+
+impl EagerLoadChildrenOfType<Continent, EverythingTrail, CountryContinentContext, ()> for Country {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Continent, ())>, Self::Error> {
+        let ids = models
+            .iter()
+            .map(|model| model.continent_id)
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Continent>, Self::Error> {
+        <models::Continent as LoadFrom<i32>>::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Continent, &())) -> bool {
+        node.country.continent_id == (child.0).continent.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Continent) {
+        node.continent.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.continent.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Continent, _, CountryContinentContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Continent {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Eager load the full user → country → continent chain for every user in the store.
+pub fn load_all_users(db: &Db) -> Vec<User> {
+    let mut users = User::from_db_models(&db.users);
+    User::eager_load_all_children_for_each(&mut users, &db.users, db, &EverythingTrail)
+        .expect("eager loading failed");
+    users
+}
+
+/// Load countries through a per-request [`Cache`], the way a caching custom impl would: cached
+/// ids are served from the cache, the rest go through `LoadFrom` and are cached for next time.
+pub fn load_countries_through_cache(
+    ids: &[i32],
+    db: &Db,
+    cache: &mut Cache<i32>,
+) -> Vec<models::Country> {
+    let (mut countries, missing) = cache.get_many::<models::Country>(ids);
+
+    let fresh = <models::Country as LoadFrom<i32>>::load(&missing, db).expect("load failed");
+    for country in fresh {
+        cache.insert(country.id, country.clone());
+        countries.push(country);
+    }
+
+    countries
+}
@@ -0,0 +1,142 @@
+//! The `Connection` associated type has no bounds, so it can be a trait object. That lets the
+//! same node graph run against a real Diesel connection in production and a boxed fake in
+//! tests — nothing about the nodes or the derive changes, only what the context puts in the
+//! box.
+
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasOne};
+use juniper_from_schema::graphql_schema;
+
+graphql_schema! {
+    schema { query: Query }
+
+    type Query { noop: Boolean! @juniper(ownership: "owned") }
+
+    type User {
+        id: Int!
+        country: Country!
+    }
+
+    type Country {
+        id: Int!
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<bool> {
+        unimplemented!()
+    }
+}
+
+// The abstraction the nodes load through. Production and tests each implement it.
+pub trait Database {
+    fn countries(&self, ids: &[i32]) -> Result<Vec<models::Country>, Box<dyn std::error::Error>>;
+}
+
+// The connection type used everywhere: in `LoadFrom`, in `#[eager_loading(connection = _)]`,
+// and in the context.
+pub type DynConnection = Box<dyn Database + Send + Sync>;
+
+// In production the box holds a real Diesel connection.
+pub struct ProductionDatabase {
+    #[allow(dead_code)]
+    connection: diesel::pg::PgConnection,
+}
+
+impl Database for ProductionDatabase {
+    fn countries(&self, _ids: &[i32]) -> Result<Vec<models::Country>, Box<dyn std::error::Error>> {
+        // `self.connection.load(...)` etc
+        unimplemented!()
+    }
+}
+
+// In tests it holds a fake backed by plain vectors.
+pub struct FakeDatabase {
+    countries: Vec<models::Country>,
+}
+
+impl Database for FakeDatabase {
+    fn countries(&self, ids: &[i32]) -> Result<Vec<models::Country>, Box<dyn std::error::Error>> {
+        Ok(self
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    #[allow(dead_code)]
+    db: DynConnection,
+}
+
+impl juniper::Context for Context {}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        unimplemented!()
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        unimplemented!()
+    }
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        unimplemented!()
+    }
+}
+
+mod models {
+    use super::DynConnection;
+
+    #[derive(Clone)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    impl juniper_eager_loading::LoadFrom<i32> for Country {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = DynConnection;
+
+        fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            db.countries(ids)
+        }
+    }
+}
+
+#[derive(Clone, EagerLoading)]
+#[eager_loading(connection = "DynConnection", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+}
+
+#[derive(Clone, EagerLoading)]
+#[eager_loading(connection = "DynConnection", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+fn main() {
+    let db: DynConnection = Box::new(FakeDatabase {
+        countries: vec![models::Country { id: 10 }],
+    });
+    let _context = Context { db };
+}
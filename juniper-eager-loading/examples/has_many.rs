@@ -1,7 +1,6 @@
 use juniper::{Executor, FieldResult};
 use juniper_eager_loading::{prelude::*, *};
 use juniper_from_schema::graphql_schema;
-use std::error::Error;
 
 graphql_schema! {
     schema { query: Query }
@@ -22,7 +21,7 @@ graphql_schema! {
 pub struct Query;
 
 impl QueryFields for Query {
-    fn field_noop(&self, executor: &Executor<'_, Context>) -> FieldResult<bool> {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<bool> {
         unimplemented!()
     }
 }
@@ -30,40 +29,42 @@ impl QueryFields for Query {
 pub struct DbConnection;
 
 impl DbConnection {
+    #[allow(dead_code)]
     fn load_all_users(&self) -> Vec<models::User> {
         unimplemented!()
     }
 }
 
 pub struct Context {
+    #[allow(dead_code)]
     db: DbConnection,
 }
 
 impl juniper::Context for Context {}
 
 impl UserFields for User {
-    fn field_id(&self, executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
         unimplemented!()
     }
 
     fn field_cars(
         &self,
-        executor: &Executor<'_, Context>,
-        trail: &QueryTrail<'_, Car, Walked>,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Car, Walked>,
     ) -> FieldResult<&Vec<Car>> {
         Ok(self.cars.try_unwrap()?)
     }
 }
 
 impl CarFields for Car {
-    fn field_id(&self, executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
         unimplemented!()
     }
 
     fn field_user(
         &self,
-        executor: &Executor<'_, Context>,
-        trail: &QueryTrail<'_, User, Walked>,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, User, Walked>,
     ) -> FieldResult<&User> {
         Ok(self.user.try_unwrap()?)
     }
@@ -84,7 +85,7 @@ mod models {
     }
 
     impl Car {
-        pub fn a_predicate_method(&self, db: &DbConnection) -> bool {
+        pub fn a_predicate_method(&self, _db: &DbConnection) -> bool {
             true
         }
     }
@@ -93,7 +94,7 @@ mod models {
         type Error = Box<dyn std::error::Error>;
         type Connection = DbConnection;
 
-        fn load(employments: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        fn load(_employments: &[i32], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
             unimplemented!()
         }
     }
@@ -102,7 +103,7 @@ mod models {
         type Error = Box<dyn std::error::Error>;
         type Connection = DbConnection;
 
-        fn load(employments: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        fn load(_employments: &[i32], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
             unimplemented!()
         }
     }
@@ -111,11 +112,10 @@ mod models {
         type Error = Box<dyn std::error::Error>;
         type Connection = DbConnection;
 
-        fn load(employments: &[User], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        fn load(_employments: &[User], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
             unimplemented!()
         }
     }
-
 }
 
 #[derive(Clone, EagerLoading)]
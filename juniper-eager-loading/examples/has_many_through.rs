@@ -1,7 +1,6 @@
 use juniper::{Executor, FieldResult};
 use juniper_eager_loading::{prelude::*, *};
 use juniper_from_schema::graphql_schema;
-use std::error::Error;
 
 graphql_schema! {
     schema { query: Query }
@@ -22,7 +21,7 @@ graphql_schema! {
 pub struct Query;
 
 impl QueryFields for Query {
-    fn field_noop(&self, executor: &Executor<'_, Context>) -> FieldResult<bool> {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<bool> {
         unimplemented!()
     }
 }
@@ -30,40 +29,42 @@ impl QueryFields for Query {
 pub struct DbConnection;
 
 impl DbConnection {
+    #[allow(dead_code)]
     fn load_all_users(&self) -> Vec<models::User> {
         unimplemented!()
     }
 }
 
 pub struct Context {
+    #[allow(dead_code)]
     db: DbConnection,
 }
 
 impl juniper::Context for Context {}
 
 impl UserFields for User {
-    fn field_id(&self, executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
         unimplemented!()
     }
 
     fn field_companies(
         &self,
-        executor: &Executor<'_, Context>,
-        trail: &QueryTrail<'_, Company, Walked>,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Company, Walked>,
     ) -> FieldResult<&Vec<Company>> {
         Ok(self.companies.try_unwrap()?)
     }
 }
 
 impl CompanyFields for Company {
-    fn field_id(&self, executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
         unimplemented!()
     }
 
     fn field_employees(
         &self,
-        executor: &Executor<'_, Context>,
-        trail: &QueryTrail<'_, User, Walked>,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, User, Walked>,
     ) -> FieldResult<&Vec<User>> {
         Ok(self.employees.try_unwrap()?)
     }
@@ -90,7 +91,7 @@ mod models {
     }
 
     impl Employment {
-        pub fn a_predicate_method(&self, db: &super::DbConnection) -> bool {
+        pub fn a_predicate_method(&self, _db: &super::DbConnection) -> bool {
             true
         }
     }
@@ -99,7 +100,7 @@ mod models {
         type Error = Box<dyn std::error::Error>;
         type Connection = DbConnection;
 
-        fn load(employments: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        fn load(_employments: &[i32], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
             unimplemented!()
         }
     }
@@ -108,7 +109,7 @@ mod models {
         type Error = Box<dyn std::error::Error>;
         type Connection = DbConnection;
 
-        fn load(employments: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        fn load(_employments: &[i32], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
             unimplemented!()
         }
     }
@@ -117,7 +118,7 @@ mod models {
         type Error = Box<dyn std::error::Error>;
         type Connection = DbConnection;
 
-        fn load(employments: &[User], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        fn load(_employments: &[User], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
             unimplemented!()
         }
     }
@@ -126,7 +127,10 @@ mod models {
         type Error = Box<dyn std::error::Error>;
         type Connection = DbConnection;
 
-        fn load(employments: &[Company], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        fn load(
+            _employments: &[Company],
+            _db: &Self::Connection,
+        ) -> Result<Vec<Self>, Self::Error> {
             unimplemented!()
         }
     }
@@ -136,8 +140,8 @@ mod models {
         type Connection = DbConnection;
 
         fn load(
-            employments: &[Employment],
-            db: &Self::Connection,
+            _employments: &[Employment],
+            _db: &Self::Connection,
         ) -> Result<Vec<Self>, Self::Error> {
             unimplemented!()
         }
@@ -148,8 +152,8 @@ mod models {
         type Connection = DbConnection;
 
         fn load(
-            employments: &[Employment],
-            db: &Self::Connection,
+            _employments: &[Employment],
+            _db: &Self::Connection,
         ) -> Result<Vec<Self>, Self::Error> {
             unimplemented!()
         }
@@ -1,7 +1,6 @@
 use juniper::{Executor, FieldResult};
 use juniper_eager_loading::{prelude::*, EagerLoading, HasOne};
 use juniper_from_schema::graphql_schema;
-use std::error::Error;
 
 graphql_schema! {
     schema { query: Query }
@@ -21,7 +20,7 @@ graphql_schema! {
 pub struct Query;
 
 impl QueryFields for Query {
-    fn field_noop(&self, executor: &Executor<'_, Context>) -> FieldResult<bool> {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<bool> {
         unimplemented!()
     }
 }
@@ -29,33 +28,35 @@ impl QueryFields for Query {
 pub struct DbConnection;
 
 impl DbConnection {
+    #[allow(dead_code)]
     fn load_all_users(&self) -> Vec<models::User> {
         unimplemented!()
     }
 }
 
 pub struct Context {
+    #[allow(dead_code)]
     db: DbConnection,
 }
 
 impl juniper::Context for Context {}
 
 impl UserFields for User {
-    fn field_id(&self, executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
         unimplemented!()
     }
 
     fn field_country(
         &self,
-        executor: &Executor<'_, Context>,
-        trail: &QueryTrail<'_, Country, Walked>,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
     ) -> FieldResult<&Country> {
         unimplemented!()
     }
 }
 
 impl CountryFields for Country {
-    fn field_id(&self, executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
         unimplemented!()
     }
 }
@@ -78,7 +79,7 @@ mod models {
         type Error = Box<dyn std::error::Error>;
         type Connection = DbConnection;
 
-        fn load(employments: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        fn load(_employments: &[i32], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
             unimplemented!()
         }
     }
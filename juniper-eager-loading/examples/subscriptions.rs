@@ -0,0 +1,121 @@
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasOne, SharedCache, SubscriptionLoader};
+use juniper_from_schema::graphql_schema;
+use std::time::Duration;
+
+graphql_schema! {
+    schema { query: Query }
+
+    type Query { noop: Boolean! @juniper(ownership: "owned") }
+
+    type User {
+        id: Int!
+        country: Country!
+    }
+
+    type Country {
+        id: Int!
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<bool> {
+        unimplemented!()
+    }
+}
+
+pub struct DbConnection;
+
+pub struct Context {
+    #[allow(dead_code)]
+    db: DbConnection,
+}
+
+impl juniper::Context for Context {}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        unimplemented!()
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        unimplemented!()
+    }
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        unimplemented!()
+    }
+}
+
+mod models {
+    use super::DbConnection;
+
+    #[derive(Clone)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    impl juniper_eager_loading::LoadFrom<i32> for Country {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = DbConnection;
+
+        fn load(_ids: &[i32], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            unimplemented!()
+        }
+    }
+}
+
+#[derive(Clone, EagerLoading)]
+#[eager_loading(connection = "DbConnection", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+}
+
+#[derive(Clone, EagerLoading)]
+#[eager_loading(connection = "DbConnection", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+// A subscription pushes batches of users to the client on every event. By holding on to one
+// `SharedCache` for the lifetime of the subscription, nodes that already went out in a previous
+// event skip eager loading entirely. The TTL makes sure a long-lived subscription doesn't serve
+// stale children forever.
+//
+// `SubscriptionLoader` is executor agnostic, so this function can be driven by whatever stream
+// or task your subscription implementation uses.
+#[allow(dead_code)]
+fn handle_events(
+    db: &DbConnection,
+    events: std::sync::mpsc::Receiver<Vec<models::User>>,
+    trail: &QueryTrail<'_, User, Walked>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cache = SharedCache::with_ttl(Duration::from_secs(30));
+    let loader = SubscriptionLoader::new(db, cache, |user: &models::User| user.id);
+
+    for batch in loader.load_batches(events, trail) {
+        let _users: Vec<User> = batch?;
+        // push the loaded users to the client here
+    }
+
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,472 @@
+//! Async counterparts of the eager loading traits, for data layers built on async drivers.
+//!
+//! The loading methods here are `async fn`s; everything else — matching children to parents,
+//! ordering, pagination windows, missing-children handling — is the same synchronous logic as
+//! the sync pipeline, with the same semantics. The [derive](derive.EagerLoading.html) emits
+//! implementations of these traits instead of the sync ones when the `async` attribute is
+//! present, loading through [`load_from_pool`](fn.load_from_pool.html): the connection type is
+//! an async [`ConnectionPool`](trait.ConnectionPool.html) and the models implement
+//! [`AsyncLoadFrom`](trait.AsyncLoadFrom.html). See the `pool` module docs for why the pool
+//! itself is the connection.
+//!
+//! Sibling associations currently load sequentially, one `await` after the other. The API is
+//! async end-to-end though, so concurrent sibling loads can be added without breaking anyone.
+
+use crate::{
+    same_type, AssociationLoadStats, GenericQueryTrail, GraphqlNodeForModel, LoadResult, LoadStats,
+    Pagination,
+};
+use juniper_from_schema::Walked;
+use std::hash::Hash;
+use std::sync::Arc;
+
+/// Async counterpart of [`EagerLoadAllChildren`](trait.EagerLoadAllChildren.html).
+///
+/// You shouldn't need to implement this trait yourself even when customizing eager loading.
+#[allow(async_fn_in_trait)]
+pub trait AsyncEagerLoadAllChildren<QueryTrailT>
+where
+    Self: GraphqlNodeForModel,
+{
+    /// For each field in your GraphQL type that implements
+    /// [`AsyncEagerLoadChildrenOfType`][] await [`eager_load_children`][] to do eager loading
+    /// of that field.
+    ///
+    /// [`AsyncEagerLoadChildrenOfType`]: trait.AsyncEagerLoadChildrenOfType.html
+    /// [`eager_load_children`]: trait.AsyncEagerLoadChildrenOfType.html#method.eager_load_children
+    async fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+    ) -> Result<(), Self::Error>;
+
+    /// Like [`eager_load_all_children_for_each`](#tymethod.eager_load_all_children_for_each),
+    /// but accumulates [`LoadStats`](struct.LoadStats.html). Same contract as the sync
+    /// [`EagerLoadAllChildren::eager_load_all_children_for_each_with_stats`](trait.EagerLoadAllChildren.html#method.eager_load_all_children_for_each_with_stats).
+    async fn eager_load_all_children_for_each_with_stats(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+        stats: &mut LoadStats,
+    ) -> Result<(), Self::Error> {
+        let _ = stats;
+        Self::eager_load_all_children_for_each(nodes, models, db, trail).await
+    }
+
+    /// Does the trail select any of this type's associations? Same contract as
+    /// [`EagerLoadAllChildren::has_nested_selections`][].
+    ///
+    /// [`EagerLoadAllChildren::has_nested_selections`]: trait.EagerLoadAllChildren.html#method.has_nested_selections
+    fn has_nested_selections(trail: &QueryTrailT) -> bool {
+        let _ = trail;
+        true
+    }
+
+    /// Build the nodes from the models and eager load all their children, in one call. Same
+    /// contract as
+    /// [`EagerLoadAllChildren::eager_load`](trait.EagerLoadAllChildren.html#method.eager_load).
+    async fn eager_load(
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+    ) -> Result<Vec<Self>, Self::Error> {
+        if models.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut nodes = Self::from_db_models(models);
+        Self::eager_load_all_children_for_each(&mut nodes, models, db, trail).await?;
+        Ok(nodes)
+    }
+
+    /// Perform eager loading for a single GraphQL value. Same contract as
+    /// [`EagerLoadAllChildren::eager_load_all_children`](trait.EagerLoadAllChildren.html#method.eager_load_all_children).
+    async fn eager_load_all_children(
+        mut node: Self,
+        model: &Self::Model,
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+    ) -> Result<Self, Self::Error> {
+        Self::eager_load_all_children_for_each(
+            std::slice::from_mut(&mut node),
+            std::slice::from_ref(model),
+            db,
+            trail,
+        )
+        .await?;
+
+        Ok(node)
+    }
+
+    /// Like [`eager_load_all_children`](#method.eager_load_all_children), but builds the node
+    /// from the model first.
+    async fn eager_load_all_children_from_model(
+        model: &Self::Model,
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+    ) -> Result<Self, Self::Error> {
+        let node = Self::new_from_model(model);
+        Self::eager_load_all_children(node, model, db, trail).await
+    }
+}
+
+/// Async counterpart of [`EagerLoadChildrenOfType`](trait.EagerLoadChildrenOfType.html).
+///
+/// [`child_ids`](#tymethod.child_ids) and [`load_children`](#tymethod.load_children) are
+/// awaited; the matching hooks are synchronous and have the same contracts as their sync
+/// namesakes. The default [`eager_load_children`](#method.eager_load_children) flow mirrors
+/// the sync one step for step — ordering, pagination windows, shared children, and
+/// missing-children handling all behave identically — so switching a data layer to async
+/// doesn't change what gets loaded or attached.
+#[allow(async_fn_in_trait)]
+pub trait AsyncEagerLoadChildrenOfType<Child, QueryTrailT, Context, JoinModel = ()>
+where
+    Self: GraphqlNodeForModel,
+    Child: GraphqlNodeForModel<Connection = Self::Connection, Error = Self::Error>
+        + AsyncEagerLoadAllChildren<QueryTrailT>
+        + Clone,
+    QueryTrailT: GenericQueryTrail<Child, Walked>,
+    JoinModel: 'static + Clone,
+{
+    /// The id type the child uses. Same contract as the sync trait's `ChildId`.
+    type ChildId: Hash + Eq;
+
+    /// Given a list of models, load either the list of child ids or child models associated.
+    #[allow(clippy::type_complexity)]
+    async fn child_ids(
+        models: &[Self::Model],
+        db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (Child::Model, JoinModel)>, Self::Error>;
+
+    /// Load a list of children from a list of ids. As in the sync flow, the ids are
+    /// deduplicated (keeping first-seen order) before this is called.
+    async fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<Child::Model>, Self::Error>;
+
+    /// Like [`child_ids`](#tymethod.child_ids), but with access to the query trail. The
+    /// default delegates; see
+    /// [`EagerLoadChildrenOfType::child_ids_with_trail`](trait.EagerLoadChildrenOfType.html#method.child_ids_with_trail).
+    #[allow(clippy::type_complexity)]
+    async fn child_ids_with_trail(
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+    ) -> Result<LoadResult<Self::ChildId, (Child::Model, JoinModel)>, Self::Error> {
+        let _ = trail;
+        Self::child_ids(models, db).await
+    }
+
+    /// Like [`load_children`](#tymethod.load_children), but with access to the query trail.
+    async fn load_children_with_trail(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+    ) -> Result<Vec<Child::Model>, Self::Error> {
+        let _ = trail;
+        Self::load_children(ids, db).await
+    }
+
+    /// Is this association selected by the query? Same contract as
+    /// [`EagerLoadChildrenOfType::association_selected`](trait.EagerLoadChildrenOfType.html#method.association_selected).
+    fn association_selected(child_trail: Option<&QueryTrailT>) -> bool {
+        child_trail.is_some()
+    }
+
+    /// Does this parent and this child belong together?
+    fn is_child_of(parent: &Self, child: &(Child, &JoinModel)) -> bool;
+
+    /// A hash of the key this parent matches children on. Same contract as
+    /// [`EagerLoadChildrenOfType::parent_match_hash`](trait.EagerLoadChildrenOfType.html#method.parent_match_hash).
+    fn parent_match_hash(parent: &Self) -> Option<u64> {
+        let _ = parent;
+        None
+    }
+
+    /// A hash of the key this child matches parents on. Same contract as
+    /// [`EagerLoadChildrenOfType::child_match_hash`](trait.EagerLoadChildrenOfType.html#method.child_match_hash).
+    fn child_match_hash(child: &(Child, &JoinModel)) -> Option<u64> {
+        let _ = child;
+        None
+    }
+
+    /// Store the loaded child on the association.
+    fn loaded_child(node: &mut Self, child: Child);
+
+    /// Store a shared, eager loaded child on the association. Same contract as
+    /// [`EagerLoadChildrenOfType::loaded_shared_child`](trait.EagerLoadChildrenOfType.html#method.loaded_shared_child).
+    fn loaded_shared_child(node: &mut Self, child: Arc<Child>) {
+        Self::loaded_child(node, (*child).clone());
+    }
+
+    /// The association should have been loaded by now, if not store an error inside the
+    /// association (if applicable for the particular association).
+    fn assert_loaded_otherwise_failed(node: &mut Self);
+
+    /// Called after matching with the models of the parents that no child matched. Same
+    /// permissive default as
+    /// [`EagerLoadChildrenOfType::on_missing_children`](trait.EagerLoadChildrenOfType.html#method.on_missing_children).
+    fn on_missing_children(unmatched_models: &[&Self::Model]) -> Result<(), Self::Error> {
+        let _ = unmatched_models;
+        Ok(())
+    }
+
+    /// Order the loaded child models before they're matched to parents. Same contract as
+    /// [`EagerLoadChildrenOfType::order_children`](trait.EagerLoadChildrenOfType.html#method.order_children).
+    fn order_children(child_models: &mut Vec<(Child::Model, JoinModel)>) {
+        let _ = child_models;
+    }
+
+    /// The per-parent window to apply when matching children to parents, if any. Same contract
+    /// as
+    /// [`EagerLoadChildrenOfType::pagination`](trait.EagerLoadChildrenOfType.html#method.pagination).
+    fn pagination(trail: &QueryTrailT) -> Option<Pagination> {
+        let _ = trail;
+        None
+    }
+
+    /// Combine all the methods above to eager load the children for a list of GraphQL values
+    /// and models.
+    ///
+    /// This is an exact mirror of the sync
+    /// [`eager_load_children`](trait.EagerLoadChildrenOfType.html#method.eager_load_children) —
+    /// same input contract, ordering guarantees, and complexity — with the loads and the
+    /// nested recursion awaited. Keep the two in sync when changing either.
+    async fn eager_load_children(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+    ) -> Result<(), Self::Error> {
+        Self::eager_load_children_with_stats(nodes, models, db, trail, &mut LoadStats::default())
+            .await
+    }
+
+    /// Like [`eager_load_children`](#method.eager_load_children), but records what the pass
+    /// cost into the given [`LoadStats`](struct.LoadStats.html). Same accounting as the sync
+    /// [`eager_load_children_with_stats`](trait.EagerLoadChildrenOfType.html#method.eager_load_children_with_stats).
+    async fn eager_load_children_with_stats(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+        stats: &mut LoadStats,
+    ) -> Result<(), Self::Error> {
+        debug_assert_eq!(
+            nodes.len(),
+            models.len(),
+            "`nodes` and `models` must correspond index-wise",
+        );
+
+        let mut association_stats = AssociationLoadStats {
+            child_type: std::any::type_name::<Child>(),
+            ..AssociationLoadStats::default()
+        };
+
+        let child_models = match Self::child_ids_with_trail(models, db, trail).await? {
+            LoadResult::Ids(child_ids) => {
+                assert!(same_type::<JoinModel, ()>());
+
+                // Same as the sync flow: dedupe so loaders only ever see each id once.
+                let child_ids = crate::unique(child_ids);
+                association_stats.ids_requested = child_ids.len();
+                if !child_ids.is_empty() {
+                    association_stats.batches_issued = 1;
+                }
+                let loaded_models = Self::load_children_with_trail(&child_ids, db, trail).await?;
+                loaded_models
+                    .into_iter()
+                    .map(|model| {
+                        #[allow(unsafe_code)]
+                        let join_model = unsafe {
+                            // Just like in the sync flow: this branch is only taken when
+                            // `JoinModel` is `()`, which the assert above checks.
+                            std::mem::transmute_copy::<(), JoinModel>(&())
+                        };
+
+                        (model, join_model)
+                    })
+                    .collect::<Vec<_>>()
+            }
+            LoadResult::Models(model_and_join_pairs) => {
+                association_stats.batches_issued = 1;
+                model_and_join_pairs
+            }
+        };
+
+        Self::eager_load_children_from_models(
+            nodes,
+            models,
+            db,
+            trail,
+            stats,
+            association_stats,
+            child_models,
+        )
+        .await
+    }
+
+    /// Like [`eager_load_children_with_stats`](#method.eager_load_children_with_stats), but
+    /// matching already-loaded child models instead of loading them. Same contract as the sync
+    /// [`EagerLoadChildrenOfType::eager_load_children_from_models`](trait.EagerLoadChildrenOfType.html#method.eager_load_children_from_models).
+    async fn eager_load_children_from_models(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+        stats: &mut LoadStats,
+        mut association_stats: AssociationLoadStats,
+        mut child_models: Vec<(Child::Model, JoinModel)>,
+    ) -> Result<(), Self::Error> {
+        debug_assert_eq!(
+            nodes.len(),
+            models.len(),
+            "`nodes` and `models` must correspond index-wise",
+        );
+
+        association_stats.rows_loaded = child_models.len();
+        stats.associations.push(association_stats);
+
+        Self::order_children(&mut child_models);
+
+        let pagination = Self::pagination(trail);
+        let mut window_positions = pagination.map(|_| vec![0_usize; nodes.len()]);
+
+        // Same as the sync flow: keep the models only when the recursion below needs them,
+        // otherwise move them straight into the nodes.
+        let nested_selections = Child::has_nested_selections(trail);
+
+        let mut children = Vec::with_capacity(child_models.len());
+        let mut referenced_models = Vec::with_capacity(if nested_selections {
+            child_models.len()
+        } else {
+            0
+        });
+        let mut match_ranges = Vec::with_capacity(child_models.len());
+        let mut matched_parents = Vec::with_capacity(nodes.len());
+        let mut parent_matched = vec![false; nodes.len()];
+
+        // Same hash-based fast path as the sync flow: candidates come out of the buckets in
+        // node order and are confirmed with `is_child_of`.
+        let mut parents_by_hash: Option<std::collections::HashMap<u64, Vec<usize>>> = None;
+
+        for (child_model, join_model) in child_models {
+            let (child_node, kept_model) = if nested_selections {
+                (Child::new_from_model(&child_model), Some(child_model))
+            } else {
+                (Child::new_from_owned_model(child_model), None)
+            };
+            let child = (child_node, &join_model);
+
+            let start = matched_parents.len();
+            if let Some(hash) = Self::child_match_hash(&child) {
+                let buckets = parents_by_hash.get_or_insert_with(|| {
+                    let mut buckets: std::collections::HashMap<u64, Vec<usize>> =
+                        std::collections::HashMap::with_capacity(nodes.len());
+                    for (idx, node) in nodes.iter().enumerate() {
+                        if let Some(hash) = Self::parent_match_hash(node) {
+                            buckets.entry(hash).or_default().push(idx);
+                        }
+                    }
+                    buckets
+                });
+
+                if let Some(candidates) = buckets.get(&hash) {
+                    matched_parents.extend(
+                        candidates
+                            .iter()
+                            .copied()
+                            .filter(|&idx| Self::is_child_of(&nodes[idx], &child)),
+                    );
+                }
+            } else {
+                matched_parents.extend(
+                    nodes
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, node)| Self::is_child_of(node, &child))
+                        .map(|(idx, _)| idx),
+                );
+            }
+
+            if matched_parents.len() == start {
+                continue;
+            }
+
+            for &idx in &matched_parents[start..] {
+                parent_matched[idx] = true;
+            }
+
+            if let (Some(pagination), Some(positions)) = (&pagination, window_positions.as_mut()) {
+                let mut keep = start;
+                for i in start..matched_parents.len() {
+                    let idx = matched_parents[i];
+                    let position = positions[idx];
+                    positions[idx] += 1;
+                    if pagination.contains(position) {
+                        matched_parents[keep] = idx;
+                        keep += 1;
+                    }
+                }
+                matched_parents.truncate(keep);
+
+                if matched_parents.len() == start {
+                    continue;
+                }
+            }
+
+            children.push(child.0);
+            if let Some(model) = kept_model {
+                referenced_models.push(model);
+            }
+            match_ranges.push((start, matched_parents.len()));
+        }
+
+        if parent_matched.iter().any(|matched| !matched) {
+            let unmatched = models
+                .iter()
+                .zip(&parent_matched)
+                .filter(|(_, matched)| !**matched)
+                .map(|(model, _)| model)
+                .collect::<Vec<_>>();
+            Self::on_missing_children(&unmatched)?;
+        }
+
+        if nested_selections {
+            let len_before = referenced_models.len();
+
+            Child::eager_load_all_children_for_each_with_stats(
+                &mut children,
+                &referenced_models,
+                db,
+                trail,
+                stats,
+            )
+            .await?;
+
+            assert_eq!(len_before, referenced_models.len());
+        }
+
+        for (child, (start, end)) in children.into_iter().zip(match_ranges) {
+            let parents = &matched_parents[start..end];
+
+            if let [idx] = *parents {
+                Self::loaded_child(&mut nodes[idx], child);
+            } else {
+                let child = Arc::new(child);
+                for &idx in parents {
+                    Self::loaded_shared_child(&mut nodes[idx], Arc::clone(&child));
+                }
+            }
+        }
+
+        for node in nodes {
+            Self::assert_loaded_otherwise_failed(node);
+        }
+
+        Ok(())
+    }
+}
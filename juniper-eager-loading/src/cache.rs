@@ -0,0 +1,1298 @@
+//! Cache types for reusing loaded values across eager loading passes.
+//!
+//! The caches here are type aware. A single cache can hold values of different types, keyed by
+//! the id type your app uses, without the entries interfering with each other.
+
+use std::any::{Any, TypeId};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The `Send` requirement the caches put on stored values.
+///
+/// On most targets this is equivalent to `Send`. With the `wasm` feature enabled — for
+/// single threaded targets like `wasm32-unknown-unknown` — it is implemented for every type
+/// instead, so cached values and eager loaded nodes don't have to be `Send`.
+#[cfg(not(feature = "wasm"))]
+pub trait MaybeSend: Send {}
+
+#[cfg(not(feature = "wasm"))]
+impl<T: Send> MaybeSend for T {}
+
+/// The `Send` requirement the caches put on stored values.
+///
+/// On most targets this is equivalent to `Send`. With the `wasm` feature enabled — for
+/// single threaded targets like `wasm32-unknown-unknown` — it is implemented for every type
+/// instead, so cached values and eager loaded nodes don't have to be `Send`.
+#[cfg(feature = "wasm")]
+pub trait MaybeSend {}
+
+#[cfg(feature = "wasm")]
+impl<T> MaybeSend for T {}
+
+#[cfg(not(feature = "wasm"))]
+type AnyValue = Box<dyn Any + Send>;
+
+#[cfg(feature = "wasm")]
+type AnyValue = Box<dyn Any>;
+
+// The hasher behind `Cache`'s map. The keys are small and fixed-shape — a `TypeId` plus an
+// integer-ish id — so SipHash's per-byte processing and DoS resistance are pure overhead:
+// the keys are the application's own model ids, not attacker-chosen strings. This is the
+// usual multiply-xor construction (as in rustc's FxHash), inlined here rather than pulled in
+// as a dependency.
+#[derive(Default)]
+struct FastHasher(u64);
+
+impl FastHasher {
+    fn mix(&mut self, word: u64) {
+        const SEED: u64 = 0x517c_c1b7_2722_0a95;
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl std::hash::Hasher for FastHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.mix(u64::from_ne_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, n: u8) {
+        self.mix(u64::from(n));
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.mix(u64::from(n));
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.mix(n);
+    }
+
+    fn write_u128(&mut self, n: u128) {
+        self.mix(n as u64);
+        self.mix((n >> 64) as u64);
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.mix(n as u64);
+    }
+}
+
+type FastHashMap<K, V> = HashMap<K, V, std::hash::BuildHasherDefault<FastHasher>>;
+
+// The borrowed form of the `(TypeId, K)` map key. `HashMap::get`'s `K: Borrow<Q>` doesn't
+// reach inside tuples — there's no owned `(TypeId, K)` to borrow from when all the caller has
+// is a `&str` against a `Cache<String>` — so lookups go through a trait object instead: both
+// the stored `(TypeId, K)` and a temporary `(TypeId, &Q)` present themselves as
+// `dyn BorrowedCacheKey<Q>`, whose `Hash`/`Eq` match the owned tuple's componentwise behavior
+// (the `Borrow` contract guarantees `K` and `Q` hash identically).
+trait BorrowedCacheKey<Q: ?Sized> {
+    fn parts(&self) -> (TypeId, &Q);
+}
+
+impl<K: Borrow<Q>, Q: ?Sized> BorrowedCacheKey<Q> for (TypeId, K) {
+    fn parts(&self) -> (TypeId, &Q) {
+        (self.0, self.1.borrow())
+    }
+}
+
+impl<'a, Q: ?Sized + 'a, K: Borrow<Q> + 'a> Borrow<dyn BorrowedCacheKey<Q> + 'a> for (TypeId, K) {
+    fn borrow(&self) -> &(dyn BorrowedCacheKey<Q> + 'a) {
+        self
+    }
+}
+
+impl<Q: ?Sized + Hash> Hash for dyn BorrowedCacheKey<Q> + '_ {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let (type_id, key) = self.parts();
+        type_id.hash(state);
+        key.hash(state);
+    }
+}
+
+impl<Q: ?Sized + Eq> PartialEq for dyn BorrowedCacheKey<Q> + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        self.parts().0 == other.parts().0 && self.parts().1 == other.parts().1
+    }
+}
+
+impl<Q: ?Sized + Eq> Eq for dyn BorrowedCacheKey<Q> + '_ {}
+
+// The hit/miss counters behind `Cache`. Atomics aren't available (or are emulated poorly) on
+// some wasm targets, so the `wasm` feature swaps them for plain `Cell`s.
+//
+// The counters are pure statistics: nothing synchronizes through them, so `Relaxed` is enough
+// and keeps them off the hot path. Totals are still exact — relaxed `fetch_add`s never lose
+// updates, they just don't order other memory accesses. Each counter is padded to its own cache
+// line so the hit and miss counters don't false-share under concurrent lookups.
+#[cfg(not(feature = "wasm"))]
+#[derive(Default)]
+#[repr(align(64))]
+struct Counter(std::sync::atomic::AtomicUsize);
+
+#[cfg(not(feature = "wasm"))]
+impl Counter {
+    fn increment(&self) {
+        self.add(1);
+    }
+
+    fn add(&self, n: usize) {
+        self.0.fetch_add(n, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    fn set(&self, n: usize) {
+        self.0.store(n, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    fn next(&self) -> usize {
+        self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1
+    }
+
+    fn get(&self) -> usize {
+        self.0.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+#[cfg(feature = "wasm")]
+#[derive(Default)]
+struct Counter(std::cell::Cell<usize>);
+
+#[cfg(feature = "wasm")]
+impl Counter {
+    fn increment(&self) {
+        self.add(1);
+    }
+
+    fn add(&self, n: usize) {
+        self.0.set(self.0.get() + n);
+    }
+
+    fn set(&self, n: usize) {
+        self.0.set(n);
+    }
+
+    fn next(&self) -> usize {
+        self.0.set(self.0.get() + 1);
+        self.0.get()
+    }
+
+    fn get(&self) -> usize {
+        self.0.get()
+    }
+}
+
+// The state behind `Cache::detect_n_plus_one`: per model type, how often it was lazily loaded
+// and whether we already warned about it.
+struct LazyLoadDetector {
+    threshold: usize,
+    counts: HashMap<TypeId, LazyLoadCount>,
+}
+
+struct LazyLoadCount {
+    model: &'static str,
+    count: usize,
+    warned: bool,
+}
+
+/// The clock the caches use to expire entries: a function returning the time elapsed since
+/// some fixed epoch.
+///
+/// The default clock is based on the system time. On targets without a usable system clock —
+/// such as `wasm32-unknown-unknown` under the `wasm` feature — the default clock always returns
+/// zero, which effectively disables TTL expiry; inject a real clock with
+/// [`with_ttl_and_clock`](struct.SharedCache.html#method.with_ttl_and_clock), for example one
+/// backed by `performance.now()`.
+pub type Clock = fn() -> Duration;
+
+#[cfg(not(feature = "wasm"))]
+fn default_clock() -> Duration {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "wasm")]
+fn default_clock() -> Duration {
+    Duration::from_secs(0)
+}
+
+/// A type aware cache meant to live for a single request.
+///
+/// While [`SharedCache`](struct.SharedCache.html) is shared between requests and therefore has to
+/// worry about threads and stale entries, a `Cache` is owned by one request and simply remembers
+/// every value inserted into it until it is dropped at the end of the request. That makes it safe
+/// to use without a TTL: within a request the data cannot go stale.
+///
+/// Entries are keyed by the pair of the stored value's type and the key, so two models that
+/// happen to share a numeric id space — `User` with id `1` and `Country` with id `1`, say —
+/// never read each other's entries.
+///
+/// # Example
+///
+/// ```
+/// use juniper_eager_loading::Cache;
+///
+/// let mut cache = Cache::<i32>::new();
+///
+/// cache.insert(1, "one".to_string());
+/// assert_eq!(cache.get::<String>(1), Some("one".to_string()));
+/// assert_eq!(cache.get::<String>(2), None);
+///
+/// assert_eq!(cache.hits(), 1);
+/// assert_eq!(cache.misses(), 1);
+/// ```
+pub struct Cache<K: Hash + Eq> {
+    map: FastHashMap<(TypeId, K), CacheEntry>,
+    max_entries: Option<usize>,
+    ttl: Option<Duration>,
+    clock: Clock,
+    // A logical clock for least-recently-used eviction: every lookup stamps the entry with
+    // the next tick. Per-entry stamps are `Counter`s so lookups can update them through
+    // `&self`.
+    tick: Counter,
+    hits: Counter,
+    misses: Counter,
+    // Per-type breakdown of the counters above. Lookups go through `&self` but a miss for a
+    // type that was never inserted still has to create its bucket, so unlike the `Counter`s
+    // this sits behind a `Mutex` — uncontended in practice, since a `Cache` belongs to one
+    // request.
+    type_stats: Mutex<HashMap<TypeId, TypeStats>>,
+    lazy_load_detector: Option<LazyLoadDetector>,
+}
+
+struct TypeStats {
+    name: &'static str,
+    hits: usize,
+    misses: usize,
+}
+
+struct CacheEntry {
+    value: AnyValue,
+    last_used: Counter,
+    inserted_at: Duration,
+}
+
+impl CacheEntry {
+    fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.value.downcast_ref::<T>()
+    }
+}
+
+/// The hit/miss statistics for one cached type, from
+/// [`Cache::stats_by_type`](struct.Cache.html#method.stats_by_type).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TypeCacheStats {
+    /// The stored type's name, as `std::any::type_name` renders it. Collections stored with
+    /// [`insert_vec`](struct.Cache.html#method.insert_vec) show up as their own `Box<[T]>`
+    /// type.
+    pub type_name: &'static str,
+    /// Lookups of this type that found a value.
+    pub hits: usize,
+    /// Lookups of this type that found nothing.
+    pub misses: usize,
+    /// The number of entries of this type currently held.
+    pub entries: usize,
+}
+
+impl<K: Hash + Eq> fmt::Debug for Cache<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Cache").finish()
+    }
+}
+
+impl<K: Hash + Eq> Default for Cache<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq> Cache<K> {
+    /// Create a new empty cache holding any number of entries, none of which expire.
+    pub fn new() -> Self {
+        Cache {
+            map: FastHashMap::default(),
+            max_entries: None,
+            ttl: None,
+            clock: default_clock,
+            tick: Counter::default(),
+            hits: Counter::default(),
+            misses: Counter::default(),
+            type_stats: Mutex::new(HashMap::new()),
+            lazy_load_detector: None,
+        }
+    }
+
+    /// Create a new empty cache that never holds more than `max_entries` entries, counted
+    /// across all cached types. Vec entries stored with [`insert_vec`](#method.insert_vec)
+    /// count as one entry each.
+    ///
+    /// When an insert would exceed the limit the least recently used entry is evicted first —
+    /// every lookup, hit or probe, counts as a use. A later request for an evicted entry is an
+    /// ordinary miss. The limit is for list queries touching tens of thousands of rows, where
+    /// remembering every loaded child until the end of the request costs more memory than the
+    /// reuse is worth; [`new`](#method.new) stays unbounded.
+    pub fn with_max_entries(max_entries: usize) -> Self {
+        Cache {
+            max_entries: Some(max_entries),
+            ..Cache::new()
+        }
+    }
+
+    /// Create a new empty cache whose entries expire after `ttl`, measured from when they were
+    /// inserted.
+    ///
+    /// An expired entry counts as a miss when requested. Expired entries are only dropped
+    /// lazily — when an insert hits a configured size limit, or explicitly through
+    /// [`prune_expired`](#method.prune_expired) — so a TTL on its own bounds staleness, not
+    /// memory. This matters for caches kept alive across operations, for example on a
+    /// long-lived subscription connection; within a single request the data cannot go stale
+    /// and [`new`](#method.new)'s never-expire behavior is the right default.
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Cache {
+            ttl: Some(ttl),
+            ..Cache::new()
+        }
+    }
+
+    /// Like [`with_ttl`](#method.with_ttl), but reading the time from the given
+    /// [`Clock`](type.Clock.html) instead of the default system clock.
+    ///
+    /// You only need this on targets where the system clock isn't usable, such as
+    /// `wasm32-unknown-unknown` — see the notes on [`Clock`](type.Clock.html). It's also handy
+    /// in tests, where an injected clock makes expiry deterministic.
+    pub fn with_ttl_and_clock(ttl: Duration, clock: Clock) -> Self {
+        Cache {
+            ttl: Some(ttl),
+            clock,
+            ..Cache::new()
+        }
+    }
+
+    /// Bound an existing cache at `max_entries` entries, counted and evicted exactly as
+    /// [`with_max_entries`](#method.with_max_entries) describes.
+    ///
+    /// This is how a size limit and a TTL are combined, since each has its own constructor.
+    /// When both are set, an insert that hits the limit drops expired entries before evicting
+    /// a live one.
+    pub fn set_max_entries(&mut self, max_entries: usize) {
+        self.max_entries = Some(max_entries);
+    }
+
+    /// Insert a value for the given key, replacing any previous value of the same type for that
+    /// key.
+    pub fn insert<T: 'static + MaybeSend>(&mut self, key: K, value: T) {
+        // Make sure the type shows up in `stats_by_type` even if it's never looked up.
+        self.record_type_counts(TypeId::of::<T>(), std::any::type_name::<T>(), 0, 0);
+        self.insert_any((TypeId::of::<T>(), key), Box::new(value));
+    }
+
+    /// Pre-populate the cache with models fetched some other way, keyed by the id
+    /// `key_for_model` extracts.
+    ///
+    /// This is for root resolvers that already got related rows in their own SQL query — a
+    /// join, say. Priming those rows means loaders that consult the cache, like
+    /// [`MemoizedLoader`](struct.MemoizedLoader.html), issue no query at all for them. The
+    /// derive generates a `prime_cache` method on each node type that calls this with the
+    /// right key extraction, so you don't have to know how entries are keyed internally.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use juniper_eager_loading::Cache;
+    ///
+    /// #[derive(Clone)]
+    /// struct Country { id: i32 }
+    ///
+    /// let mut cache = Cache::<i32>::new();
+    /// cache.prime(vec![Country { id: 1 }], |country| country.id);
+    ///
+    /// assert!(cache.get::<Country>(1).is_some());
+    /// ```
+    pub fn prime<T: 'static + MaybeSend>(
+        &mut self,
+        models: impl IntoIterator<Item = T>,
+        key_for_model: impl Fn(&T) -> K,
+    ) {
+        self.record_type_counts(TypeId::of::<T>(), std::any::type_name::<T>(), 0, 0);
+        for model in models {
+            let key = key_for_model(&model);
+            self.insert_any((TypeId::of::<T>(), key), Box::new(model));
+        }
+    }
+
+    /// Insert a batch of key/value pairs of one type, behaving exactly like one
+    /// [`insert`](#method.insert) per pair.
+    ///
+    /// This is for the "store everything the loader returned" step after a batch load — the
+    /// statistics bookkeeping is done once for the whole batch instead of once per pair, the
+    /// way [`get_many`](#method.get_many) batches lookups.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use juniper_eager_loading::Cache;
+    ///
+    /// let mut cache = Cache::<i32>::new();
+    /// cache.insert_many((1..=3).map(|id| (id, id.to_string())));
+    ///
+    /// assert_eq!(cache.get::<String>(2), Some("2".to_string()));
+    /// ```
+    pub fn insert_many<T: 'static + MaybeSend>(
+        &mut self,
+        entries: impl IntoIterator<Item = (K, T)>,
+    ) {
+        self.record_type_counts(TypeId::of::<T>(), std::any::type_name::<T>(), 0, 0);
+        for (key, value) in entries {
+            self.insert_any((TypeId::of::<T>(), key), Box::new(value));
+        }
+    }
+
+    fn insert_any(&mut self, map_key: (TypeId, K), value: AnyValue) {
+        if let Some(max_entries) = self.max_entries {
+            if self.map.len() >= max_entries && !self.map.contains_key(&map_key) {
+                // Expired entries are dead weight; drop them before sacrificing a live one.
+                self.prune_expired();
+                if self.map.len() >= max_entries {
+                    self.evict_least_recently_used();
+                }
+            }
+        }
+
+        let last_used = Counter::default();
+        last_used.set(self.tick.next());
+        self.map.insert(
+            map_key,
+            CacheEntry {
+                value,
+                last_used,
+                inserted_at: (self.clock)(),
+            },
+        );
+    }
+
+    // Scans for the oldest stamp, so it's `O(entries)` — only paid on inserts that hit the
+    // configured limit, and the limit bounds how many entries there are to scan.
+    fn evict_least_recently_used(&mut self) {
+        let oldest = self.map.values().map(|entry| entry.last_used.get()).min();
+
+        if let Some(oldest) = oldest {
+            let mut evicted = false;
+            self.map.retain(|_, entry| {
+                if !evicted && entry.last_used.get() == oldest {
+                    evicted = true;
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+    }
+
+    fn touch(&self, entry: &CacheEntry) {
+        entry.last_used.set(self.tick.next());
+    }
+
+    fn record_type_counts(&self, type_id: TypeId, name: &'static str, hits: usize, misses: usize) {
+        let mut stats = self.type_stats.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = stats.entry(type_id).or_insert(TypeStats {
+            name,
+            hits: 0,
+            misses: 0,
+        });
+        stats.hits += hits;
+        stats.misses += misses;
+    }
+
+    // Same check `SharedCache` uses. Lookups go through `&self`, so an expired entry can't be
+    // removed on the spot — it's treated as absent and dropped later by `prune_expired` (which
+    // inserts run automatically when they hit the size limit).
+    fn expired(&self, entry: &CacheEntry) -> bool {
+        match self.ttl {
+            Some(ttl) => {
+                let now = (self.clock)();
+                now.checked_sub(entry.inserted_at).unwrap_or_default() >= ttl
+            }
+            None => false,
+        }
+    }
+
+    fn entry_for<Q>(&self, type_id: TypeId, key: &Q) -> Option<&CacheEntry>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let lookup: (TypeId, &Q) = (type_id, key);
+        let lookup: &dyn BorrowedCacheKey<Q> = &lookup;
+        self.map.get(lookup)
+    }
+
+    /// Get a clone of the value of type `T` for the given key.
+    pub fn get<T: 'static + Clone>(&self, key: K) -> Option<T> {
+        self.get_by(&key)
+    }
+
+    /// Like [`get`](#method.get), but taking the key by reference, with the same `K: Borrow<Q>`
+    /// flexibility as [`HashMap::get`]: a `Cache<String>` can be looked up with a `&str`
+    /// without allocating an owned key first.
+    ///
+    /// The value type usually can't be inferred here, and turbofishing a two-parameter method
+    /// means spelling out both: `cache.get_by::<Model, _>(key)`.
+    ///
+    /// [`HashMap::get`]: std::collections::HashMap::get
+    pub fn get_by<T, Q>(&self, key: &Q) -> Option<T>
+    where
+        T: 'static + Clone,
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.entry_for(TypeId::of::<T>(), key) {
+            Some(entry) if !self.expired(entry) => {
+                self.touch(entry);
+                self.hits.increment();
+                self.record_type_counts(TypeId::of::<T>(), std::any::type_name::<T>(), 1, 0);
+                entry.downcast_ref::<T>().cloned()
+            }
+            _ => {
+                self.misses.increment();
+                self.record_type_counts(TypeId::of::<T>(), std::any::type_name::<T>(), 0, 1);
+                None
+            }
+        }
+    }
+
+    /// Like [`get`](#method.get), but without touching the hit/miss counters.
+    ///
+    /// Use this for speculative lookups that were going to go to the database anyway — probing a
+    /// cold cache for every id of a fresh request, say. Counting those as misses makes hit-rate
+    /// dashboards useless: the rate should reflect real reuse, not cold starts. Only count
+    /// lookups through [`get`](#method.get) where a hit actually saves a load.
+    pub fn probe<T: 'static + Clone>(&self, key: K) -> Option<T> {
+        self.entry_for(TypeId::of::<T>(), &key).and_then(|entry| {
+            if self.expired(entry) {
+                return None;
+            }
+            self.touch(entry);
+            entry.downcast_ref::<T>().cloned()
+        })
+    }
+
+    /// Probe the cache for many keys at once.
+    ///
+    /// Returns the values found and the keys that found nothing, each in the order the keys were
+    /// given, behaving exactly like one [`get`](#method.get) per key. The difference is that the
+    /// hit/miss counters are updated once with the aggregate counts instead of once per key,
+    /// which matters when probing tens of thousands of ids.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use juniper_eager_loading::Cache;
+    ///
+    /// let mut cache = Cache::<i32>::new();
+    /// cache.insert(1, "one".to_string());
+    ///
+    /// let (found, missing) = cache.get_many::<String>(&[1, 2, 3]);
+    ///
+    /// assert_eq!(found, ["one".to_string()]);
+    /// assert_eq!(missing, [2, 3]);
+    /// ```
+    pub fn get_many<T: 'static + Clone>(&self, keys: &[K]) -> (Vec<T>, Vec<K>)
+    where
+        K: Clone,
+    {
+        let mut found = Vec::new();
+        let mut missing = Vec::new();
+        self.get_many_into(keys, &mut found, &mut missing);
+        (found, missing)
+    }
+
+    /// Probe the cache for many keys at once, writing into buffers provided by the caller.
+    ///
+    /// Behaves like [`get_many`](#method.get_many) except the values found and the keys that
+    /// found nothing are appended to `found` and `missing` instead of being returned in fresh
+    /// vecs. Callers doing repeated large probes can clear and reuse the same two buffers and
+    /// pay no allocations at all once the buffers have grown to size. The buffers are not
+    /// cleared first.
+    pub fn get_many_into<T: 'static + Clone>(
+        &self,
+        keys: &[K],
+        found: &mut Vec<T>,
+        missing: &mut Vec<K>,
+    ) where
+        K: Clone,
+    {
+        let found_before = found.len();
+        let missing_before = missing.len();
+
+        for key in keys {
+            match self.entry_for(TypeId::of::<T>(), key) {
+                Some(entry) if !self.expired(entry) => {
+                    self.touch(entry);
+                    if let Some(value) = entry.downcast_ref::<T>() {
+                        found.push(value.clone());
+                    }
+                }
+                _ => missing.push(key.clone()),
+            }
+        }
+
+        self.hits.add(found.len() - found_before);
+        self.misses.add(missing.len() - missing_before);
+        self.record_type_counts(
+            TypeId::of::<T>(),
+            std::any::type_name::<T>(),
+            found.len() - found_before,
+            missing.len() - missing_before,
+        );
+    }
+
+    /// Insert a whole collection of values for the given key.
+    ///
+    /// This is meant for caching the full child list of a has-many association under the parent's
+    /// key. Inserting the vec with [`insert`](#method.insert) works too, but every
+    /// [`get`](#method.get) then clones all the elements. Values stored through this method are
+    /// kept as a boxed slice and borrowed back out with [`get_vec`](#method.get_vec), so hits are
+    /// just a lookup.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use juniper_eager_loading::Cache;
+    ///
+    /// let mut cache = Cache::<i32>::new();
+    /// cache.insert_vec(1, vec!["a".to_string(), "b".to_string()]);
+    ///
+    /// assert_eq!(cache.get_vec::<String>(1).map(|cars| cars.len()), Some(2));
+    /// assert_eq!(cache.get_vec::<String>(2), None);
+    /// ```
+    pub fn insert_vec<T: 'static + MaybeSend>(&mut self, key: K, values: Vec<T>) {
+        self.record_type_counts(
+            TypeId::of::<Box<[T]>>(),
+            std::any::type_name::<Box<[T]>>(),
+            0,
+            0,
+        );
+        self.insert_any(
+            (TypeId::of::<Box<[T]>>(), key),
+            Box::new(values.into_boxed_slice()),
+        );
+    }
+
+    /// Get the collection of values of type `T` stored for the given key with
+    /// [`insert_vec`](#method.insert_vec).
+    pub fn get_vec<T: 'static>(&self, key: K) -> Option<&[T]> {
+        match self.entry_for(TypeId::of::<Box<[T]>>(), &key) {
+            Some(entry) if !self.expired(entry) => {
+                self.touch(entry);
+                self.hits.increment();
+                self.record_type_counts(
+                    TypeId::of::<Box<[T]>>(),
+                    std::any::type_name::<Box<[T]>>(),
+                    1,
+                    0,
+                );
+                entry.downcast_ref::<Box<[T]>>().map(|values| &**values)
+            }
+            _ => {
+                self.misses.increment();
+                self.record_type_counts(
+                    TypeId::of::<Box<[T]>>(),
+                    std::any::type_name::<Box<[T]>>(),
+                    0,
+                    1,
+                );
+                None
+            }
+        }
+    }
+
+    /// Remove the value of type `T` for the given key, if any.
+    ///
+    /// This is the counterpart of [`SharedCache::invalidate`](struct.SharedCache.html#method.invalidate)
+    /// for the per-request cache: after a mutation updates a row, removing the stale model makes
+    /// later eager loads in the same request hit the database again. A lookup after a remove is
+    /// an ordinary miss. Collections stored with [`insert_vec`](#method.insert_vec) live under
+    /// their own type and are removed with `remove::<Box<[T]>>` — or more bluntly with
+    /// [`clear`](#method.clear).
+    pub fn remove<T: 'static>(&mut self, key: K) {
+        self.map.remove(&(TypeId::of::<T>(), key));
+    }
+
+    /// Remove every entry of type `T`, whatever its key, leaving other types untouched.
+    ///
+    /// Use this when a mutation invalidates models you can't enumerate — a bulk update, say —
+    /// without throwing away everything else the request has loaded.
+    pub fn clear_type<T: 'static>(&mut self) {
+        self.map
+            .retain(|(type_id, _), _| *type_id != TypeId::of::<T>());
+    }
+
+    /// Remove all entries regardless of type. The hit/miss counters are not reset.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Drop every entry that has outlived the TTL. Does nothing on a cache without one.
+    ///
+    /// Expired entries already read as misses; this only reclaims their memory. Inserts call it
+    /// automatically when they hit a [`with_max_entries`](#method.with_max_entries) limit, so
+    /// you only need it yourself on a long-lived unbounded cache.
+    pub fn prune_expired(&mut self) {
+        if let Some(ttl) = self.ttl {
+            let now = (self.clock)();
+            self.map
+                .retain(|_, entry| now.checked_sub(entry.inserted_at).unwrap_or_default() < ttl);
+        }
+    }
+
+    /// The number of entries currently held, counted across all cached types.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Is the cache empty?
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// The number of lookups that found a value.
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+
+    /// The number of lookups that found nothing.
+    pub fn misses(&self) -> usize {
+        self.misses.get()
+    }
+
+    /// The hit/miss counters broken down by cached type, plus each type's current entry count.
+    ///
+    /// [`hits`](#method.hits) and [`misses`](#method.misses) are the sums of the per-type
+    /// counts; a type that was only ever requested — never inserted — still gets an entry, so
+    /// a cache that never hits for `Country` says so. The breakdown is sorted by type name for
+    /// stable log output; logging it at the end of a request is a cheap way to spot
+    /// associations worth denormalizing. Like the global counters, [`probe`](#method.probe)
+    /// lookups aren't counted.
+    pub fn stats_by_type(&self) -> Vec<TypeCacheStats> {
+        let mut entry_counts = HashMap::new();
+        for (type_id, _) in self.map.keys() {
+            *entry_counts.entry(*type_id).or_insert(0) += 1;
+        }
+
+        let stats = self.type_stats.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stats = stats
+            .iter()
+            .map(|(type_id, stats)| TypeCacheStats {
+                type_name: stats.name,
+                hits: stats.hits,
+                misses: stats.misses,
+                entries: entry_counts.get(type_id).copied().unwrap_or(0),
+            })
+            .collect::<Vec<_>>();
+        stats.sort_unstable_by_key(|stats| stats.type_name);
+        stats
+    }
+
+    /// Opt in to runtime N+1 detection.
+    ///
+    /// After this call every [`record_lazy_load`](#method.record_lazy_load) is counted per model
+    /// type, and the first time a type is lazily loaded more than `threshold` times a warning
+    /// naming the type and the count is emitted through the [`log`] crate. Since a `Cache` lives
+    /// for a single request, the counts are per request.
+    ///
+    /// This is meant for incremental migrations: resolvers that still lazy load inside loops
+    /// silently defeat eager loading, and this makes them show up in your logs.
+    ///
+    /// [`log`]: https://docs.rs/log
+    pub fn detect_n_plus_one(&mut self, threshold: usize) {
+        self.lazy_load_detector = Some(LazyLoadDetector {
+            threshold,
+            counts: HashMap::new(),
+        });
+    }
+
+    /// Record that a `Model` was loaded lazily, outside of eager loading.
+    ///
+    /// Call this from resolvers that still lazy load. Does nothing unless
+    /// [`detect_n_plus_one`](#method.detect_n_plus_one) was called.
+    pub fn record_lazy_load<Model: 'static>(&mut self) {
+        if let Some(detector) = &mut self.lazy_load_detector {
+            let count = detector
+                .counts
+                .entry(TypeId::of::<Model>())
+                .or_insert(LazyLoadCount {
+                    model: std::any::type_name::<Model>(),
+                    count: 0,
+                    warned: false,
+                });
+            count.count += 1;
+
+            if count.count > detector.threshold && !count.warned {
+                count.warned = true;
+                log::warn!(
+                    "`{}` was lazily loaded {} times in one request (threshold is {}). \
+                     This looks like an N+1 query, consider eager loading it.",
+                    count.model,
+                    count.count,
+                    detector.threshold,
+                );
+            }
+        }
+    }
+
+    /// The number of lazy loads recorded for `Model` in this request.
+    ///
+    /// Always zero unless [`detect_n_plus_one`](#method.detect_n_plus_one) was called.
+    pub fn lazy_load_count<Model: 'static>(&self) -> usize {
+        self.lazy_load_detector
+            .as_ref()
+            .and_then(|detector| detector.counts.get(&TypeId::of::<Model>()))
+            .map(|count| count.count)
+            .unwrap_or(0)
+    }
+}
+
+impl Cache<String> {
+    /// Create an [`InternedCache`](struct.InternedCache.html), which interns its string keys
+    /// instead of cloning them into the map.
+    pub fn with_interner() -> InternedCache {
+        InternedCache::new()
+    }
+}
+
+/// A [`Cache`](struct.Cache.html) for string keys that interns them.
+///
+/// With string ids — UUIDs serialized as `String`, say — a plain `Cache<String>` clones every
+/// key into its map and hashes the whole string on every lookup. This cache instead maps each
+/// distinct key to a small integer handle the first time it's seen and keys the underlying
+/// storage by the handle, so each distinct id is stored once no matter how many types are cached
+/// under it, and repeat operations hash a string only to find the handle.
+///
+/// # Example
+///
+/// ```
+/// use juniper_eager_loading::Cache;
+///
+/// let mut cache = Cache::with_interner();
+///
+/// cache.insert("550e8400-e29b-41d4-a716-446655440000", 1_i32);
+/// assert_eq!(cache.get::<i32>("550e8400-e29b-41d4-a716-446655440000"), Some(1));
+/// assert_eq!(cache.get::<i32>("some-other-id"), None);
+/// ```
+#[derive(Default)]
+pub struct InternedCache {
+    interner: Interner,
+    cache: Cache<u32>,
+}
+
+impl fmt::Debug for InternedCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InternedCache").finish()
+    }
+}
+
+impl InternedCache {
+    /// Create a new empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a value for the given key, replacing any previous value of the same type for that
+    /// key.
+    ///
+    /// The key string is cloned only the first time the key is seen.
+    pub fn insert<T: 'static + MaybeSend>(&mut self, key: &str, value: T) {
+        let handle = self.interner.intern(key);
+        self.cache.insert(handle, value);
+    }
+
+    /// Get a clone of the value of type `T` for the given key.
+    pub fn get<T: 'static + Clone>(&self, key: &str) -> Option<T> {
+        match self.interner.lookup(key) {
+            Some(handle) => self.cache.get(handle),
+            None => {
+                self.cache.misses.increment();
+                None
+            }
+        }
+    }
+
+    /// The number of lookups that found a value.
+    pub fn hits(&self) -> usize {
+        self.cache.hits()
+    }
+
+    /// The number of lookups that found nothing.
+    pub fn misses(&self) -> usize {
+        self.cache.misses()
+    }
+}
+
+// Maps each distinct string key to a small dense integer handle, cloning the string once per
+// distinct key.
+#[derive(Default)]
+struct Interner {
+    handles: HashMap<String, u32>,
+}
+
+impl Interner {
+    fn intern(&mut self, key: &str) -> u32 {
+        if let Some(handle) = self.handles.get(key) {
+            return *handle;
+        }
+
+        let handle = self.handles.len() as u32;
+        self.handles.insert(key.to_owned(), handle);
+        handle
+    }
+
+    fn lookup(&self, key: &str) -> Option<u32> {
+        self.handles.get(key).copied()
+    }
+}
+
+/// A clonable, thread safe cache handle meant to live longer than a single query execution.
+///
+/// This is primarily useful for subscription-like setups where the same nodes get loaded over
+/// and over for each event pushed to the client. See
+/// [`SubscriptionLoader`](struct.SubscriptionLoader.html) for how to combine it with eager
+/// loading.
+///
+/// Cloning a `SharedCache` is cheap and the clone will refer to the same underlying storage.
+///
+/// # Expiring entries
+///
+/// Because a `SharedCache` can be long-lived you normally don't want entries to be served
+/// forever. You can either construct the cache with [`with_ttl`](#method.with_ttl) so entries
+/// expire automatically, or call [`invalidate`](#method.invalidate) when you know a value has
+/// changed.
+///
+/// # Example
+///
+/// ```
+/// use juniper_eager_loading::SharedCache;
+///
+/// let cache = SharedCache::<i32>::new();
+///
+/// cache.insert(1, "one".to_string());
+/// assert_eq!(cache.get::<String>(1), Some("one".to_string()));
+///
+/// cache.invalidate::<String>(1);
+/// assert_eq!(cache.get::<String>(1), None);
+/// ```
+pub struct SharedCache<K: Hash + Eq> {
+    inner: Arc<Mutex<SharedCacheInner<K>>>,
+}
+
+impl<K: Hash + Eq> Clone for SharedCache<K> {
+    fn clone(&self) -> Self {
+        SharedCache {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<K: Hash + Eq> fmt::Debug for SharedCache<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SharedCache").finish()
+    }
+}
+
+impl<K: Hash + Eq> Default for SharedCache<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct SharedCacheInner<K: Hash + Eq> {
+    map: HashMap<(TypeId, K), Entry>,
+    ttl: Option<Duration>,
+    clock: Clock,
+    hits: usize,
+    misses: usize,
+}
+
+struct Entry {
+    inserted_at: Duration,
+    value: AnyValue,
+}
+
+impl<K: Hash + Eq> SharedCache<K> {
+    /// Create a new empty cache whose entries never expire.
+    pub fn new() -> Self {
+        Self::new_inner(None, default_clock)
+    }
+
+    /// Create a new empty cache whose entries expire after `ttl`.
+    ///
+    /// An expired entry counts as a miss when requested and will be removed lazily.
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self::new_inner(Some(ttl), default_clock)
+    }
+
+    /// Create a new empty cache whose entries expire after `ttl` according to the given
+    /// [`Clock`](type.Clock.html).
+    ///
+    /// You only need this on targets where the default system clock isn't usable, such as
+    /// `wasm32-unknown-unknown`.
+    pub fn with_ttl_and_clock(ttl: Duration, clock: Clock) -> Self {
+        Self::new_inner(Some(ttl), clock)
+    }
+
+    fn new_inner(ttl: Option<Duration>, clock: Clock) -> Self {
+        SharedCache {
+            inner: Arc::new(Mutex::new(SharedCacheInner {
+                map: HashMap::new(),
+                ttl,
+                clock,
+                hits: 0,
+                misses: 0,
+            })),
+        }
+    }
+
+    /// Insert a value for the given key, replacing any previous value of the same type for that
+    /// key.
+    pub fn insert<T: 'static + MaybeSend>(&self, key: K, value: T) {
+        let mut inner = self.lock();
+        let inserted_at = (inner.clock)();
+        inner.map.insert(
+            (TypeId::of::<T>(), key),
+            Entry {
+                inserted_at,
+                value: Box::new(value),
+            },
+        );
+    }
+
+    /// Get a clone of the value of type `T` for the given key.
+    ///
+    /// Returns `None` if no value has been inserted or if the entry has expired.
+    pub fn get<T: 'static + Clone>(&self, key: K) -> Option<T> {
+        let mut inner = self.lock();
+        let ttl = inner.ttl;
+        let now = (inner.clock)();
+        let map_key = (TypeId::of::<T>(), key);
+
+        let expired = match inner.map.get(&map_key) {
+            Some(entry) => match ttl {
+                Some(ttl) => now.checked_sub(entry.inserted_at).unwrap_or_default() >= ttl,
+                None => false,
+            },
+            None => {
+                inner.misses += 1;
+                return None;
+            }
+        };
+
+        if expired {
+            inner.map.remove(&map_key);
+            inner.misses += 1;
+            return None;
+        }
+
+        inner.hits += 1;
+        let entry = &inner.map[&map_key];
+        entry.value.downcast_ref::<T>().cloned()
+    }
+
+    /// Remove the value of type `T` for the given key, if any.
+    pub fn invalidate<T: 'static>(&self, key: K) {
+        let mut inner = self.lock();
+        inner.map.remove(&(TypeId::of::<T>(), key));
+    }
+
+    /// Remove all entries regardless of type.
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        inner.map.clear();
+    }
+
+    /// The number of lookups that found an unexpired value.
+    pub fn hits(&self) -> usize {
+        self.lock().hits
+    }
+
+    /// The number of lookups that found nothing, or found an expired value.
+    pub fn misses(&self) -> usize {
+        self.lock().misses
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SharedCacheInner<K>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The insert/get surface shared by [`Cache`](struct.Cache.html) and
+/// [`SharedCache`](struct.SharedCache.html).
+///
+/// Code that consults a cache — a resolver helper, a custom loader — can be generic over this
+/// trait and work unchanged whether the caller hands it the per-request `Cache` or a
+/// `SharedCache` stored in the juniper context. The methods take `&mut self` so the
+/// per-request cache can implement them directly; `SharedCache` uses its interior mutability
+/// and doesn't actually need the exclusivity.
+pub trait CacheStorage<K: Hash + Eq> {
+    /// Insert a value for the given key, replacing any previous value of the same type for
+    /// that key.
+    fn insert<T: 'static + Clone + MaybeSend>(&mut self, key: K, value: T);
+
+    /// Get a clone of the value of type `T` for the given key.
+    fn get<T: 'static + Clone>(&self, key: K) -> Option<T>;
+
+    /// Remove the value of type `T` for the given key, if any, so the next lookup misses.
+    fn remove<T: 'static>(&mut self, key: K);
+}
+
+impl<K: Hash + Eq> CacheStorage<K> for Cache<K> {
+    fn insert<T: 'static + Clone + MaybeSend>(&mut self, key: K, value: T) {
+        Cache::insert(self, key, value);
+    }
+
+    fn get<T: 'static + Clone>(&self, key: K) -> Option<T> {
+        Cache::get(self, key)
+    }
+
+    fn remove<T: 'static>(&mut self, key: K) {
+        Cache::remove::<T>(self, key);
+    }
+}
+
+impl<K: Hash + Eq> CacheStorage<K> for SharedCache<K> {
+    fn insert<T: 'static + Clone + MaybeSend>(&mut self, key: K, value: T) {
+        SharedCache::insert(self, key, value);
+    }
+
+    fn get<T: 'static + Clone>(&self, key: K) -> Option<T> {
+        SharedCache::get(self, key)
+    }
+
+    fn remove<T: 'static>(&mut self, key: K) {
+        SharedCache::invalidate::<T>(self, key);
+    }
+}
+
+/// A per-request [`Cache`](struct.Cache.html) in front of a longer-lived second level.
+///
+/// The second level is anything implementing [`CacheStorage`](trait.CacheStorage.html) — a
+/// [`SharedCache`](struct.SharedCache.html) stored next to the connection pool, or your own
+/// wrapper around an external store like Redis or `moka`. Lookups consult the per-request
+/// front first and fall back to the second level; inserts write through to both. Since the
+/// second level outlives the request, reference data loaded once — countries, plans — hits
+/// from every later request, while the front keeps repeat lookups within one request off the
+/// shared store entirely.
+///
+/// Invalidation goes through both levels too, so removing a model after a mutation behaves
+/// the way it does on a plain `Cache`.
+///
+/// # Example
+///
+/// ```
+/// use juniper_eager_loading::{Cache, CacheStorage, LayeredCache, SharedCache};
+///
+/// // Lives next to the connection pool.
+/// let shared = SharedCache::<i32>::new();
+///
+/// // Request one loads a country and stores it.
+/// let mut cache = LayeredCache::new(Cache::new(), shared.clone());
+/// cache.insert(1, "Denmark".to_string());
+///
+/// // Request two gets a fresh front but hits via the shared second level.
+/// let cache = LayeredCache::new(Cache::new(), shared.clone());
+/// assert_eq!(cache.get::<String>(1), Some("Denmark".to_string()));
+/// ```
+#[derive(Debug)]
+pub struct LayeredCache<K: Hash + Eq, B> {
+    front: Cache<K>,
+    backend: B,
+}
+
+impl<K: Hash + Eq, B: CacheStorage<K>> LayeredCache<K, B> {
+    /// Layer the given per-request cache in front of the given second level.
+    pub fn new(front: Cache<K>, backend: B) -> Self {
+        LayeredCache { front, backend }
+    }
+
+    /// The per-request front. Its hit/miss counters only see the lookups the second level
+    /// didn't have to answer.
+    pub fn front(&self) -> &Cache<K> {
+        &self.front
+    }
+
+    /// The second level, for handing back to wherever it lives between requests.
+    pub fn into_backend(self) -> B {
+        self.backend
+    }
+}
+
+impl<K: Hash + Eq + Clone, B: CacheStorage<K>> CacheStorage<K> for LayeredCache<K, B> {
+    fn insert<T: 'static + Clone + MaybeSend>(&mut self, key: K, value: T) {
+        self.backend.insert(key.clone(), value.clone());
+        self.front.insert(key, value);
+    }
+
+    fn get<T: 'static + Clone>(&self, key: K) -> Option<T> {
+        // Probe the front so lookups the second level answers don't drag down the front's
+        // hit rate — its counters should reflect what the front alone is worth.
+        if let Some(value) = self.front.probe::<T>(key.clone()) {
+            return Some(value);
+        }
+        self.backend.get(key)
+    }
+
+    fn remove<T: 'static>(&mut self, key: K) {
+        self.front.remove::<T>(key.clone());
+        self.backend.remove::<T>(key);
+    }
+}
+
+#[cfg(all(test, feature = "wasm"))]
+mod wasm_tests {
+    use super::*;
+
+    #[test]
+    fn cell_backed_counters_behave_like_the_atomic_ones() {
+        let mut cache = Cache::<i32>::new();
+        cache.insert(1, "one".to_string());
+
+        assert_eq!(cache.get::<String>(1), Some("one".to_string()));
+        assert_eq!(cache.get::<String>(2), None);
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 1);
+    }
+
+    #[test]
+    fn the_default_zero_clock_disables_ttl_expiry() {
+        let cache = SharedCache::<i32>::with_ttl(Duration::from_secs(1));
+        cache.insert(1, "one".to_string());
+        assert_eq!(cache.get::<String>(1), Some("one".to_string()));
+    }
+
+    #[test]
+    fn an_injected_clock_controls_expiry() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        static NOW_MILLIS: AtomicU64 = AtomicU64::new(0);
+        fn test_clock() -> Duration {
+            Duration::from_millis(NOW_MILLIS.load(Ordering::SeqCst))
+        }
+
+        let cache = SharedCache::<i32>::with_ttl_and_clock(Duration::from_millis(10), test_clock);
+        cache.insert(1, "one".to_string());
+        assert_eq!(cache.get::<String>(1), Some("one".to_string()));
+
+        NOW_MILLIS.store(20, Ordering::SeqCst);
+        assert_eq!(cache.get::<String>(1), None);
+    }
+}
@@ -0,0 +1,48 @@
+//! Threading request-scoped context — the current viewer, metrics handles — into loaders.
+
+use std::fmt;
+
+/// A connection type pairing a database connection with request-scoped application context, so
+/// [`LoadFrom`](trait.LoadFrom.html) implementations can see the current viewer for row-level
+/// authorization or record metrics without smuggling state through a bespoke connection type.
+///
+/// The eager loading machinery deliberately threads a single `Connection` value through every
+/// association rather than adding a second context channel to each trait method — an associated
+/// `Context` type would double every signature and break every manual implementation for what
+/// composition already solves. This type makes the composition first class: set
+/// `Connection = DbAndContext<YourDb, YourCtx>` on the node types, have `LoadFrom` impls query
+/// through `connection.db`, and read whatever the request stashed in `connection.ctx`. It's the
+/// same pattern as [`DbAndRemote`](struct.DbAndRemote.html), which pairs the connection with a
+/// remote service client instead.
+///
+/// ```
+/// use juniper_eager_loading::DbAndContext;
+///
+/// struct Viewer {
+///     user_id: i32,
+/// }
+///
+/// # struct Db;
+/// # let db = Db;
+/// let connection = DbAndContext::new(db, Viewer { user_id: 1 });
+/// assert_eq!(connection.ctx.user_id, 1);
+/// ```
+pub struct DbAndContext<Db, Ctx> {
+    /// The database connection.
+    pub db: Db,
+    /// The request-scoped application context.
+    pub ctx: Ctx,
+}
+
+impl<Db, Ctx> DbAndContext<Db, Ctx> {
+    /// Bundle a database connection and request-scoped context.
+    pub fn new(db: Db, ctx: Ctx) -> Self {
+        DbAndContext { db, ctx }
+    }
+}
+
+impl<Db, Ctx> fmt::Debug for DbAndContext<Db, Ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DbAndContext").finish()
+    }
+}
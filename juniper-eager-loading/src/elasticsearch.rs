@@ -0,0 +1,91 @@
+//! Helpers for loading children from Elasticsearch. Requires the `elasticsearch` feature.
+//!
+//! Some child types aren't rows in your SQL database but denormalized search documents living in
+//! Elasticsearch, keyed by the same ids. The helper here issues one `_mget` for the whole id set
+//! so those children can be eager loaded with a single round trip, just like database backed
+//! ones.
+//!
+//! The HTTP client is abstracted behind [`MgetTransport`](trait.MgetTransport.html) so the
+//! helper works with whatever Elasticsearch client you already use, and so tests can substitute
+//! a fake returning canned responses.
+//!
+//! Until the derive supports pointing an association at a custom loader you plug this in by
+//! implementing [`EagerLoadChildrenOfType`](../trait.EagerLoadChildrenOfType.html) manually and
+//! calling the helper from `load_children`, blocking on the future with whatever runtime your
+//! app uses:
+//!
+//! ```text
+//! fn load_children(ids: &[Self::ChildId], db: &Self::Connection) -> Result<Vec<models::Bio>, Self::Error> {
+//!     futures::executor::block_on(load_from_elasticsearch(&db.elasticsearch, "bios", ids))
+//! }
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::future::Future;
+
+/// The parsed body of an Elasticsearch `_mget` response.
+#[derive(Debug, Deserialize)]
+pub struct MgetResponse {
+    /// One entry per requested id, in request order.
+    pub docs: Vec<MgetDoc>,
+}
+
+/// A single document in an [`MgetResponse`](struct.MgetResponse.html).
+#[derive(Debug, Deserialize)]
+pub struct MgetDoc {
+    /// Whether a document exists for the requested id.
+    pub found: bool,
+    /// The document source. `None` when the document wasn't found or `_source` was disabled.
+    #[serde(rename = "_source")]
+    pub source: Option<serde_json::Value>,
+}
+
+/// A transport capable of issuing Elasticsearch `_mget` requests.
+///
+/// Implement this for your Elasticsearch client of choice. Tests can implement it with a fake
+/// that returns canned [`MgetResponse`](struct.MgetResponse.html)s.
+pub trait MgetTransport {
+    /// The error type returned by the transport.
+    ///
+    /// It must absorb `serde_json::Error` since deserializing `_source` into the model type can
+    /// fail.
+    type Error: From<serde_json::Error>;
+
+    /// The future returned by [`mget`](#tymethod.mget).
+    type Future: Future<Output = Result<MgetResponse, Self::Error>>;
+
+    /// Issue an `_mget` against `index` for the given document ids.
+    fn mget(&self, index: &str, ids: &[String]) -> Self::Future;
+}
+
+/// Load models from Elasticsearch by issuing one `_mget` for the whole id set.
+///
+/// Each found document's `_source` is deserialized into the model type. Missing documents are
+/// simply absent from the result — not errors — matching how
+/// [`LoadFrom`](../trait.LoadFrom.html) treats ids without a row.
+pub async fn load_from_elasticsearch<T, Id, Transport>(
+    transport: &Transport,
+    index: &str,
+    ids: &[Id],
+) -> Result<Vec<T>, Transport::Error>
+where
+    T: DeserializeOwned,
+    Id: ToString,
+    Transport: MgetTransport,
+{
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let ids = ids.iter().map(ToString::to_string).collect::<Vec<_>>();
+    let response = transport.mget(index, &ids).await?;
+
+    let mut models = Vec::with_capacity(response.docs.len());
+    for doc in response.docs {
+        if let (true, Some(source)) = (doc.found, doc.source) {
+            models.push(serde_json::from_value(source)?);
+        }
+    }
+    Ok(models)
+}
@@ -0,0 +1,80 @@
+//! Helpers for resolving batches of entities by key, as in Apollo Federation.
+
+use crate::{small_vec::unique_ids, EagerLoadAllChildren, GraphqlNodeForModel, LoadFrom};
+use std::collections::HashMap;
+
+/// Eager load nodes for a batch of entity keys in one go.
+///
+/// Subgraphs in an Apollo Federation setup receive `_entities(representations: [...])` requests
+/// containing a whole batch of keys for a node type. Resolving those one by one defeats the
+/// point of eager loading. This function instead:
+///
+/// 1. Loads all the models for the (deduplicated) keys with one
+///    [`LoadFrom`](trait.LoadFrom.html) call.
+/// 2. Constructs the nodes with
+///    [`from_db_models`](trait.GraphqlNodeForModel.html#method.from_db_models).
+/// 3. Eager loads all their children with
+///    [`eager_load_all_children_for_each`](trait.EagerLoadAllChildren.html#tymethod.eager_load_all_children_for_each).
+///
+/// The result has one element per key, in key order. Keys with no matching model yield `None`,
+/// which is what the `_entities` contract expects for unknown representations.
+///
+/// `key_for_model` extracts the key from a loaded model, normally `|model| model.id`, and is
+/// used to pair models back up with the keys that requested them.
+///
+/// # Getting the trail
+///
+/// The `_entities` field isn't part of your juniper-from-schema schema, so there is no generated
+/// `QueryTrail` accessor for it. Define the field returning your node type (or a union of node
+/// types) in the schema and use the trail for the concrete type, exactly like you would in a
+/// regular list resolver:
+///
+/// ```text
+/// fn field_entities(
+///     &self,
+///     executor: &Executor<'_, Context>,
+///     trail: &QueryTrail<'_, User, Walked>,
+///     representations: Vec<Representation>,
+/// ) -> FieldResult<Vec<Option<User>>> {
+///     let keys = representations.iter().map(|rep| rep.id).collect::<Vec<_>>();
+///     let users = juniper_eager_loading::eager_load_entities(
+///         &keys,
+///         &executor.context().db,
+///         trail,
+///         |user: &models::User| user.id,
+///     )?;
+///     Ok(users)
+/// }
+/// ```
+pub fn eager_load_entities<T, Q, F>(
+    keys: &[T::Id],
+    db: &T::Connection,
+    trail: &Q,
+    key_for_model: F,
+) -> Result<Vec<Option<T>>, T::Error>
+where
+    T: GraphqlNodeForModel + EagerLoadAllChildren<Q> + Clone,
+    T::Model: LoadFrom<T::Id, Error = T::Error, Connection = T::Connection>,
+    T::Id: Clone,
+    F: Fn(&T::Model) -> T::Id,
+{
+    if keys.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let unique_keys = unique_ids(keys);
+    let models = T::Model::load(&unique_keys, db)?;
+
+    let mut nodes = T::from_db_models(&models);
+    T::eager_load_all_children_for_each(&mut nodes, &models, db, trail)?;
+
+    let mut nodes_by_key = HashMap::with_capacity(nodes.len());
+    for (model, node) in models.iter().zip(nodes) {
+        nodes_by_key.insert(key_for_model(model), node);
+    }
+
+    Ok(keys
+        .iter()
+        .map(|key| nodes_by_key.get(key).cloned())
+        .collect())
+}
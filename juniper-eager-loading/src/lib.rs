@@ -288,14 +288,11 @@
 //!         // Load the model users.
 //!         let user_models = db.load_all_users();
 //!
-//!         // Turn the model users into GraphQL users.
-//!         let mut users = User::from_db_models(&user_models);
-//!
-//!         // Perform the eager loading.
+//!         // Turn the model users into GraphQL users and perform the eager loading.
 //!         // `trail` is used to only eager load the fields that are requested. Because
 //!         // we're using `QueryTrail`s from "juniper_from_schema" it would be a compile
 //!         // error if we eager loaded too much.
-//!         User::eager_load_all_children_for_each(&mut users, &user_models, db, trail)?;
+//!         let users = User::eager_load(&user_models, db, trail)?;
 //!
 //!         Ok(users)
 //!     }
@@ -362,9 +359,13 @@
 //! |---|---|---|---|
 //! | `connection` | The type of connection your app uses. This could be a database connection or a connection to another web service. | N/A | `connection = "diesel::pg::PgConnection"` |
 //! | `error` | The type of error eager loading might result in. | N/A | `error = "diesel::result::Error"` |
-//! | `model` | The model type behind your GraphQL struct | `models::{name of struct}` | `model = "crate::db::models::User"` |
+//! | `model` | The model type behind your GraphQL struct. Wrapping it in `Arc` makes every parent a popular child gets attached to share one model allocation; loading delegates to the inner type's `LoadFrom`. | `models::{name of struct}` | `model = "crate::db::models::User"`, `model = "Arc<models::Country>"` |
 //! | `id` | Which id type does your app use? | `i32` | `id = "UUID"` |
 //! | `root_model_field` | The name of the field has holds the backing model | `{name of struct}` in snakecase. | `root_model_field = "user"` |
+//! | `error_variant` | Generate `impl From<juniper_eager_loading::Error> for {error}` wrapping the crate's error in the named variant of your error enum, so `?` works on `try_unwrap` in resolvers. Set it on exactly one struct per error type — `From` can only be implemented once. | Not set | `error_variant = "EagerLoading"` |
+//! | `async` | Emit impls of the async eager loading traits instead of the sync ones. The connection type must be a [`ConnectionPool`](trait.ConnectionPool.html) and the models must implement [`AsyncLoadFrom`](trait.AsyncLoadFrom.html). Requires the `async` feature. | Not set | `async` |
+//! | `print` | Print everything the derive generates for the struct to stderr while compiling, run through `rustfmt` when it's installed. Useful for debugging, or as a starting point when moving to hand-written impls. | Not set | `print` |
+//! | `emit_to` | Like `print` but write the generated code to the given file instead of stderr. | Not set | `emit_to = "/tmp/user_eager_loading.rs"` |
 //!
 //! # Associations
 //!
@@ -403,6 +404,27 @@
 //! The resulting code wont be formatted. We recommend you do that with
 //! [rustfmt](https://github.com/rust-lang/rustfmt).
 //!
+//! ## Plain fields that aren't associations
+//!
+//! Besides the model field and the association fields your struct can hold plain extra fields —
+//! computed values, request-scoped state — that the derive shouldn't touch at all. Mark them
+//! with `#[eager_loading(skip)]` and the generated constructors will initialize them with
+//! `Default::default()` instead of trying to build them from the model:
+//!
+//! ```ignore
+//! #[derive(Clone, EagerLoading)]
+//! #[eager_loading(connection = "DbConnection", error = "diesel::result::Error")]
+//! struct User {
+//!     user: models::User,
+//!
+//!     #[has_one(default)]
+//!     country: HasOne<Country>,
+//!
+//!     #[eager_loading(skip)]
+//!     view_count: std::cell::Cell<i32>,
+//! }
+//! ```
+//!
 //! # Diesel helper
 //!
 //! Implementing [`LoadFrom`][] for lots of model types might involve lots of boilerplate. If you're
@@ -425,6 +447,14 @@
 //! in a resolver is the only way to get the behaviour you need then so be it. Avoiding some N+1
 //! queries is better than avoiding none.
 //!
+//! One setup that comes up often enough to mention is a polymorphic association — an edge
+//! pointing at a GraphQL union, backed by `subject_type` and `subject_id` columns on the model.
+//! The derive can't express that, but a manual [`EagerLoadChildrenOfType`][] implementation for
+//! the union enum can (mark the field with `skip`): use the `(type, id)` pair as the child id,
+//! group the ids by the type column in `load_children` and issue one query per concrete type,
+//! and match the variant back up in `is_child_of`. Each concrete type is still loaded in a
+//! single batch across all parents.
+//!
 //! However if you have a setup that you think this library should support please don't hestitate
 //! to [open an issue](https://github.com/davidpdrsn/juniper-eager-loading).
 //!
@@ -448,15 +478,48 @@
     unused_variables
 )]
 
+#[cfg(feature = "async")]
+mod async_load;
+mod cache;
+mod context;
+#[cfg(feature = "elasticsearch")]
+pub mod elasticsearch;
+mod federation;
 mod macros;
+#[cfg(feature = "cached")]
+mod memoized;
+mod pool;
+mod remote;
+#[cfg(feature = "scylla")]
+pub mod scylla;
+mod small_vec;
+mod subscription;
+#[cfg(feature = "test-helpers")]
+pub mod test_support;
 
 use juniper_from_schema::Walked;
-use std::{fmt, hash::Hash};
-
+use std::{collections::HashMap, fmt, hash::Hash, sync::Arc};
+
+#[cfg(feature = "async")]
+pub use crate::async_load::{AsyncEagerLoadAllChildren, AsyncEagerLoadChildrenOfType};
+pub use crate::cache::{
+    Cache, CacheStorage, Clock, InternedCache, LayeredCache, MaybeSend, SharedCache, TypeCacheStats,
+};
+pub use crate::context::DbAndContext;
+pub use crate::federation::eager_load_entities;
+#[cfg(feature = "cached")]
+pub use crate::memoized::MemoizedLoader;
+pub use crate::pool::{load_from_pool, AsyncLoadFrom, ConnectionPool};
+pub use crate::remote::{load_remote, DbAndRemote, RemoteCallHooks, RemoteLoadFrom, RetryLimit};
+pub use crate::subscription::SubscriptionLoader;
 pub use juniper_eager_loading_code_gen::EagerLoading;
 
 /// Re-exports the traits needed for doing eager loading. Meant to be glob imported.
 pub mod prelude {
+    #[cfg(feature = "async")]
+    pub use super::AsyncEagerLoadAllChildren;
+    #[cfg(feature = "async")]
+    pub use super::AsyncEagerLoadChildrenOfType;
     pub use super::EagerLoadAllChildren;
     pub use super::EagerLoadChildrenOfType;
     pub use super::GraphqlNodeForModel;
@@ -479,6 +542,28 @@ pub enum AssociationType {
     HasManyThrough,
 }
 
+/// Where in the GraphQL schema an edge error happened: the type holding the association and
+/// the field's name.
+///
+/// The derived code records this on every edge it builds, so errors from
+/// [`try_unwrap`](struct.HasOne.html#method.try_unwrap) can say *which* association on
+/// *which* type went wrong — "`User.country` should have been eager loaded" instead of just
+/// "`HasOne` should have been eager loaded". Displays as `User.country`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ErrorLocation {
+    /// The GraphQL type holding the association, e.g. `User`.
+    pub parent_type: &'static str,
+
+    /// The association's field name, e.g. `country`.
+    pub field_name: &'static str,
+}
+
+impl fmt::Display for ErrorLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.parent_type, self.field_name)
+    }
+}
+
 /// A non-optional "has one" association.
 ///
 /// Imagine you have these models:
@@ -509,6 +594,7 @@ pub enum AssociationType {
 /// | `root_model_field` | The name of the field on the associated GraphQL type that holds the database model | `{name of field}` | `root_model_field = "country"` |
 /// | `graphql_field` | The name of this field in your GraphQL schema | `{name of field}` | `graphql_field = "country"` |
 /// | `default` | Use the default value for all unspecified attributes | N/A | `default` |
+/// | `on_missing` | What to do when the foreign key points at a child that doesn't exist. `error` fails the whole load with [`Error::MissingChildren`](enum.Error.html#variant.MissingChildren), which requires your error type to implement `From<juniper_eager_loading::Error>`. `null` keeps the default behavior | `null` | `on_missing = "error"` |
 ///
 /// Additionally it also supports the attributes `print`, and `skip`. See the [root model
 /// docs](/#attributes-supported-on-all-associations) for more into on those.
@@ -522,24 +608,156 @@ pub enum AssociationType {
 /// [`try_unwrap`][] will return an error.
 ///
 /// [`try_unwrap`]: struct.HasOne.html#method.try_unwrap
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
-pub struct HasOne<T>(HasOneInner<T>);
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HasOne<T> {
+    inner: HasOneInner<T>,
+    // Diagnostic metadata, not state: not serialized, consistent with being ignored by
+    // equality and hashing below.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    location: Option<ErrorLocation>,
+}
+
+// Equality, ordering, and hashing consider only the edge's state and value. The location
+// recorded by the derived code is diagnostic metadata, and including it would make derived
+// nodes compare unequal to hand-built expected values in snapshot tests.
+impl<T: PartialEq> PartialEq for HasOne<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<T: Eq> Eq for HasOne<T> {}
+
+impl<T: PartialOrd> PartialOrd for HasOne<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.inner.partial_cmp(&other.inner)
+    }
+}
+
+impl<T: Ord> Ord for HasOne<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.inner.cmp(&other.inner)
+    }
+}
+
+impl<T: Hash> Hash for HasOne<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
+}
 
 impl<T> Default for HasOne<T> {
     fn default() -> Self {
-        HasOne(HasOneInner::default())
+        HasOne {
+            inner: HasOneInner::default(),
+            location: None,
+        }
     }
 }
 
 impl<T> HasOne<T> {
+    /// Create a default, not-yet-loaded edge that remembers which type and field it sits in.
+    ///
+    /// The derived code uses this so that errors carry their
+    /// [location](struct.ErrorLocation.html) — "`User.country` should have been eager loaded"
+    /// — and so that panics from
+    /// [strict not-loaded checks](fn.set_strict_not_loaded_checks.html) can name the exact
+    /// field that was accessed before being eager loaded.
+    pub fn new_for_field(parent_type: &'static str, field_name: &'static str) -> Self {
+        HasOne {
+            inner: HasOneInner::default(),
+            location: Some(ErrorLocation {
+                parent_type,
+                field_name,
+            }),
+        }
+    }
+
     /// Borrow the loaded value. If the value has not been loaded it will return an error.
+    ///
+    /// # Panics
+    ///
+    /// Panics instead of returning [`Error::NotLoaded`](enum.Error.html#variant.NotLoaded) when
+    /// [strict not-loaded checks](fn.set_strict_not_loaded_checks.html) are enabled for the
+    /// current thread.
     pub fn try_unwrap(&self) -> Result<&T, Error> {
-        self.0.try_unwrap()
+        self.panic_if_strict_and_not_loaded();
+        self.inner.try_unwrap().map_err(|err| err.at(self.location))
+    }
+
+    /// Move the loaded value out of the edge. The error cases mirror
+    /// [`try_unwrap`](#method.try_unwrap), including the strict not-loaded panic.
+    ///
+    /// This avoids cloning large loaded models when ownership is needed, for example to hand
+    /// the value into another node or return it from a mutation.
+    pub fn into_inner(self) -> Result<T, Error> {
+        self.panic_if_strict_and_not_loaded();
+        let location = self.location;
+        self.inner.into_inner().map_err(|err| err.at(location))
+    }
+
+    /// Move the loaded value out of the edge, leaving a not-loaded edge behind.
+    ///
+    /// If no value is loaded the edge is left untouched and the error mirrors
+    /// [`try_unwrap`](#method.try_unwrap), including the strict not-loaded panic.
+    pub fn take(&mut self) -> Result<T, Error> {
+        self.panic_if_strict_and_not_loaded();
+        if let HasOneInner::Loaded(_) = self.inner {
+            std::mem::take(&mut self.inner).into_inner()
+        } else {
+            Err(self
+                .inner
+                .try_unwrap()
+                .err()
+                .expect("non-loaded edges always error")
+                .at(self.location))
+        }
+    }
+
+    /// Borrow the loaded value, panicking with the given message if there isn't one.
+    ///
+    /// The panic message also names the payload type and the edge's state, so a failing test
+    /// points straight at the association that was missed. This is the edge-type analogue of
+    /// `Option::expect`: for asserting in tests that eager loading actually populated a field,
+    /// and for code paths where an unloaded edge is a programmer error.
+    pub fn expect_loaded(&self, msg: &str) -> &T {
+        match self.inner.try_unwrap() {
+            Ok(value) => value,
+            Err(err) => panic!("{}: `HasOne<{}>`: {}", msg, std::any::type_name::<T>(), err,),
+        }
+    }
+
+    fn panic_if_strict_and_not_loaded(&self) {
+        if strict_not_loaded_checks_enabled() && matches!(self.inner, HasOneInner::NotLoaded) {
+            panic!(
+                "`{}` (`HasOne<{}>`) was accessed before being eager loaded. \
+                 Did you forget to walk the query trail for it?",
+                self.location
+                    .map(|location| location.field_name)
+                    .unwrap_or("<unknown field>"),
+                std::any::type_name::<T>(),
+            );
+        }
     }
 
     /// Set the given value as the loaded value.
     pub fn loaded(&mut self, inner: T) {
-        self.0.loaded(inner)
+        self.inner.loaded(inner)
+    }
+
+    /// Mark the edge as intentionally skipped because the GraphQL field wasn't selected by the
+    /// query.
+    ///
+    /// The derived code calls this when the `QueryTrail` doesn't include the field. It makes
+    /// [`try_unwrap`][] return [`Error::NotRequested`](enum.Error.html#variant.NotRequested)
+    /// instead of the generic [`Error::NotLoaded`](enum.Error.html#variant.NotLoaded), so
+    /// "the query didn't ask for this" is distinguishable from "you forgot to eager load it".
+    /// Does nothing if a value was already loaded.
+    ///
+    /// [`try_unwrap`]: struct.HasOne.html#method.try_unwrap
+    pub fn not_requested(&mut self) {
+        self.inner.not_requested()
     }
 
     /// Check that a loaded value is present otherwise set `self` to an error state after which
@@ -547,42 +765,166 @@ impl<T> HasOne<T> {
     ///
     /// [`try_unwrap`]: struct.HasOne.html#method.try_unwrap
     pub fn assert_loaded_otherwise_failed(&mut self) {
-        self.0.assert_loaded_otherwise_failed()
+        self.inner.assert_loaded_otherwise_failed()
+    }
+
+    /// Like [`assert_loaded_otherwise_failed`][], but additionally records which ids were
+    /// involved when the load did fail.
+    ///
+    /// The closure only runs on the failure path, so collecting the ids costs nothing for
+    /// successful loads. The recorded details end up in the error returned by
+    /// [`try_unwrap`][], which makes "failed to load" messages actionable: they name the
+    /// parent row and the child id it pointed at.
+    ///
+    /// The derived [`EagerLoadChildrenOfType`][] implementations call this method for you.
+    ///
+    /// [`assert_loaded_otherwise_failed`]: struct.HasOne.html#method.assert_loaded_otherwise_failed
+    /// [`try_unwrap`]: struct.HasOne.html#method.try_unwrap
+    /// [`EagerLoadChildrenOfType`]: trait.EagerLoadChildrenOfType.html
+    pub fn assert_loaded_otherwise_failed_with<F>(&mut self, details: F)
+    where
+        F: FnOnce() -> LoadFailedDetails,
+    {
+        self.inner.assert_loaded_otherwise_failed_with(details)
+    }
+
+    /// Map the loaded value, preserving the edge's state.
+    ///
+    /// A not-loaded, not-requested, or failed edge passes through unchanged (the closure
+    /// doesn't run), so mapping never hides what happened during eager loading.
+    pub fn map<U, F>(self, f: F) -> HasOne<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        let inner = match self.inner {
+            HasOneInner::Loaded(value) => HasOneInner::Loaded(f(value)),
+            HasOneInner::NotLoaded => HasOneInner::NotLoaded,
+            HasOneInner::NotRequested => HasOneInner::NotRequested,
+            HasOneInner::LoadFailed(details) => HasOneInner::LoadFailed(details),
+        };
+        HasOne {
+            inner,
+            location: self.location,
+        }
+    }
+
+    /// Borrow the edge as `HasOne<&T>`, preserving its state.
+    ///
+    /// Useful together with [`map`](#method.map) to transform the loaded value without
+    /// consuming the edge.
+    pub fn as_ref(&self) -> HasOne<&T> {
+        let inner = match &self.inner {
+            HasOneInner::Loaded(value) => HasOneInner::Loaded(value),
+            HasOneInner::NotLoaded => HasOneInner::NotLoaded,
+            HasOneInner::NotRequested => HasOneInner::NotRequested,
+            HasOneInner::LoadFailed(details) => HasOneInner::LoadFailed(details.clone()),
+        };
+        HasOne {
+            inner,
+            location: self.location,
+        }
+    }
+
+    /// Is a value loaded?
+    pub fn is_loaded(&self) -> bool {
+        matches!(self.inner, HasOneInner::Loaded(_))
+    }
+
+    /// Is the edge still in its initial, not eager loaded state?
+    ///
+    /// Returns `false` for edges [marked as not requested](#method.not_requested) — those were
+    /// skipped on purpose.
+    pub fn is_not_loaded(&self) -> bool {
+        matches!(self.inner, HasOneInner::NotLoaded)
+    }
+
+    /// Did loading the value fail?
+    pub fn is_failed(&self) -> bool {
+        matches!(self.inner, HasOneInner::LoadFailed(_))
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+thread_local! {
+    static STRICT_NOT_LOADED_CHECKS: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Enable or disable strict not-loaded checks for the current thread.
+///
+/// With strict checks enabled, calling [`try_unwrap`](struct.HasOne.html#method.try_unwrap) on a
+/// [`HasOne`](struct.HasOne.html) that was never eager loaded panics immediately — naming the
+/// field (when the edge was built by the derived code), the edge kind, and the payload type —
+/// instead of returning [`Error::NotLoaded`](enum.Error.html#variant.NotLoaded). That usually
+/// points straight at a missing `QueryTrail` walk in the derive attributes.
+///
+/// This is a development aid. Leave it off in production, where the behavior is unchanged.
+pub fn set_strict_not_loaded_checks(enabled: bool) {
+    STRICT_NOT_LOADED_CHECKS.with(|flag| flag.set(enabled));
+}
+
+fn strict_not_loaded_checks_enabled() -> bool {
+    STRICT_NOT_LOADED_CHECKS.with(|flag| flag.get())
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum HasOneInner<T> {
     Loaded(T),
+    #[default]
     NotLoaded,
-    LoadFailed,
-}
-
-impl<T> Default for HasOneInner<T> {
-    fn default() -> Self {
-        HasOneInner::NotLoaded
-    }
+    NotRequested,
+    // The details borrow `'static` strings and can't be deserialized, so serialization
+    // preserves the variant but not the diagnostic ids.
+    LoadFailed(#[cfg_attr(feature = "serde", serde(skip))] Option<Box<LoadFailedDetails>>),
 }
 
 impl<T> HasOneInner<T> {
     fn try_unwrap(&self) -> Result<&T, Error> {
         match self {
             HasOneInner::Loaded(inner) => Ok(inner),
-            HasOneInner::NotLoaded => Err(Error::NotLoaded(AssociationType::HasOne)),
-            HasOneInner::LoadFailed => Err(Error::LoadFailed(AssociationType::HasOne)),
+            HasOneInner::NotLoaded => Err(Error::NotLoaded(AssociationType::HasOne, None)),
+            HasOneInner::NotRequested => Err(Error::NotRequested(AssociationType::HasOne, None)),
+            HasOneInner::LoadFailed(None) => Err(Error::LoadFailed(AssociationType::HasOne, None)),
+            HasOneInner::LoadFailed(Some(details)) => Err(Error::LoadFailedForIds(
+                AssociationType::HasOne,
+                (**details).clone(),
+            )),
+        }
+    }
+
+    fn into_inner(self) -> Result<T, Error> {
+        match self {
+            HasOneInner::Loaded(inner) => Ok(inner),
+            HasOneInner::NotLoaded => Err(Error::NotLoaded(AssociationType::HasOne, None)),
+            HasOneInner::NotRequested => Err(Error::NotRequested(AssociationType::HasOne, None)),
+            HasOneInner::LoadFailed(None) => Err(Error::LoadFailed(AssociationType::HasOne, None)),
+            HasOneInner::LoadFailed(Some(details)) => {
+                Err(Error::LoadFailedForIds(AssociationType::HasOne, *details))
+            }
         }
     }
 
     fn loaded(&mut self, inner: T) {
-        std::mem::replace(self, HasOneInner::Loaded(inner));
+        *self = HasOneInner::Loaded(inner);
+    }
+
+    fn not_requested(&mut self) {
+        if let HasOneInner::NotLoaded = self {
+            *self = HasOneInner::NotRequested;
+        }
     }
 
     fn assert_loaded_otherwise_failed(&mut self) {
-        match self {
-            HasOneInner::NotLoaded => {
-                std::mem::replace(self, HasOneInner::LoadFailed);
-            }
-            _ => {}
+        if let HasOneInner::NotLoaded = self {
+            *self = HasOneInner::LoadFailed(None);
+        }
+    }
+
+    fn assert_loaded_otherwise_failed_with<F>(&mut self, details: F)
+    where
+        F: FnOnce() -> LoadFailedDetails,
+    {
+        if let HasOneInner::NotLoaded = self {
+            *self = HasOneInner::LoadFailed(Some(Box::new(details())));
         }
     }
 }
@@ -598,45 +940,233 @@ impl<T> HasOneInner<T> {
 ///
 /// # Attributes
 ///
-/// It supports the same attributes as [`HasOne`].
+/// It supports the same attributes as [`HasOne`]. In addition `on_missing = "null"` makes a
+/// foreign key pointing at a row that couldn't be found resolve to `None` instead of failing
+/// — for schemas where the pointed-at rows legitimately get hard-deleted. The strict behavior
+/// described under [errors](#errors) stays the default.
 ///
 /// [`HasOne`]: struct.HasOne.html
 ///
 /// # Errors
 ///
-/// [`try_unwrap`][] will never error. If the association wasn't loaded or wasn't found it will
-/// return `Ok(None)`.
+/// [`try_unwrap`][] returns `Ok(None)` both when the association wasn't loaded and when the
+/// foreign key was null — a user legitimately without a city resolves to `None`. It only
+/// errors when the foreign key pointed at a row that couldn't be found: the derived code marks
+/// the edge as failed, and [`try_unwrap`][] returns
+/// [`Error::LoadFailedForIds`](enum.Error.html#variant.LoadFailedForIds) naming the rows
+/// involved, instead of masking the dangling foreign key as a legitimate null.
 ///
 /// [`try_unwrap`]: struct.OptionHasOne.html#method.try_unwrap
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
-pub struct OptionHasOne<T>(Option<T>);
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionHasOne<T> {
+    value: Option<T>,
+    state: OptionHasOneState,
+    // Diagnostic metadata, like `HasOne`'s: not serialized and ignored by the comparisons
+    // below.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    location: Option<ErrorLocation>,
+}
+
+// Like `HasOne`, the location recorded by the derived code is diagnostic metadata and takes
+// no part in equality, ordering, or hashing.
+impl<T: PartialEq> PartialEq for OptionHasOne<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value && self.state == other.state
+    }
+}
+
+impl<T: Eq> Eq for OptionHasOne<T> {}
+
+impl<T: PartialOrd> PartialOrd for OptionHasOne<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        (&self.value, &self.state).partial_cmp(&(&other.value, &other.state))
+    }
+}
+
+impl<T: Ord> Ord for OptionHasOne<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.value, &self.state).cmp(&(&other.value, &other.state))
+    }
+}
+
+impl<T: Hash> Hash for OptionHasOne<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+        self.state.hash(state);
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum OptionHasOneState {
+    Loaded,
+    NotLoaded,
+    LoadFailed(#[cfg_attr(feature = "serde", serde(skip))] Option<Box<LoadFailedDetails>>),
+}
 
 impl<T> Default for OptionHasOne<T> {
     fn default() -> Self {
-        OptionHasOne(None)
+        OptionHasOne {
+            value: None,
+            state: OptionHasOneState::NotLoaded,
+            location: None,
+        }
     }
 }
 
 impl<T> OptionHasOne<T> {
+    /// Create a default, not-yet-loaded edge that remembers which type and field it sits in,
+    /// so errors carry their [location](struct.ErrorLocation.html). The derived code builds
+    /// edges this way.
+    pub fn new_for_field(parent_type: &'static str, field_name: &'static str) -> Self {
+        OptionHasOne {
+            location: Some(ErrorLocation {
+                parent_type,
+                field_name,
+            }),
+            ..OptionHasOne::default()
+        }
+    }
     /// Borrow the loaded value. If the value has not been loaded it will return `Ok(None)`. It
-    /// will not error.
+    /// only errors if the load failed — see the [errors section](#errors).
     pub fn try_unwrap(&self) -> Result<&Option<T>, Error> {
-        Ok(&self.0)
+        match &self.state {
+            OptionHasOneState::LoadFailed(None) => Err(Error::LoadFailed(
+                AssociationType::OptionHasOne,
+                self.location,
+            )),
+            OptionHasOneState::LoadFailed(Some(details)) => Err(Error::LoadFailedForIds(
+                AssociationType::OptionHasOne,
+                (**details).clone(),
+            )),
+            OptionHasOneState::Loaded | OptionHasOneState::NotLoaded => Ok(&self.value),
+        }
     }
 
     /// Set the given value as the loaded value.
     pub fn loaded(&mut self, inner: T) {
-        std::mem::replace(self, OptionHasOne(Some(inner)));
+        self.value = Some(inner);
+        self.state = OptionHasOneState::Loaded;
+    }
+
+    /// Mark the association as loaded with no value, because the foreign key was null.
+    ///
+    /// The derived code calls this for models whose foreign key is `None`, so that
+    /// [`assert_loaded_otherwise_failed`](#method.assert_loaded_otherwise_failed) won't
+    /// mistake a legitimate null for a failed load. Does nothing if a value was already
+    /// loaded.
+    pub fn loaded_none(&mut self) {
+        if let OptionHasOneState::NotLoaded = self.state {
+            self.state = OptionHasOneState::Loaded;
+        }
     }
 
-    /// Check that a loaded value is present otherwise set `self` to `None`.
+    /// Check that the association reached a loaded state, otherwise set `self` to an error
+    /// state after which [`try_unwrap`](#method.try_unwrap) will return an error.
+    ///
+    /// Both a loaded value and a null foreign key marked with
+    /// [`loaded_none`](#method.loaded_none) count as loaded.
     pub fn assert_loaded_otherwise_failed(&mut self) {
-        match self.0 {
-            Some(_) => {}
-            None => {
-                std::mem::replace(self, OptionHasOne(None));
-            }
+        if let OptionHasOneState::NotLoaded = self.state {
+            self.state = OptionHasOneState::LoadFailed(None);
+        }
+    }
+
+    /// Like [`assert_loaded_otherwise_failed`](#method.assert_loaded_otherwise_failed), but
+    /// additionally records which ids were involved when the load did fail. The closure only
+    /// runs on the failure path.
+    ///
+    /// The derived [`EagerLoadChildrenOfType`](trait.EagerLoadChildrenOfType.html)
+    /// implementations call this method for you.
+    pub fn assert_loaded_otherwise_failed_with<F>(&mut self, details: F)
+    where
+        F: FnOnce() -> LoadFailedDetails,
+    {
+        if let OptionHasOneState::NotLoaded = self.state {
+            self.state = OptionHasOneState::LoadFailed(Some(Box::new(details())));
+        }
+    }
+
+    /// Map the loaded value, preserving the edge's state.
+    ///
+    /// The closure runs only if a value was actually loaded; `None` and failed edges pass
+    /// through unchanged.
+    pub fn map<U, F>(self, f: F) -> OptionHasOne<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        OptionHasOne {
+            value: self.value.map(f),
+            state: self.state,
+            location: self.location,
+        }
+    }
+
+    /// Borrow the edge as `OptionHasOne<&T>`, preserving its state.
+    pub fn as_ref(&self) -> OptionHasOne<&T> {
+        OptionHasOne {
+            value: self.value.as_ref(),
+            state: self.state.clone(),
+            location: self.location,
+        }
+    }
+
+    /// Is the edge loaded? Both a loaded value and a legitimate `None` count as loaded.
+    pub fn is_loaded(&self) -> bool {
+        matches!(self.state, OptionHasOneState::Loaded)
+    }
+
+    /// Is the edge still in its initial, not eager loaded state?
+    pub fn is_not_loaded(&self) -> bool {
+        matches!(self.state, OptionHasOneState::NotLoaded)
+    }
+
+    /// Did loading the value fail?
+    pub fn is_failed(&self) -> bool {
+        matches!(self.state, OptionHasOneState::LoadFailed(_))
+    }
+
+    /// Borrow the loaded value, panicking with the given message if the edge isn't in a
+    /// loaded state. A legitimate `None` doesn't panic — only a not-loaded or failed edge
+    /// does.
+    ///
+    /// See [`HasOne::expect_loaded`](struct.HasOne.html#method.expect_loaded) for when to
+    /// reach for this.
+    pub fn expect_loaded(&self, msg: &str) -> &Option<T> {
+        if self.is_not_loaded() {
+            panic!(
+                "{}: `OptionHasOne<{}>`: `OptionHasOne` should have been eager loaded, but wasn't",
+                msg,
+                std::any::type_name::<T>(),
+            );
         }
+        match self.try_unwrap() {
+            Ok(value) => value,
+            Err(err) => panic!(
+                "{}: `OptionHasOne<{}>`: {}",
+                msg,
+                std::any::type_name::<T>(),
+                err,
+            ),
+        }
+    }
+
+    /// Move the loaded value out of the edge. The error cases mirror
+    /// [`try_unwrap`](#method.try_unwrap).
+    pub fn into_inner(self) -> Result<Option<T>, Error> {
+        self.try_unwrap()?;
+        Ok(self.value)
+    }
+
+    /// Move the loaded value out of the edge, leaving a not-loaded edge behind.
+    ///
+    /// If the load failed the edge is left untouched and the error mirrors
+    /// [`try_unwrap`](#method.try_unwrap).
+    pub fn take(&mut self) -> Result<Option<T>, Error> {
+        self.try_unwrap()?;
+        self.state = OptionHasOneState::NotLoaded;
+        Ok(self.value.take())
     }
 }
 
@@ -660,6 +1190,21 @@ impl<T> OptionHasOne<T> {
 ///
 /// This means users can own many cars, but cars can only be owned by one user.
 ///
+/// # How children are loaded
+///
+/// Because the foreign key lives on the child, the parent has no child ids to hand back.
+/// Instead the derived code passes all the parent models to
+/// `LoadFrom<{parent model}>` in one call. Your implementation returns every child whose
+/// foreign key is in that set — typically `select * from cars where user_id in (...)` — and
+/// the children are then grouped onto their parents by comparing the foreign key. So it is
+/// one query regardless of how many parents are being loaded.
+///
+/// The exception is the `ids_field` attribute, for schemas that keep the relationship on the
+/// parent as an array column of child ids — for example a Postgres `tag_ids integer[]` on
+/// posts. The ids are then collected off the parents (flattened and deduplicated), loaded
+/// through `LoadFrom<{id}>` in one query, and each parent's children are attached in the
+/// order of its own array.
+///
 /// # Example
 ///
 /// You can find a complete example of `HasMany` [here](https://github.com/davidpdrsn/juniper-eager-loading/tree/master/juniper-eager-loading/examples/has_many.rs).
@@ -670,40 +1215,287 @@ impl<T> OptionHasOne<T> {
 /// |---|---|---|---|
 /// | `foreign_key_field` | The name of the foreign key field | `{name of struct}_id` | `foreign_key_field = "user_id"` |
 /// | `foreign_key_optional` | The foreign key type is optional | Not set | `foreign_key_optional` |
+/// | `ids_field` | The field on the parent model holding an array of child ids, instead of a foreign key on the child. Children come back in the order of each parent's array | Not set | `ids_field = "tag_ids"` |
 /// | `root_model_field` | The name of the field on the associated GraphQL type that holds the database model | N/A (unless using `skip`) | `root_model_field = "car"` |
 /// | `graphql_field` | The name of this field in your GraphQL schema | `{name of field}` | `graphql_field = "country"` |
 /// | `predicate_method` | Method used to filter child associations. This can be used if you only want to include a subset of the models | N/A (attribute is optional) | `predicate_method = "a_predicate_method"` |
+/// | `order_by_method` | Method called on the child model that returns the sort key children are ordered by within each parent's list. Sorting is ascending and stable; return [`std::cmp::Reverse`] for descending | N/A (attribute is optional) | `order_by_method = "created_at"` |
+/// | `paginate_with` | Function from the query trail to an [`Option<Pagination>`](struct.Pagination.html) window applied per parent when attaching children | N/A (attribute is optional) | `paginate_with = "cars_pagination"` |
+/// | `on_missing` | What to do when a parent has no children. `error` fails the whole load with [`Error::MissingChildren`](enum.Error.html#variant.MissingChildren), which requires your error type to implement `From<juniper_eager_loading::Error>`. `skip` keeps the default behavior | `skip` | `on_missing = "error"` |
 ///
 /// # Errors
 ///
-/// [`try_unwrap`][] will never error. If the association wasn't loaded or wasn't found it will
-/// return `Ok(vec![])`.
+/// [`try_unwrap`][] errors only if the edge was explicitly marked as failed with
+/// [`load_failed`][] — the derived code never does that. Otherwise it returns `Ok(vec![])`,
+/// and in particular a parent that legitimately has zero children resolves to an empty list
+/// after eager loading, it doesn't error. A failure recorded with `load_failed_with` also
+/// carries [details](struct.LoadFailedDetails.html) about the ids involved.
 ///
+/// [`load_failed`]: struct.HasMany.html#method.load_failed
 /// [`try_unwrap`]: struct.HasMany.html#method.try_unwrap
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
-pub struct HasMany<T>(Vec<T>);
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HasMany<T> {
+    values: Vec<T>,
+    failed: bool,
+    // Only set through `load_failed_with`; like `HasOne`'s failure details it isn't
+    // serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    failure_details: Option<Box<LoadFailedDetails>>,
+    // Diagnostic metadata, like `HasOne`'s: not serialized and ignored by the comparisons
+    // below.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    location: Option<ErrorLocation>,
+}
+
+// Like `HasOne`, the location recorded by the derived code is diagnostic metadata and takes
+// no part in equality, ordering, or hashing.
+impl<T: PartialEq> PartialEq for HasMany<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+            && self.failed == other.failed
+            && self.failure_details == other.failure_details
+    }
+}
+
+impl<T: Eq> Eq for HasMany<T> {}
+
+impl<T: PartialOrd> PartialOrd for HasMany<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        (&self.values, &self.failed, &self.failure_details).partial_cmp(&(
+            &other.values,
+            &other.failed,
+            &other.failure_details,
+        ))
+    }
+}
+
+impl<T: Ord> Ord for HasMany<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.values, &self.failed, &self.failure_details).cmp(&(
+            &other.values,
+            &other.failed,
+            &other.failure_details,
+        ))
+    }
+}
+
+impl<T: Hash> Hash for HasMany<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.values.hash(state);
+        self.failed.hash(state);
+        self.failure_details.hash(state);
+    }
+}
 
 impl<T> Default for HasMany<T> {
     fn default() -> Self {
-        HasMany(Vec::new())
+        HasMany {
+            values: Vec::new(),
+            failed: false,
+            failure_details: None,
+            location: None,
+        }
     }
 }
 
 impl<T> HasMany<T> {
+    /// Create a default, empty edge that remembers which type and field it sits in, so errors
+    /// carry their [location](struct.ErrorLocation.html). The derived code builds edges this
+    /// way.
+    pub fn new_for_field(parent_type: &'static str, field_name: &'static str) -> Self {
+        HasMany {
+            location: Some(ErrorLocation {
+                parent_type,
+                field_name,
+            }),
+            ..HasMany::default()
+        }
+    }
+
     /// Borrow the loaded values. If no values have been loaded it will return an empty list.
-    /// It will not return an error.
+    /// It only errors if the edge was explicitly marked with
+    /// [`load_failed`](#method.load_failed).
     pub fn try_unwrap(&self) -> Result<&Vec<T>, Error> {
-        Ok(&self.0)
+        if let Some(details) = &self.failure_details {
+            Err(Error::LoadFailedForIds(
+                AssociationType::HasMany,
+                (**details).clone(),
+            ))
+        } else if self.failed {
+            Err(Error::LoadFailed(AssociationType::HasMany, self.location))
+        } else {
+            Ok(&self.values)
+        }
     }
 
     /// Add the loaded value to the list.
     pub fn loaded(&mut self, inner: T) {
-        self.0.push(inner);
+        self.values.push(inner);
+    }
+
+    /// Set the whole list of loaded values at once, replacing anything loaded before.
+    ///
+    /// Unlike calling [`loaded`](#method.loaded) in a loop this is idempotent: running the same
+    /// load twice — during a retry, say — leaves the edge with one copy of each child instead of
+    /// accumulating duplicates. It also clears a failure marked with
+    /// [`load_failed`](#method.load_failed), since the retry evidently succeeded.
+    pub fn loaded_all(&mut self, values: Vec<T>) {
+        self.values = values;
+        self.failed = false;
+        self.failure_details = None;
+    }
+
+    /// Extend the list with a batch of loaded values, keeping anything loaded before.
+    ///
+    /// Sits between [`loaded`](#method.loaded) and [`loaded_all`](#method.loaded_all): the
+    /// right call when children arrive in batches that should accumulate. A failure marked
+    /// with [`load_failed`](#method.load_failed) is left in place.
+    pub fn append(&mut self, values: impl IntoIterator<Item = T>) {
+        self.values.extend(values);
+    }
+
+    /// Reorder the loaded values in place by the given key, with a stable sort.
+    ///
+    /// The eager loading flow attaches children in loader order, which is the same for every
+    /// parent. This is how derived code restores a per-parent order instead — for example
+    /// putting children back in the order of the parent's id array column when the association
+    /// uses `ids_field`. Does nothing on a failed edge, which holds no values.
+    pub fn sort_by_key<K, F>(&mut self, key: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.values.sort_by_key(key);
+    }
+
+    /// Mark the association as failed to load, after which
+    /// [`try_unwrap`](#method.try_unwrap) returns
+    /// [`Error::LoadFailed`](enum.Error.html#variant.LoadFailed) instead of an empty list.
+    ///
+    /// The derived code never calls this — an empty list is a legitimate result for a has-many
+    /// association. It's for manual [`EagerLoadChildrenOfType`][] implementations that can tell
+    /// a failed load apart from a childless parent and don't want the failure masked as "no
+    /// children".
+    ///
+    /// [`EagerLoadChildrenOfType`]: trait.EagerLoadChildrenOfType.html
+    pub fn load_failed(&mut self) {
+        self.failed = true;
+    }
+
+    /// Like [`load_failed`](#method.load_failed), but record
+    /// [details](struct.LoadFailedDetails.html) about which ids were involved, so
+    /// [`try_unwrap`](#method.try_unwrap) returns
+    /// [`Error::LoadFailedForIds`](enum.Error.html#variant.LoadFailedForIds) naming the rows
+    /// instead of a bare "failed to load".
+    pub fn load_failed_with(&mut self, details: LoadFailedDetails) {
+        self.failed = true;
+        self.failure_details = Some(Box::new(details));
     }
 
-    /// This function doesn't do anything since the default is an empty list and there is no error
-    /// state.
+    /// This function doesn't do anything since the default is an empty list. Failures are only
+    /// recorded through [`load_failed`](#method.load_failed).
     pub fn assert_loaded_otherwise_failed(&mut self) {}
+
+    /// Map every loaded value, preserving the edge's state.
+    pub fn map<U, F>(self, f: F) -> HasMany<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        HasMany {
+            values: self.values.into_iter().map(f).collect(),
+            failed: self.failed,
+            failure_details: self.failure_details,
+            location: self.location,
+        }
+    }
+
+    /// Borrow the edge as `HasMany<&T>`, preserving its state.
+    pub fn as_ref(&self) -> HasMany<&T> {
+        HasMany {
+            values: self.values.iter().collect(),
+            failed: self.failed,
+            failure_details: self.failure_details.clone(),
+            location: self.location,
+        }
+    }
+
+    /// Are any values loaded? An empty list counts as not loaded, since the two are
+    /// indistinguishable for a list edge.
+    pub fn is_loaded(&self) -> bool {
+        !self.values.is_empty()
+    }
+
+    /// Is the list empty and the edge not failed?
+    pub fn is_not_loaded(&self) -> bool {
+        self.values.is_empty() && !self.failed
+    }
+
+    /// Was the edge marked as failed with [`load_failed`](#method.load_failed)?
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+
+    /// Borrow the loaded values, panicking with the given message if the edge is empty or
+    /// failed. Unlike [`try_unwrap`](#method.try_unwrap) an empty list panics here: the point
+    /// is asserting that eager loading actually populated the field.
+    ///
+    /// See [`HasOne::expect_loaded`](struct.HasOne.html#method.expect_loaded) for when to
+    /// reach for this.
+    pub fn expect_loaded(&self, msg: &str) -> &Vec<T> {
+        if let Err(err) = self.try_unwrap() {
+            panic!(
+                "{}: `HasMany<{}>`: {}",
+                msg,
+                std::any::type_name::<T>(),
+                err,
+            );
+        }
+        if self.values.is_empty() {
+            panic!(
+                "{}: `HasMany<{}>` should have been eager loaded, but is empty",
+                msg,
+                std::any::type_name::<T>(),
+            );
+        }
+        &self.values
+    }
+
+    /// Move the loaded values out of the edge. The error cases mirror
+    /// [`try_unwrap`](#method.try_unwrap).
+    pub fn into_inner(self) -> Result<Vec<T>, Error> {
+        self.try_unwrap()?;
+        Ok(self.values)
+    }
+
+    /// Move the loaded values out of the edge, leaving a not-loaded edge behind.
+    ///
+    /// If the edge was marked as failed it is left untouched and the error mirrors
+    /// [`try_unwrap`](#method.try_unwrap).
+    pub fn take(&mut self) -> Result<Vec<T>, Error> {
+        self.try_unwrap()?;
+        Ok(std::mem::take(&mut self.values))
+    }
+
+    /// Iterate over the loaded values.
+    ///
+    /// This is deliberately `Result`-flavored: a failed edge errors like
+    /// [`try_unwrap`](#method.try_unwrap) instead of masquerading as an empty collection.
+    pub fn try_iter(&self) -> Result<std::slice::Iter<'_, T>, Error> {
+        self.try_unwrap().map(|values| values.iter())
+    }
+
+    /// How many values are loaded. A failed edge errors like
+    /// [`try_unwrap`](#method.try_unwrap).
+    pub fn len(&self) -> Result<usize, Error> {
+        self.try_unwrap().map(Vec::len)
+    }
+
+    /// Is the list of loaded values empty? A failed edge errors like
+    /// [`try_unwrap`](#method.try_unwrap).
+    pub fn is_empty(&self) -> Result<bool, Error> {
+        self.try_unwrap().map(Vec::is_empty)
+    }
 }
 
 /// A "has many through" association.
@@ -749,41 +1541,280 @@ impl<T> HasMany<T> {
 /// | `foreign_key_field` | The field on the join model that contains the parent models id | `{name of parent type in lowercase}_id` | `foreign_key_field = "car_id"` |
 /// | `graphql_field` | The name of this field in your GraphQL schema | `{name of field}` | `graphql_field = "country"` |
 /// | `predicate_method` | Method used to filter child associations. This can be used if you only want to include a subset of the models. This method will be called to filter the join models. | N/A (attribute is optional) | `predicate_method = "a_predicate_method"` |
+/// | `order_by_method` | Method called on the child model that returns the sort key children are ordered by within each parent's list. Sorting is ascending and stable; return [`std::cmp::Reverse`] for descending | N/A (attribute is optional) | `order_by_method = "created_at"` |
+/// | `paginate_with` | Function from the query trail to an [`Option<Pagination>`](struct.Pagination.html) window applied per parent when attaching children | N/A (attribute is optional) | `paginate_with = "cars_pagination"` |
+/// | `on_missing` | What to do when a parent has no children. `error` fails the whole load with [`Error::MissingChildren`](enum.Error.html#variant.MissingChildren), which requires your error type to implement `From<juniper_eager_loading::Error>`. `skip` keeps the default behavior | `skip` | `on_missing = "error"` |
 ///
 /// # Errors
 ///
-/// [`try_unwrap`][] will never error. If the association wasn't loaded or wasn't found it will
-/// return `Ok(vec![])`.
+/// [`try_unwrap`][] errors only if the edge was explicitly marked as failed with
+/// [`load_failed`][] — the derived code never does that. Otherwise it returns `Ok(vec![])`,
+/// and in particular a parent that legitimately has zero children resolves to an empty list
+/// after eager loading, it doesn't error. A failure recorded with `load_failed_with` also
+/// carries [details](struct.LoadFailedDetails.html) about the ids involved.
 ///
+/// [`load_failed`]: struct.HasManyThrough.html#method.load_failed
 /// [`try_unwrap`]: struct.HasManyThrough.html#method.try_unwrap
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
-pub struct HasManyThrough<T>(Vec<T>);
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HasManyThrough<T> {
+    values: Vec<T>,
+    failed: bool,
+    // Only set through `load_failed_with`; like `HasOne`'s failure details it isn't
+    // serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    failure_details: Option<Box<LoadFailedDetails>>,
+    // Diagnostic metadata, like `HasOne`'s: not serialized and ignored by the comparisons
+    // below.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    location: Option<ErrorLocation>,
+}
 
-impl<T> Default for HasManyThrough<T> {
-    fn default() -> Self {
-        HasManyThrough(Vec::new())
+// Like `HasOne`, the location recorded by the derived code is diagnostic metadata and takes
+// no part in equality, ordering, or hashing.
+impl<T: PartialEq> PartialEq for HasManyThrough<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+            && self.failed == other.failed
+            && self.failure_details == other.failure_details
     }
 }
 
-impl<T> HasManyThrough<T> {
-    /// Borrow the loaded values. If no values have been loaded it will return an empty list.
-    /// It will not return an error.
-    pub fn try_unwrap(&self) -> Result<&Vec<T>, Error> {
-        Ok(&self.0)
+impl<T: Eq> Eq for HasManyThrough<T> {}
+
+impl<T: PartialOrd> PartialOrd for HasManyThrough<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        (&self.values, &self.failed, &self.failure_details).partial_cmp(&(
+            &other.values,
+            &other.failed,
+            &other.failure_details,
+        ))
     }
+}
 
-    /// Add the loaded value to the list.
-    pub fn loaded(&mut self, inner: T) {
-        self.0.push(inner);
+impl<T: Ord> Ord for HasManyThrough<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.values, &self.failed, &self.failure_details).cmp(&(
+            &other.values,
+            &other.failed,
+            &other.failure_details,
+        ))
     }
+}
 
-    /// This function doesn't do anything since the default is an empty list and there is no error
-    /// state.
-    pub fn assert_loaded_otherwise_failed(&mut self) {}
+impl<T: Hash> Hash for HasManyThrough<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.values.hash(state);
+        self.failed.hash(state);
+        self.failure_details.hash(state);
+    }
 }
 
-/// A GraphQL type backed by a model object.
-///
+impl<T> Default for HasManyThrough<T> {
+    fn default() -> Self {
+        HasManyThrough {
+            values: Vec::new(),
+            failed: false,
+            failure_details: None,
+            location: None,
+        }
+    }
+}
+
+impl<T> HasManyThrough<T> {
+    /// Create a default, empty edge that remembers which type and field it sits in, so errors
+    /// carry their [location](struct.ErrorLocation.html). The derived code builds edges this
+    /// way.
+    pub fn new_for_field(parent_type: &'static str, field_name: &'static str) -> Self {
+        HasManyThrough {
+            location: Some(ErrorLocation {
+                parent_type,
+                field_name,
+            }),
+            ..HasManyThrough::default()
+        }
+    }
+
+    /// Borrow the loaded values. If no values have been loaded it will return an empty list.
+    /// It only errors if the edge was explicitly marked with
+    /// [`load_failed`](#method.load_failed).
+    pub fn try_unwrap(&self) -> Result<&Vec<T>, Error> {
+        if let Some(details) = &self.failure_details {
+            Err(Error::LoadFailedForIds(
+                AssociationType::HasManyThrough,
+                (**details).clone(),
+            ))
+        } else if self.failed {
+            Err(Error::LoadFailed(
+                AssociationType::HasManyThrough,
+                self.location,
+            ))
+        } else {
+            Ok(&self.values)
+        }
+    }
+
+    /// Add the loaded value to the list.
+    pub fn loaded(&mut self, inner: T) {
+        self.values.push(inner);
+    }
+
+    /// Set the whole list of loaded values at once, replacing anything loaded before.
+    ///
+    /// Unlike calling [`loaded`](#method.loaded) in a loop this is idempotent: running the same
+    /// load twice — during a retry, say — leaves the edge with one copy of each child instead of
+    /// accumulating duplicates. It also clears a failure marked with
+    /// [`load_failed`](#method.load_failed), since the retry evidently succeeded.
+    pub fn loaded_all(&mut self, values: Vec<T>) {
+        self.values = values;
+        self.failed = false;
+        self.failure_details = None;
+    }
+
+    /// Extend the list with a batch of loaded values, keeping anything loaded before.
+    ///
+    /// See [`HasMany::append`](struct.HasMany.html#method.append).
+    pub fn append(&mut self, values: impl IntoIterator<Item = T>) {
+        self.values.extend(values);
+    }
+
+    /// Reorder the loaded values in place by the given key, with a stable sort.
+    ///
+    /// See [`HasMany::sort_by_key`](struct.HasMany.html#method.sort_by_key).
+    pub fn sort_by_key<K, F>(&mut self, key: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.values.sort_by_key(key);
+    }
+
+    /// Mark the association as failed to load, after which
+    /// [`try_unwrap`](#method.try_unwrap) returns
+    /// [`Error::LoadFailed`](enum.Error.html#variant.LoadFailed) instead of an empty list.
+    ///
+    /// Like [`HasMany::load_failed`](struct.HasMany.html#method.load_failed) this is only for
+    /// manual implementations — the derived code never calls it.
+    pub fn load_failed(&mut self) {
+        self.failed = true;
+    }
+
+    /// Like [`load_failed`](#method.load_failed), but record
+    /// [details](struct.LoadFailedDetails.html) about which ids were involved.
+    ///
+    /// See [`HasMany::load_failed_with`](struct.HasMany.html#method.load_failed_with).
+    pub fn load_failed_with(&mut self, details: LoadFailedDetails) {
+        self.failed = true;
+        self.failure_details = Some(Box::new(details));
+    }
+
+    /// This function doesn't do anything since the default is an empty list. Failures are only
+    /// recorded through [`load_failed`](#method.load_failed).
+    pub fn assert_loaded_otherwise_failed(&mut self) {}
+
+    /// Map every loaded value, preserving the edge's state.
+    pub fn map<U, F>(self, f: F) -> HasManyThrough<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        HasManyThrough {
+            values: self.values.into_iter().map(f).collect(),
+            failed: self.failed,
+            failure_details: self.failure_details,
+            location: self.location,
+        }
+    }
+
+    /// Borrow the edge as `HasManyThrough<&T>`, preserving its state.
+    pub fn as_ref(&self) -> HasManyThrough<&T> {
+        HasManyThrough {
+            values: self.values.iter().collect(),
+            failed: self.failed,
+            failure_details: self.failure_details.clone(),
+            location: self.location,
+        }
+    }
+
+    /// Are any values loaded? An empty list counts as not loaded, since the two are
+    /// indistinguishable for a list edge.
+    pub fn is_loaded(&self) -> bool {
+        !self.values.is_empty()
+    }
+
+    /// Is the list empty and the edge not failed?
+    pub fn is_not_loaded(&self) -> bool {
+        self.values.is_empty() && !self.failed
+    }
+
+    /// Was the edge marked as failed with [`load_failed`](#method.load_failed)?
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+
+    /// Borrow the loaded values, panicking with the given message if the edge is empty or
+    /// failed. Unlike [`try_unwrap`](#method.try_unwrap) an empty list panics here: the point
+    /// is asserting that eager loading actually populated the field.
+    ///
+    /// See [`HasOne::expect_loaded`](struct.HasOne.html#method.expect_loaded) for when to
+    /// reach for this.
+    pub fn expect_loaded(&self, msg: &str) -> &Vec<T> {
+        if let Err(err) = self.try_unwrap() {
+            panic!(
+                "{}: `HasManyThrough<{}>`: {}",
+                msg,
+                std::any::type_name::<T>(),
+                err,
+            );
+        }
+        if self.values.is_empty() {
+            panic!(
+                "{}: `HasManyThrough<{}>` should have been eager loaded, but is empty",
+                msg,
+                std::any::type_name::<T>(),
+            );
+        }
+        &self.values
+    }
+
+    /// Move the loaded values out of the edge. The error cases mirror
+    /// [`try_unwrap`](#method.try_unwrap).
+    pub fn into_inner(self) -> Result<Vec<T>, Error> {
+        self.try_unwrap()?;
+        Ok(self.values)
+    }
+
+    /// Move the loaded values out of the edge, leaving a not-loaded edge behind.
+    ///
+    /// If the edge was marked as failed it is left untouched and the error mirrors
+    /// [`try_unwrap`](#method.try_unwrap).
+    pub fn take(&mut self) -> Result<Vec<T>, Error> {
+        self.try_unwrap()?;
+        Ok(std::mem::take(&mut self.values))
+    }
+
+    /// Iterate over the loaded values.
+    ///
+    /// This is deliberately `Result`-flavored: a failed edge errors like
+    /// [`try_unwrap`](#method.try_unwrap) instead of masquerading as an empty collection.
+    pub fn try_iter(&self) -> Result<std::slice::Iter<'_, T>, Error> {
+        self.try_unwrap().map(|values| values.iter())
+    }
+
+    /// How many values are loaded. A failed edge errors like
+    /// [`try_unwrap`](#method.try_unwrap).
+    pub fn len(&self) -> Result<usize, Error> {
+        self.try_unwrap().map(Vec::len)
+    }
+
+    /// Is the list of loaded values empty? A failed edge errors like
+    /// [`try_unwrap`](#method.try_unwrap).
+    pub fn is_empty(&self) -> Result<bool, Error> {
+        self.try_unwrap().map(Vec::is_empty)
+    }
+}
+
+/// A GraphQL type backed by a model object.
+///
 /// You shouldn't need to implement this trait yourself even when customizing eager loading.
 pub trait GraphqlNodeForModel: Sized {
     /// The model type.
@@ -794,6 +1825,12 @@ pub trait GraphqlNodeForModel: Sized {
 
     /// The connection type required to do the loading. This can be a database connection or maybe
     /// a connection an external web service.
+    ///
+    /// There are deliberately no bounds on this type, and the machinery only ever takes
+    /// `&Self::Connection`. So it can also be a trait object such as
+    /// `Box<dyn Database + Send + Sync>`, letting production pass a real connection while tests
+    /// substitute a boxed fake without changing the node types. See
+    /// "examples/dyn_connection.rs" for a complete setup.
     type Connection;
 
     /// The error type.
@@ -802,6 +1839,16 @@ pub trait GraphqlNodeForModel: Sized {
     /// Create a new GraphQL type from a model.
     fn new_from_model(model: &Self::Model) -> Self;
 
+    /// Create a new GraphQL type from an owned model, moving the model in.
+    ///
+    /// The default clones through [`new_from_model`](#tymethod.new_from_model), which is
+    /// always correct. The derive overrides it to actually move the model into the node, which
+    /// matters when models carry large columns — the eager loading flow uses this for freshly
+    /// loaded children that nothing else references.
+    fn new_from_owned_model(model: Self::Model) -> Self {
+        Self::new_from_model(&model)
+    }
+
     /// Create a list of GraphQL types from a list of models.
     fn from_db_models(models: &[Self::Model]) -> Vec<Self> {
         models
@@ -809,6 +1856,15 @@ pub trait GraphqlNodeForModel: Sized {
             .map(|model| Self::new_from_model(model))
             .collect()
     }
+
+    /// Create a list of GraphQL types from an owned list of models, moving each model in.
+    ///
+    /// The owned counterpart of [`from_db_models`](#method.from_db_models), going through
+    /// [`new_from_owned_model`](#method.new_from_owned_model). Prefer this in resolvers that
+    /// own their model list anyway — with the derive it avoids cloning every model.
+    fn from_db_model_vec(models: Vec<Self::Model>) -> Vec<Self> {
+        models.into_iter().map(Self::new_from_owned_model).collect()
+    }
 }
 
 /// Trait used for generic constraint on [`QueryTrail`](https://docs.rs/juniper-from-schema/#query-trails)s
@@ -1058,13 +2114,11 @@ pub trait GenericQueryTrail<T, K> {}
 pub trait EagerLoadChildrenOfType<Child, QueryTrailT, Context, JoinModel = ()>
 where
     Self: GraphqlNodeForModel,
-    Child: GraphqlNodeForModel<
-            Connection = Self::Connection,
-            Error = Self::Error,
-        > + EagerLoadAllChildren<QueryTrailT>
+    Child: GraphqlNodeForModel<Connection = Self::Connection, Error = Self::Error>
+        + EagerLoadAllChildren<QueryTrailT>
         + Clone,
     QueryTrailT: GenericQueryTrail<Child, Walked>,
-    JoinModel: 'static + Clone + ?Sized,
+    JoinModel: 'static + Clone,
 {
     /// The id type the child uses. This will be different for the different [association types][].
     ///
@@ -1072,40 +2126,243 @@ where
     type ChildId: Hash + Eq;
 
     /// Given a list of models, load either the list of child ids or child models associated.
+    #[allow(clippy::type_complexity)]
     fn child_ids(
         models: &[Self::Model],
         db: &Self::Connection,
     ) -> Result<LoadResult<Self::ChildId, (Child::Model, JoinModel)>, Self::Error>;
 
     /// Load a list of children from a list of ids.
+    ///
+    /// The default flow deduplicates the ids (keeping first-seen order) before calling this,
+    /// so implementations see each id exactly once even when many parents share a child.
     fn load_children(
         ids: &[Self::ChildId],
         db: &Self::Connection,
     ) -> Result<Vec<Child::Model>, Self::Error>;
 
+    /// Like [`child_ids`](#tymethod.child_ids), but with access to the query trail.
+    ///
+    /// This is what [`eager_load_children`](#method.eager_load_children) actually calls; the
+    /// default delegates to `child_ids`, so associations that don't care about the trail don't
+    /// need to implement it. Override it in a manual implementation when the load depends on
+    /// what the client asked for — for example filtering `comments(onlyPublished: true)` in
+    /// the query instead of in memory. The query trail doesn't currently expose GraphQL field
+    /// arguments, so a custom trail type (or wherever the resolver stashed the arguments) has
+    /// to carry them.
+    #[allow(clippy::type_complexity)]
+    fn child_ids_with_trail(
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+    ) -> Result<LoadResult<Self::ChildId, (Child::Model, JoinModel)>, Self::Error> {
+        let _ = trail;
+        Self::child_ids(models, db)
+    }
+
+    /// Like [`load_children`](#tymethod.load_children), but with access to the query trail.
+    ///
+    /// The default delegates to `load_children`. See
+    /// [`child_ids_with_trail`](#method.child_ids_with_trail) for when to override it.
+    fn load_children_with_trail(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+    ) -> Result<Vec<Child::Model>, Self::Error> {
+        let _ = trail;
+        Self::load_children(ids, db)
+    }
+
+    /// Is this association selected by the query?
+    ///
+    /// The derived `eager_load_all_children_for_each` walks the query trail to this
+    /// association's field and consults this with the result before descending. The default
+    /// is the trail gate itself: `Some` means the client selected the field. Either way the
+    /// descent still requires the walked trail, so overriding this can only restrict loading
+    /// further — it cannot force an unselected association to load, which is the guarantee
+    /// that an unselected child table is never queried. It's callable directly so generated
+    /// implementations can be unit tested against a trail built in a test resolver.
+    fn association_selected(child_trail: Option<&QueryTrailT>) -> bool {
+        child_trail.is_some()
+    }
+
     /// Does this parent and this child belong together?
     fn is_child_of(parent: &Self, child: &(Child, &JoinModel)) -> bool;
 
+    /// A hash of the key this parent matches children on, for hash-based matching.
+    ///
+    /// When [`child_match_hash`](#method.child_match_hash) returns `Some`,
+    /// [`eager_load_children`](#method.eager_load_children) groups the parents by this hash
+    /// once and looks each child's candidate parents up in O(1) instead of scanning every
+    /// parent with [`is_child_of`](#tymethod.is_child_of). The candidates are still confirmed
+    /// with `is_child_of`, so hash collisions only cost extra comparisons — but a parent whose
+    /// hash doesn't equal the child's is never considered, so both sides must hash the same
+    /// key value through [`match_hash`](fn.match_hash.html). Returning `None` means this
+    /// parent has no key (for example a `None` foreign key) and matches no hashed child.
+    ///
+    /// The defaults return `None`, which keeps the pairwise scan. The derive overrides both
+    /// methods with the foreign key comparison it also generates `is_child_of` from.
+    fn parent_match_hash(parent: &Self) -> Option<u64> {
+        let _ = parent;
+        None
+    }
+
+    /// A hash of the key this child matches parents on, for hash-based matching.
+    ///
+    /// See [`parent_match_hash`](#method.parent_match_hash). Returning `None` for a child
+    /// falls back to the pairwise `is_child_of` scan for that child.
+    fn child_match_hash(child: &(Child, &JoinModel)) -> Option<u64> {
+        let _ = child;
+        None
+    }
+
     /// Store the loaded child on the association.
     fn loaded_child(node: &mut Self, child: Child);
 
+    /// Store a shared, eager loaded child on the association.
+    ///
+    /// The default implementation clones the child out of the [`Arc`] and calls
+    /// [`loaded_child`](#tymethod.loaded_child), which is the right thing when associations own
+    /// their children. Node types whose association fields hold `Arc<Child>` — see the derive's `shared`
+    /// field attribute — store the `Arc` directly instead, so the one canonical
+    /// child instance built per distinct id is shared by all its parents rather than cloned into
+    /// each of them.
+    ///
+    /// [`Arc`]: https://doc.rust-lang.org/std/sync/struct.Arc.html
+    fn loaded_shared_child(node: &mut Self, child: Arc<Child>) {
+        Self::loaded_child(node, (*child).clone());
+    }
+
+    /// Store a whole batch of loaded children on the association in one call.
+    ///
+    /// The default implementation calls [`loaded_child`](#tymethod.loaded_child) once per
+    /// child, which is always correct. Manual implementations backed by a list edge can
+    /// override it to hand the batch straight to
+    /// [`HasMany::append`](struct.HasMany.html#method.append) instead of pushing one child at
+    /// a time.
+    fn loaded_children(node: &mut Self, children: Vec<Child>) {
+        for child in children {
+            Self::loaded_child(node, child);
+        }
+    }
+
+    /// Order the loaded child models before they're matched to parents.
+    ///
+    /// The default does nothing, which keeps whatever order the loader returned. Since the
+    /// attachment in [`eager_load_children`](#method.eager_load_children) preserves this
+    /// order, sorting here determines the order children end up in within each parent's list
+    /// edge. The derive calls a method on the child model for this when the association has an
+    /// `order_by_method` attribute; the hook runs after the models have been assembled —
+    /// whether they came from the loader or a cache — so the order survives cache hits.
+    fn order_children(child_models: &mut Vec<(Child::Model, JoinModel)>) {
+        let _ = child_models;
+    }
+
+    /// The per-parent window to apply when matching children to parents, if any.
+    ///
+    /// The default is `None`, meaning every matching child is attached. The derive overrides
+    /// this when the association has a `paginate_with` attribute, which names a function from
+    /// the query trail to an [`Option<Pagination>`](struct.Pagination.html). The query trail
+    /// doesn't currently expose GraphQL field arguments, so that function has to get `first`
+    /// and `offset` from wherever the resolver stashed them; once trails carry arguments it
+    /// can read them directly.
+    fn pagination(trail: &QueryTrailT) -> Option<Pagination> {
+        let _ = trail;
+        None
+    }
+
     /// The association should have been loaded by now, if not store an error inside the
     /// association (if applicable for the particular association).
     fn assert_loaded_otherwise_failed(node: &mut Self);
 
+    /// Called after matching with the models of the parents that no child matched.
+    ///
+    /// The default is permissive and does nothing, which keeps the regular behavior of each
+    /// association kind: [`HasOne`](struct.HasOne.html) edges error on access,
+    /// [`OptionHasOne`](struct.OptionHasOne.html) stays `None`, and the list edges stay empty.
+    /// Return an error — or set `on_missing = "error"` in the derive — to instead fail the
+    /// whole load when a foreign key points at a child that doesn't exist.
+    fn on_missing_children(unmatched_models: &[&Self::Model]) -> Result<(), Self::Error> {
+        let _ = unmatched_models;
+        Ok(())
+    }
+
     /// Combine all the methods above to eager load the children for a list of GraphQL values and
     /// models.
+    ///
+    /// # Input contract
+    ///
+    /// `nodes` and `models` must correspond index-wise: `nodes[i]` is the GraphQL value built
+    /// from `models[i]`, which is what [`from_db_models`][] produces. Duplicate models are
+    /// allowed — each duplicate node independently receives its children, and the id
+    /// deduplication that happens before loading doesn't change that.
+    ///
+    /// [`from_db_models`]: trait.GraphqlNodeForModel.html#method.from_db_models
+    ///
+    /// # Ordering
+    ///
+    /// The result is deterministic: nothing in the default flow depends on `HashMap` iteration
+    /// order. Nodes keep the order of the input models, children are attached in the order the
+    /// loader returned them, and [`unique`](fn.unique.html) keeps the first occurrence of each
+    /// id. Custom implementations of the individual methods should preserve this property —
+    /// either follow input order or sort explicitly.
+    ///
+    /// # Complexity
+    ///
+    /// When the association supplies match hashes — the derive always does, see
+    /// [`parent_match_hash`](#method.parent_match_hash) — the parents are grouped by hash once
+    /// and each child only runs [`is_child_of`](#tymethod.is_child_of) against its candidate
+    /// bucket, making a pass `O(parents + children)` expected. Without hashes the matching
+    /// runs `is_child_of` for every (parent, child) pair, so a pass is
+    /// `O(parents × children)` comparisons. Everything else is linear, each node is
+    /// constructed once and moved (or cloned once per extra parent) into its edge. The
+    /// `complexity_guard` integration test pins the end-to-end cost of a large pass so changes
+    /// to this method can't silently regress it.
     fn eager_load_children(
         nodes: &mut [Self],
         models: &[Self::Model],
         db: &Self::Connection,
         trail: &QueryTrailT,
     ) -> Result<(), Self::Error> {
-        let child_models = match Self::child_ids(models, db)? {
+        Self::eager_load_children_with_stats(nodes, models, db, trail, &mut LoadStats::default())
+    }
+
+    /// Like [`eager_load_children`](#method.eager_load_children), but records what the pass
+    /// cost — and what its nested passes cost — into the given
+    /// [`LoadStats`](struct.LoadStats.html).
+    fn eager_load_children_with_stats(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+        stats: &mut LoadStats,
+    ) -> Result<(), Self::Error> {
+        debug_assert_eq!(
+            nodes.len(),
+            models.len(),
+            "`nodes` and `models` must correspond index-wise",
+        );
+
+        let mut association_stats = AssociationLoadStats {
+            child_type: std::any::type_name::<Child>(),
+            ..AssociationLoadStats::default()
+        };
+
+        let child_models = match Self::child_ids_with_trail(models, db, trail)? {
             LoadResult::Ids(child_ids) => {
                 assert!(same_type::<JoinModel, ()>());
 
-                let loaded_models = Self::load_children(&child_ids, db)?;
+                // Many parents sharing few children is the common case, so the raw id list is
+                // full of repeats. Dedupe here so loaders (and any caches behind them) only
+                // ever see each id once, whatever `child_ids` returned.
+                let child_ids = unique(child_ids);
+                association_stats.ids_requested = child_ids.len();
+                // With no ids there's nothing for the loader to do — derived
+                // implementations return early without a query.
+                if !child_ids.is_empty() {
+                    association_stats.batches_issued = 1;
+                }
+                let loaded_models = Self::load_children_with_trail(&child_ids, db, trail)?;
                 loaded_models
                     .into_iter()
                     .map(|model| {
@@ -1123,51 +2380,214 @@ where
                     })
                     .collect::<Vec<_>>()
             }
-            LoadResult::Models(model_and_join_pairs) => model_and_join_pairs,
+            LoadResult::Models(model_and_join_pairs) => {
+                // `child_ids` loaded the models itself, in one batch as far as the flow can
+                // see — `HasManyThrough` may issue several queries within it.
+                association_stats.batches_issued = 1;
+                model_and_join_pairs
+            }
         };
 
-        let children = child_models
-            .iter()
-            .map(|child_model| (Child::new_from_model(&child_model.0), child_model.1.clone()))
-            .collect::<Vec<_>>();
-
-        let mut children_without_join_models =
-            children.iter().map(|x| x.0.clone()).collect::<Vec<_>>();
+        Self::eager_load_children_from_models(
+            nodes,
+            models,
+            db,
+            trail,
+            stats,
+            association_stats,
+            child_models,
+        )
+    }
 
-        let child_models_without_join_models =
-            child_models.iter().map(|x| x.0.clone()).collect::<Vec<_>>();
+    /// Like [`eager_load_children_with_stats`](#method.eager_load_children_with_stats), but
+    /// matching already-loaded child models instead of loading them.
+    ///
+    /// `association_stats` describes what producing `child_models` cost; the row count is
+    /// filled in and the entry recorded before matching. The derived
+    /// `eager_load_all_children_for_each` calls this when several associations on one parent
+    /// target the same child type: their foreign keys are unioned and loaded in one batch,
+    /// and each field then matches against the shared rows. Rows a field doesn't reference
+    /// are dropped by the matching, just like over-fetched loader results.
+    fn eager_load_children_from_models(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+        stats: &mut LoadStats,
+        mut association_stats: AssociationLoadStats,
+        mut child_models: Vec<(Child::Model, JoinModel)>,
+    ) -> Result<(), Self::Error> {
+        debug_assert_eq!(
+            nodes.len(),
+            models.len(),
+            "`nodes` and `models` must correspond index-wise",
+        );
+
+        association_stats.rows_loaded = child_models.len();
+        stats.associations.push(association_stats);
+
+        // Runs after the models have been assembled — whichever of `child_ids` and
+        // `load_children` produced them, and whether they came from a loader or a cache — so a
+        // chosen order survives cache hits. The attachment loop below preserves it.
+        Self::order_children(&mut child_models);
+
+        let pagination = Self::pagination(trail);
+        // Zero-based position of the next matching child, per parent. Only tracked when a
+        // window is actually applied.
+        let mut window_positions = pagination.map(|_| vec![0_usize; nodes.len()]);
+
+        // One buffer of child nodes is built here and flows through the nested eager loading
+        // below straight into the attachment loop. Earlier versions cloned the nodes into a
+        // second buffer before recursing and rebuilt a third one afterwards, which in deep
+        // traversals meant every level cloned and re-collected its whole subtree.
+        //
+        // Matching happens before the nested loading so that models no parent references —
+        // loaders can over-fetch, for example when a scoped query returns rows for ids outside
+        // this page — are dropped here instead of having their whole subtree eager loaded for
+        // nothing. The parent indices each child matched are remembered in one flat buffer and
+        // reused for the attachment after the recursion.
+        //
+        // Nested selections need the child models again for the recursion below, so in that
+        // case each model is kept (moved, not cloned) alongside its node. Without nested
+        // selections the model is moved straight into the node instead — for freshly loaded
+        // children that's zero model clones end to end.
+        let nested_selections = Child::has_nested_selections(trail);
+
+        let mut children = Vec::with_capacity(child_models.len());
+        let mut referenced_models = Vec::with_capacity(if nested_selections {
+            child_models.len()
+        } else {
+            0
+        });
+        let mut match_ranges = Vec::with_capacity(child_models.len());
+        let mut matched_parents = Vec::with_capacity(nodes.len());
+        let mut parent_matched = vec![false; nodes.len()];
+
+        // Parents grouped by the hash of the key they match children on, built lazily the
+        // first time a child supplies a hash. The buckets hold parent indices in node order,
+        // and every candidate is confirmed with `is_child_of`, so the fast path matches
+        // exactly what the pairwise scan would — hash collisions only cost comparisons.
+        let mut parents_by_hash: Option<HashMap<u64, Vec<usize>>> = None;
+
+        for (child_model, join_model) in child_models {
+            let (child_node, kept_model) = if nested_selections {
+                (Child::new_from_model(&child_model), Some(child_model))
+            } else {
+                (Child::new_from_owned_model(child_model), None)
+            };
+            let child = (child_node, &join_model);
+
+            let start = matched_parents.len();
+            if let Some(hash) = Self::child_match_hash(&child) {
+                let buckets = parents_by_hash.get_or_insert_with(|| {
+                    let mut buckets: HashMap<u64, Vec<usize>> = HashMap::with_capacity(nodes.len());
+                    for (idx, node) in nodes.iter().enumerate() {
+                        if let Some(hash) = Self::parent_match_hash(node) {
+                            buckets.entry(hash).or_default().push(idx);
+                        }
+                    }
+                    buckets
+                });
+
+                if let Some(candidates) = buckets.get(&hash) {
+                    matched_parents.extend(
+                        candidates
+                            .iter()
+                            .copied()
+                            .filter(|&idx| Self::is_child_of(&nodes[idx], &child)),
+                    );
+                }
+            } else {
+                matched_parents.extend(
+                    nodes
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, node)| Self::is_child_of(node, &child))
+                        .map(|(idx, _)| idx),
+                );
+            }
 
-        let len_before = child_models_without_join_models.len();
+            if matched_parents.len() == start {
+                continue;
+            }
 
-        Child::eager_load_all_children_for_each(
-            &mut children_without_join_models,
-            &child_models_without_join_models,
-            db,
-            trail,
-        )?;
+            for &idx in &matched_parents[start..] {
+                parent_matched[idx] = true;
+            }
 
-        assert_eq!(len_before, child_models_without_join_models.len());
+            // `parent_matched` above is updated before windowing: a parent whose children all
+            // fall outside the window still has children as far as `on_missing_children` is
+            // concerned. Children the window excludes for every parent are dropped here, like
+            // over-fetched models, before their subtree is eager loaded.
+            if let (Some(pagination), Some(positions)) = (&pagination, window_positions.as_mut()) {
+                let mut keep = start;
+                for i in start..matched_parents.len() {
+                    let idx = matched_parents[i];
+                    let position = positions[idx];
+                    positions[idx] += 1;
+                    if pagination.contains(position) {
+                        matched_parents[keep] = idx;
+                        keep += 1;
+                    }
+                }
+                matched_parents.truncate(keep);
+
+                if matched_parents.len() == start {
+                    continue;
+                }
+            }
 
-        let children = children_without_join_models
-            .into_iter()
-            .enumerate()
-            .map(|(idx, child)| {
-                let join_model = &children[idx].1;
-                (child, join_model)
-            })
-            .collect::<Vec<_>>();
+            children.push(child.0);
+            if let Some(model) = kept_model {
+                referenced_models.push(model);
+            }
+            match_ranges.push((start, matched_parents.len()));
+        }
 
-        for node in nodes {
-            let matching_children = children
+        if parent_matched.iter().any(|matched| !matched) {
+            let unmatched = models
                 .iter()
-                .filter(|child_model| Self::is_child_of(node, child_model))
-                .cloned()
+                .zip(&parent_matched)
+                .filter(|(_, matched)| !**matched)
+                .map(|(model, _)| model)
                 .collect::<Vec<_>>();
+            Self::on_missing_children(&unmatched)?;
+        }
+
+        if nested_selections {
+            let len_before = referenced_models.len();
 
-            for child in matching_children {
-                Self::loaded_child(node, child.0);
+            Child::eager_load_all_children_for_each_with_stats(
+                &mut children,
+                &referenced_models,
+                db,
+                trail,
+                stats,
+            )?;
+
+            assert_eq!(len_before, referenced_models.len());
+        }
+
+        for (child, (start, end)) in children.into_iter().zip(match_ranges) {
+            let parents = &matched_parents[start..end];
+
+            if let [idx] = *parents {
+                // The dominant belongs-to case: the child has exactly one parent, so it can be
+                // moved straight into the edge without cloning anything.
+                Self::loaded_child(&mut nodes[idx], child);
+            } else {
+                // Each genuinely shared child is built once and shared into all its parents.
+                // Parents that don't opt into sharing clone it out of the `Arc` in
+                // `loaded_shared_child`, which is no worse than the clone per parent they'd
+                // otherwise get.
+                let child = Arc::new(child);
+                for &idx in parents {
+                    Self::loaded_shared_child(&mut nodes[idx], Arc::clone(&child));
+                }
             }
+        }
 
+        for node in nodes {
             Self::assert_loaded_otherwise_failed(node);
         }
 
@@ -1176,7 +2596,7 @@ where
 }
 
 /// Are two types the same?
-fn same_type<A: 'static, B: 'static>() -> bool {
+pub(crate) fn same_type<A: 'static, B: 'static>() -> bool {
     use std::any::TypeId;
     TypeId::of::<A>() == TypeId::of::<B>()
 }
@@ -1203,6 +2623,99 @@ pub enum LoadResult<A, B> {
     Models(Vec<B>),
 }
 
+/// A per-parent window applied to list associations during eager loading.
+///
+/// Returned from [`EagerLoadChildrenOfType::pagination`][] — usually through the derive's
+/// `paginate_with` attribute — and applied in
+/// [`eager_load_children`](trait.EagerLoadChildrenOfType.html#method.eager_load_children): each
+/// parent independently skips its first `offset` matching children and keeps at most `first` of
+/// the rest. Children excluded for every parent are dropped before any nested eager loading.
+///
+/// The window follows the order children are matched in, so combining this with the
+/// `order_by_method` attribute gives "the first `n` children by that order" per parent.
+///
+/// [`EagerLoadChildrenOfType::pagination`]: trait.EagerLoadChildrenOfType.html#method.pagination
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Pagination {
+    /// Keep at most this many children per parent. `None` means no limit.
+    pub first: Option<usize>,
+
+    /// Skip this many children per parent before keeping any. `None` means skip none.
+    pub offset: Option<usize>,
+}
+
+impl Pagination {
+    /// Is the zero-based index of a matched child within the window?
+    pub fn contains(&self, index: usize) -> bool {
+        let offset = self.offset.unwrap_or(0);
+        index >= offset && self.first.is_none_or(|first| index < offset + first)
+    }
+}
+
+/// Statistics accumulated while eager loading, for per-request observability.
+///
+/// Pass a `&mut LoadStats` to
+/// [`eager_load_all_children_for_each_with_stats`](trait.EagerLoadAllChildren.html#method.eager_load_all_children_for_each_with_stats)
+/// and every association the traversal visits — nested ones included, in the order they were
+/// loaded — appends an [`AssociationLoadStats`](struct.AssociationLoadStats.html) entry. Use
+/// it to assert query counts in tests ("this GraphQL query issued exactly 3 loads") or to log
+/// what a production request cost.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct LoadStats {
+    /// One entry per association pass, in traversal order.
+    pub associations: Vec<AssociationLoadStats>,
+}
+
+impl LoadStats {
+    /// Total loader batches issued across all associations.
+    pub fn batches_issued(&self) -> usize {
+        self.associations
+            .iter()
+            .map(|stats| stats.batches_issued)
+            .sum()
+    }
+
+    /// Total unique ids requested across all associations.
+    pub fn ids_requested(&self) -> usize {
+        self.associations
+            .iter()
+            .map(|stats| stats.ids_requested)
+            .sum()
+    }
+
+    /// Total rows loaded across all associations.
+    pub fn rows_loaded(&self) -> usize {
+        self.associations
+            .iter()
+            .map(|stats| stats.rows_loaded)
+            .sum()
+    }
+}
+
+/// What one association pass of [`eager_load_children`][] cost.
+///
+/// A batch is one loader invocation as the flow sees it: the `load_children` call on the id
+/// pathway, or the `child_ids` call when the association loads models directly. An
+/// association whose `child_ids` issues several queries internally — `HasManyThrough` loads
+/// the join models and the children — still counts as one batch.
+///
+/// [`eager_load_children`]: trait.EagerLoadChildrenOfType.html#method.eager_load_children
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct AssociationLoadStats {
+    /// The child node type loaded, as reported by [`std::any::type_name`].
+    pub child_type: &'static str,
+
+    /// Loader batches issued. Zero when there were no ids to load.
+    pub batches_issued: usize,
+
+    /// Unique ids passed to `load_children`. Zero on the model-loading pathway, where the
+    /// parents' models are the input instead of ids.
+    pub ids_requested: usize,
+
+    /// Child rows that came back, before matching drops over-fetched ones.
+    pub rows_loaded: usize,
+}
+
 /// The main entry point trait for doing eager loading.
 ///
 /// You shouldn't need to implement this trait yourself even when customizing eager loading.
@@ -1225,21 +2738,243 @@ where
         trail: &QueryTrailT,
     ) -> Result<(), Self::Error>;
 
+    /// Like [`eager_load_all_children_for_each`](#tymethod.eager_load_all_children_for_each),
+    /// but accumulates [`LoadStats`](struct.LoadStats.html) for every association the
+    /// traversal visits, nested ones included.
+    ///
+    /// The derive overrides this to thread the stats through; this conservative default for
+    /// manual implementations loads the same way and records nothing.
+    fn eager_load_all_children_for_each_with_stats(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+        stats: &mut LoadStats,
+    ) -> Result<(), Self::Error> {
+        let _ = stats;
+        Self::eager_load_all_children_for_each(nodes, models, db, trail)
+    }
+
+    /// Does the trail select any of this type's associations?
+    ///
+    /// [`eager_load_children`][] uses this to skip the nested
+    /// [`eager_load_all_children_for_each`](#tymethod.eager_load_all_children_for_each) call
+    /// entirely when the query only selects scalar fields of the children. The derive overrides
+    /// this with a check of the walked trail per association; the conservative default says
+    /// there's always something selected.
+    ///
+    /// [`eager_load_children`]: trait.EagerLoadChildrenOfType.html#method.eager_load_children
+    fn has_nested_selections(trail: &QueryTrailT) -> bool {
+        let _ = trail;
+        true
+    }
+
+    /// Build the nodes from the models and eager load all their children, in one call.
+    ///
+    /// This is [`from_db_models`] followed by
+    /// [`eager_load_all_children_for_each`](#tymethod.eager_load_all_children_for_each) — the
+    /// dance at the top of every list resolver. Returns an empty `Vec` without touching the
+    /// database when `models` is empty.
+    ///
+    /// [`from_db_models`]: trait.GraphqlNodeForModel.html#method.from_db_models
+    fn eager_load(
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+    ) -> Result<Vec<Self>, Self::Error> {
+        if models.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut nodes = Self::from_db_models(models);
+        Self::eager_load_all_children_for_each(&mut nodes, models, db, trail)?;
+        Ok(nodes)
+    }
+
     /// Perform eager loading for a single GraphQL value.
     ///
     /// This is the function you should call for eager loading associations of a single value.
+    /// The model is the one the node was built from; there's no slice to keep lined up.
     fn eager_load_all_children(
-        node: Self,
-        models: &[Self::Model],
+        mut node: Self,
+        model: &Self::Model,
         db: &Self::Connection,
         trail: &QueryTrailT,
     ) -> Result<Self, Self::Error> {
-        let mut nodes = vec![node];
-        Self::eager_load_all_children_for_each(&mut nodes, models, db, trail)?;
+        // One-element slices borrowed straight from the arguments: no `Vec` round-trip, and
+        // since `eager_load_all_children_for_each` only ever sees slices it cannot change the
+        // length out from under us.
+        Self::eager_load_all_children_for_each(
+            std::slice::from_mut(&mut node),
+            std::slice::from_ref(model),
+            db,
+            trail,
+        )?;
 
-        // This is safe because we just made a vec with exactly one element and
-        // eager_load_all_children_for_each doesn't remove things from the vec
-        Ok(nodes.remove(0))
+        Ok(node)
+    }
+
+    /// Like [`eager_load_all_children`](#method.eager_load_all_children), but builds the node
+    /// from the model first. The single-value counterpart of
+    /// [`eager_load`](#method.eager_load).
+    fn eager_load_all_children_from_model(
+        model: &Self::Model,
+        db: &Self::Connection,
+        trail: &QueryTrailT,
+    ) -> Result<Self, Self::Error> {
+        let node = Self::new_from_model(model);
+        Self::eager_load_all_children(node, model, db, trail)
+    }
+}
+
+/// Types that can describe what eager loading is going to do for them.
+///
+/// The [derive](derive.EagerLoading.html) implements this for you. Mostly useful for
+/// documentation and debugging: [`loading_plan`][] returns a [`LoadingPlan`](struct.LoadingPlan.html)
+/// that [renders](struct.LoadingPlan.html#method.render) as an indented tree of all the
+/// associations eager loading will consider.
+///
+/// [`loading_plan`]: trait.LoadingPlanned.html#method.loading_plan
+pub trait LoadingPlanned {
+    /// The loading plan for this type, following associations recursively.
+    ///
+    /// Cycles are detected by node type name: an association back to a type that's already in
+    /// the current branch is included, but not followed further.
+    fn loading_plan() -> LoadingPlan {
+        Self::loading_plan_into(&mut Vec::new())
+    }
+
+    /// Build the plan with an explicit stack of already-visited node types.
+    ///
+    /// This is what the derived code implements, you want
+    /// [`loading_plan`](trait.LoadingPlanned.html#method.loading_plan).
+    fn loading_plan_into(visited: &mut Vec<&'static str>) -> LoadingPlan;
+}
+
+/// Render the association graph of the given plans as Graphviz DOT text.
+///
+/// One graph node per GraphQL type, one labeled edge per association. Pass the plans of your
+/// schema's root types; everything reachable from them is included, shared types only once.
+///
+/// ```
+/// # use juniper_eager_loading::{loading_plan_dot, LoadingPlan};
+/// let dot = loading_plan_dot(&[LoadingPlan::new("User")]);
+/// assert!(dot.starts_with("digraph eager_loading {"));
+/// ```
+///
+/// Pipe the output through `dot -Tsvg` for a picture.
+pub fn loading_plan_dot(plans: &[LoadingPlan]) -> String {
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    for plan in plans {
+        plan.collect_dot(&mut nodes, &mut edges);
+    }
+
+    let mut out = String::from("digraph eager_loading {\n");
+    for node in nodes {
+        out.push_str(&format!("    \"{}\";\n", node));
+    }
+    for edge in edges {
+        out.push_str(&edge);
+        out.push('\n');
+    }
+    out.push('}');
+    out
+}
+
+/// What eager loading is going to do for a node type, as a tree of associations.
+///
+/// Get one from [`LoadingPlanned::loading_plan`](trait.LoadingPlanned.html#method.loading_plan)
+/// and print it with [`render`](struct.LoadingPlan.html#method.render).
+#[derive(Debug, Clone)]
+pub struct LoadingPlan {
+    /// The name of the node type the plan is for.
+    pub node_type: &'static str,
+    /// The associations of the node type, in field order.
+    pub edges: Vec<LoadingPlanEdge>,
+}
+
+/// One association in a [`LoadingPlan`](struct.LoadingPlan.html).
+#[derive(Debug, Clone)]
+pub struct LoadingPlanEdge {
+    /// The name of the field holding the association.
+    pub field_name: &'static str,
+    /// Which kind of association the field is.
+    pub edge_kind: AssociationType,
+    /// The name of the child node type.
+    pub child_type: &'static str,
+    /// The name of the foreign key field the association is matched on, when known.
+    pub foreign_key: Option<&'static str>,
+    /// Whether the query trail selects this branch. `None` when the plan was built without a
+    /// trail.
+    pub selected: Option<bool>,
+    /// The child's own plan. `None` when following it would cycle back to a type already in
+    /// the current branch.
+    pub child: Option<LoadingPlan>,
+}
+
+impl LoadingPlan {
+    /// Create a plan without any associations.
+    pub fn new(node_type: &'static str) -> Self {
+        LoadingPlan {
+            node_type,
+            edges: Vec::new(),
+        }
+    }
+
+    /// Render the plan as an indented tree, one association per line.
+    ///
+    /// Associations that cycle back up the tree are marked `(cycle)`, and associations the
+    /// trail doesn't select are marked `(not selected)` when the plan knows about a trail.
+    pub fn render(&self) -> String {
+        let mut out = self.node_type.to_string();
+        self.render_edges(1, &mut out);
+        out
+    }
+
+    fn collect_dot(&self, nodes: &mut Vec<&'static str>, edges: &mut Vec<String>) {
+        if !nodes.contains(&self.node_type) {
+            nodes.push(self.node_type);
+        }
+        for edge in &self.edges {
+            if !nodes.contains(&edge.child_type) {
+                nodes.push(edge.child_type);
+            }
+            let mut label = format!("{}: {:?}", edge.field_name, edge.edge_kind);
+            if let Some(foreign_key) = edge.foreign_key {
+                label.push_str(&format!(" (fk: {})", foreign_key));
+            }
+            let line = format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                self.node_type, edge.child_type, label,
+            );
+            if !edges.contains(&line) {
+                edges.push(line);
+            }
+            if let Some(child) = &edge.child {
+                child.collect_dot(nodes, edges);
+            }
+        }
+    }
+
+    fn render_edges(&self, depth: usize, out: &mut String) {
+        for edge in &self.edges {
+            out.push('\n');
+            out.push_str(&"   ".repeat(depth - 1));
+            out.push_str(&format!(
+                "└─ {}: {:?}<{}>",
+                edge.field_name, edge.edge_kind, edge.child_type,
+            ));
+            if edge.selected == Some(false) {
+                out.push_str(" (not selected)");
+            }
+            match &edge.child {
+                Some(child) => child.render_edges(depth + 1, out),
+                // A branch the trail doesn't select isn't followed, but that's not a cycle.
+                None if edge.selected == Some(false) => {}
+                None => out.push_str(" (cycle)"),
+            }
+        }
     }
 }
 
@@ -1259,51 +2994,421 @@ pub trait LoadFrom<T>: Sized {
     type Error;
 
     /// The connection type required to do the loading. This can be a database connection or maybe
-    /// a connection an external web service.
+    /// a connection an external web service. It can also be a trait object — see
+    /// [`GraphqlNodeForModel::Connection`](trait.GraphqlNodeForModel.html#associatedtype.Connection).
     type Connection;
 
     /// Perform the load.
     fn load(ids: &[T], db: &Self::Connection) -> Result<Vec<Self>, Self::Error>;
 }
 
+/// Loading `Arc<Model>` delegates to the model's own loader and wraps each row.
+///
+/// This is what makes `#[eager_loading(model = "Arc<models::Country>")]` work without any
+/// extra loader code: the node stores its model behind an [`Arc`], so the hundreds of parents
+/// a popular child ends up attached to share one allocation instead of each holding an
+/// independent clone of the model. The coherence rules wouldn't let downstream crates write
+/// this impl themselves, which is why it lives here.
+///
+/// [`Arc`]: https://doc.rust-lang.org/std/sync/struct.Arc.html
+impl<T, K> LoadFrom<K> for Arc<T>
+where
+    T: LoadFrom<K>,
+{
+    type Error = T::Error;
+    type Connection = T::Connection;
+
+    fn load(ids: &[K], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(T::load(ids, db)?.into_iter().map(Arc::new).collect())
+    }
+}
+
 /// The kinds of errors that can happen when doing eager loading.
+///
+/// The enum is `#[non_exhaustive]`: new kinds of errors can be added without a breaking change,
+/// so `match`es on it need a wildcard arm.
 #[derive(Debug)]
 #[allow(missing_copy_implementations)]
+#[non_exhaustive]
 pub enum Error {
     /// The association was not loaded.
     ///
     /// Did you forget to call
     /// [`eager_load_all_children_for_each`](trait.EagerLoadAllChildren.html#tymethod.eager_load_all_children_for_each)?
-    NotLoaded(AssociationType),
+    ///
+    /// The [location](struct.ErrorLocation.html) names the type and field involved when the
+    /// edge was built by the derived code; hand-built edges don't know where they live.
+    NotLoaded(AssociationType, Option<ErrorLocation>),
+
+    /// The association was intentionally skipped because the GraphQL field wasn't selected by
+    /// the query.
+    ///
+    /// Eager loading did the right thing, but some resolver code still accessed the edge.
+    /// Either stop touching the edge for queries that don't select the field, or make the
+    /// query select it.
+    NotRequested(AssociationType, Option<ErrorLocation>),
 
     /// Loading the association failed. This can only happen when using
     /// [`HasOne`](struct.HasOne.html). All the other association types have defaults.
-    LoadFailed(AssociationType),
+    LoadFailed(AssociationType, Option<ErrorLocation>),
+
+    /// Loading the association failed, and [details](struct.LoadFailedDetails.html) about which
+    /// ids were involved were recorded on the failure path.
+    ///
+    /// This is what you get when the edge was checked with
+    /// [`assert_loaded_otherwise_failed_with`](struct.HasOne.html#method.assert_loaded_otherwise_failed_with),
+    /// which the derived code does.
+    LoadFailedForIds(AssociationType, LoadFailedDetails),
+
+    /// Children were missing for an association whose policy requires them to exist.
+    ///
+    /// Produced by associations with `on_missing = "error"` (or a custom
+    /// [`on_missing_children`](trait.EagerLoadChildrenOfType.html#method.on_missing_children))
+    /// when a foreign key points at a child that doesn't exist.
+    MissingChildren(MissingChildren),
+
+    /// An underlying loader failed.
+    ///
+    /// Wraps the loader's own error so it stays reachable through
+    /// [`source`](https://doc.rust-lang.org/std/error/trait.Error.html#method.source) chains.
+    /// Create it with [`load`](#method.load).
+    Load(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Error {
+    /// Wrap an underlying loader error.
+    pub fn load<E>(source: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Error::Load(source.into())
+    }
+
+    /// Fill in where the error happened, if the variant carries a location and none was
+    /// recorded yet. The edge types call this on their error paths with the location the
+    /// derived code gave them.
+    fn at(mut self, location: Option<ErrorLocation>) -> Self {
+        match &mut self {
+            Error::NotLoaded(_, slot @ None)
+            | Error::NotRequested(_, slot @ None)
+            | Error::LoadFailed(_, slot @ None) => *slot = location,
+            _ => {}
+        }
+        self
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Error::NotLoaded(kind) => {
-                write!(f, "`{:?}` should have been eager loaded, but wasn't", kind)
+            Error::NotLoaded(kind, location) => match location {
+                Some(location) => {
+                    write!(f, "`{}` should have been eager loaded, but wasn't", location)
+                }
+                None => write!(f, "`{:?}` should have been eager loaded, but wasn't", kind),
+            },
+            Error::NotRequested(kind, location) => match location {
+                Some(location) => write!(
+                    f,
+                    "`{}` wasn't eager loaded because the GraphQL field wasn't selected by the query",
+                    location,
+                ),
+                None => write!(
+                    f,
+                    "`{:?}` wasn't eager loaded because the GraphQL field wasn't selected by the query",
+                    kind,
+                ),
+            },
+            Error::LoadFailed(kind, location) => match location {
+                Some(location) => write!(f, "Failed to load `{}`", location),
+                None => write!(f, "Failed to load `{:?}`", kind),
+            },
+            Error::LoadFailedForIds(kind, details) => write!(
+                f,
+                "Failed to load `{}` for `{:?}`: parent with id `{}` pointed at child id `{}`, which produced no model",
+                details.child_type, kind, details.parent_id, details.child_id,
+            ),
+            Error::MissingChildren(missing) => write!(
+                f,
+                "No `{}` found for ids `{}`, but the association requires them to exist",
+                missing.child_type,
+                missing.ids.join("`, `"),
+            ),
+            Error::Load(source) => write!(f, "Loading associated data failed: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Load(source) => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl<S: juniper::ScalarValue> juniper::IntoFieldError<S> for Error {
+    /// Convert the error into a `juniper::FieldError` with machine readable extensions.
+    ///
+    /// See [`field_error`](enum.Error.html#method.field_error) for the extensions shape. Use
+    /// the builder returned by that method instead if you want to add your own keys.
+    fn into_field_error(self) -> juniper::FieldError<S> {
+        self.field_error().build()
+    }
+}
+
+impl Error {
+    /// Start building a `juniper::FieldError` with machine readable extensions, so you can add
+    /// your own keys before [`build`](struct.FieldErrorBuilder.html#method.build)ing it.
+    ///
+    /// The extensions shape is stable and part of this crate's public API:
+    ///
+    /// | Key | Value | Present |
+    /// |---|---|---|
+    /// | `code` | `"NOT_LOADED"`, `"NOT_REQUESTED"`, `"EAGER_LOAD_FAILED"`, `"MISSING_CHILDREN"` or `"LOAD_ERROR"` | Always |
+    /// | `association` | The association kind, e.g. `"HasOne"` | Except for `MISSING_CHILDREN` |
+    /// | `parentType` | The GraphQL type holding the association, e.g. `"User"` | When the edge recorded its [location](struct.ErrorLocation.html) |
+    /// | `fieldName` | The association's field name, e.g. `"country"` | When the edge recorded its location |
+    /// | `type` | The child type that failed to load | When recorded on the failure path |
+    /// | `parentId` | The id of the parent row | When recorded on the failure path |
+    /// | `childId` | The child id the parent pointed at | When recorded on the failure path |
+    /// | `ids` | The list of ids that produced no model | For `MISSING_CHILDREN` |
+    ///
+    /// If all you need is the standard shape, `juniper::IntoFieldError` is implemented for
+    /// [`Error`](enum.Error.html) and does the same thing without the builder.
+    pub fn field_error<S: juniper::ScalarValue>(self) -> FieldErrorBuilder<S> {
+        FieldErrorBuilder {
+            error: self,
+            extra: Vec::new(),
+        }
+    }
+}
+
+/// A builder for turning an [`Error`](enum.Error.html) into a `juniper::FieldError` with extra
+/// extension keys. Created with [`Error::field_error`](enum.Error.html#method.field_error).
+#[derive(Debug)]
+pub struct FieldErrorBuilder<S = juniper::DefaultScalarValue> {
+    error: Error,
+    extra: Vec<(String, juniper::Value<S>)>,
+}
+
+impl<S: juniper::ScalarValue> FieldErrorBuilder<S> {
+    /// Add an extension key of your own to the standard shape.
+    pub fn extension(mut self, key: &str, value: juniper::Value<S>) -> Self {
+        self.extra.push((key.to_owned(), value));
+        self
+    }
+
+    /// Build the `juniper::FieldError`.
+    pub fn build(self) -> juniper::FieldError<S> {
+        let message = self.error.to_string();
+
+        let (code, kind, location) = match &self.error {
+            Error::NotLoaded(kind, location) => ("NOT_LOADED", Some(kind), *location),
+            Error::NotRequested(kind, location) => ("NOT_REQUESTED", Some(kind), *location),
+            Error::LoadFailed(kind, location) => ("EAGER_LOAD_FAILED", Some(kind), *location),
+            Error::LoadFailedForIds(kind, _) => ("EAGER_LOAD_FAILED", Some(kind), None),
+            Error::MissingChildren(_) => ("MISSING_CHILDREN", None, None),
+            Error::Load(_) => ("LOAD_ERROR", None, None),
+        };
+
+        let mut extensions = juniper::Object::with_capacity(2 + self.extra.len());
+        extensions.add_field("code", juniper::Value::scalar(code.to_owned()));
+        if let Some(kind) = kind {
+            extensions.add_field("association", juniper::Value::scalar(format!("{:?}", kind)));
+        }
+        if let Some(location) = location {
+            extensions.add_field(
+                "parentType",
+                juniper::Value::scalar(location.parent_type.to_owned()),
+            );
+            extensions.add_field(
+                "fieldName",
+                juniper::Value::scalar(location.field_name.to_owned()),
+            );
+        }
+
+        match &self.error {
+            Error::LoadFailedForIds(_, details) => {
+                extensions.add_field(
+                    "type",
+                    juniper::Value::scalar(details.child_type.to_owned()),
+                );
+                extensions.add_field(
+                    "parentId",
+                    juniper::Value::scalar(details.parent_id.clone()),
+                );
+                extensions.add_field("childId", juniper::Value::scalar(details.child_id.clone()));
+            }
+            Error::MissingChildren(missing) => {
+                extensions.add_field(
+                    "type",
+                    juniper::Value::scalar(missing.child_type.to_owned()),
+                );
+                extensions.add_field(
+                    "ids",
+                    juniper::Value::list(
+                        missing
+                            .ids
+                            .iter()
+                            .map(|id| juniper::Value::scalar(id.clone()))
+                            .collect(),
+                    ),
+                );
             }
-            Error::LoadFailed(kind) => write!(f, "Failed to load `{:?}`", kind),
+            _ => {}
+        }
+
+        for (key, value) in self.extra {
+            extensions.add_field(key, value);
         }
+
+        juniper::FieldError::new(message, juniper::Value::Object(extensions))
     }
 }
 
-impl std::error::Error for Error {}
+/// Which children were missing for an association whose policy requires them to exist.
+///
+/// Carried by [`Error::MissingChildren`](enum.Error.html#variant.MissingChildren). The ids are
+/// captured through their `Debug` representation so this type doesn't have to be generic over
+/// your id types.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct MissingChildren {
+    /// The name of the child type that was required but missing.
+    pub child_type: &'static str,
+    /// The `Debug` representations of the ids that produced no model.
+    pub ids: Vec<String>,
+}
 
-/// Remove duplicates from a list.
+impl MissingChildren {
+    /// Record the ids that produced no model.
+    pub fn new(child_type: &'static str, ids: Vec<String>) -> Self {
+        MissingChildren { child_type, ids }
+    }
+}
+
+/// Which ids were involved when an association failed to load.
+///
+/// Recorded by
+/// [`HasOne::assert_loaded_otherwise_failed_with`](struct.HasOne.html#method.assert_loaded_otherwise_failed_with)
+/// and carried by [`Error::LoadFailedForIds`](enum.Error.html#variant.LoadFailedForIds). The ids
+/// are captured through their `Debug` representation so this type doesn't have to be generic
+/// over your id types.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LoadFailedDetails {
+    /// The name of the child type that failed to load.
+    pub child_type: &'static str,
+    /// The `Debug` representation of the parent model's id.
+    pub parent_id: String,
+    /// The `Debug` representation of the child id the parent pointed at.
+    pub child_id: String,
+}
+
+impl LoadFailedDetails {
+    /// Record the ids involved in a failed load.
+    pub fn new<P, C>(child_type: &'static str, parent_id: &P, child_id: &C) -> Self
+    where
+        P: fmt::Debug,
+        C: fmt::Debug,
+    {
+        LoadFailedDetails {
+            child_type,
+            parent_id: format!("{:?}", parent_id),
+            child_id: format!("{:?}", child_id),
+        }
+    }
+}
+
+/// Group children by some key in a single pass, for example their foreign key.
+///
+/// For has-many style associations, matching every parent against every child is quadratic and
+/// dominates once fan-out gets large. Grouping the children by their parent key first makes the
+/// whole assignment linear: build the groups once, then give each parent its bucket.
+///
+/// Within a bucket children keep the order they had in `children`, so the result is
+/// deterministic.
+///
+/// Parents without children simply have no entry in the map:
+///
+/// ```
+/// use juniper_eager_loading::group_children_by_key;
+///
+/// struct Comment {
+///     post_id: i32,
+/// }
+///
+/// let comments = vec![
+///     Comment { post_id: 1 },
+///     Comment { post_id: 2 },
+///     Comment { post_id: 1 },
+/// ];
+///
+/// let mut by_post = group_children_by_key(comments, |comment| comment.post_id);
+///
+/// assert_eq!(by_post.get(&1).map(|group| group.len()), Some(2));
+/// assert_eq!(by_post.get(&2).map(|group| group.len()), Some(1));
+/// assert!(by_post.remove(&3).unwrap_or_default().is_empty());
+/// ```
+pub fn group_children_by_key<K, V, F>(children: Vec<V>, key: F) -> HashMap<K, Vec<V>>
+where
+    K: Hash + Eq,
+    F: Fn(&V) -> K,
+{
+    let mut groups = HashMap::new();
+    for child in children {
+        groups
+            .entry(key(&child))
+            .or_insert_with(Vec::new)
+            .push(child);
+    }
+    groups
+}
+
+/// Hash a join key for use with the `*_match_hash` methods on
+/// [`EagerLoadChildrenOfType`](trait.EagerLoadChildrenOfType.html).
+///
+/// Both sides of an association have to hash their key the same way for the hash-based
+/// matching to find anything, so implementations of
+/// [`parent_match_hash`](trait.EagerLoadChildrenOfType.html#method.parent_match_hash) and
+/// [`child_match_hash`](trait.EagerLoadChildrenOfType.html#method.child_match_hash) — and the
+/// code the derive generates — should all go through this function.
+///
+/// ```
+/// use juniper_eager_loading::match_hash;
+///
+/// assert_eq!(match_hash(&1), match_hash(&1));
+/// assert_ne!(match_hash(&1), match_hash(&2));
+/// ```
+pub fn match_hash<T: Hash>(key: &T) -> u64 {
+    use std::hash::Hasher;
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Remove duplicates from a list, keeping the first occurrence of each item.
 ///
 /// This function is used to remove duplicate ids from
 /// [`child_ids`](trait.EagerLoadChildrenOfType.html#tymethod.child_ids).
+///
+/// The order of the remaining items follows the input. An earlier version collected into a
+/// `HashSet` and back, which made the ids sent to loaders — and through loaders that preserve
+/// request order, the order of loaded children — change from run to run with the hasher's
+/// random seed.
 pub fn unique<T: Hash + Eq>(items: Vec<T>) -> Vec<T> {
     use std::collections::HashSet;
 
+    let mut seen = HashSet::with_capacity(items.len());
+    let keep = items
+        .iter()
+        .map(|item| seen.insert(item))
+        .collect::<Vec<_>>();
+    drop(seen);
+
+    let mut keep = keep.into_iter();
+    let mut items = items;
+    items.retain(|_| keep.next().unwrap());
     items
-        .into_iter()
-        .collect::<HashSet<_>>()
-        .into_iter()
-        .collect::<Vec<_>>()
 }
@@ -34,12 +34,23 @@
 ///     }
 /// }
 ///
+/// table! {
+///     tbl_users (user_uid) {
+///         user_uid -> Integer,
+///     }
+/// }
+///
 /// #[derive(Queryable)]
 /// struct User {
 ///     id: i32,
 /// }
 ///
 /// #[derive(Queryable)]
+/// struct LegacyUser {
+///     user_uid: i32,
+/// }
+///
+/// #[derive(Queryable)]
 /// struct Company {
 ///     id: i32,
 /// }
@@ -59,6 +70,8 @@
 ///         i32 -> (users, User),
 ///         i32 -> (companies, Company),
 ///         i32 -> (employments, Employment),
+///         i32 -> (tbl_users.user_uid, LegacyUser),
+///         (i32, i32) -> (employments.(user_id, company_id), Employment),
 ///
 ///         User.id -> (employments.user_id, Employment),
 ///         Company.id -> (employments.company_id, Employment),
@@ -90,7 +103,32 @@
 /// ```
 ///
 /// The first syntax implements `LoadFrom<i32> for User`, meaning from a `Vec<i32>` we can load a
-/// `Vec<User>`. It just takes the id type, the table, and the model struct.
+/// `Vec<User>`. It just takes the id type, the table, and the model struct. It assumes the
+/// table's primary key column is named `id`.
+///
+/// ```text
+/// i32 -> (tbl_users.user_uid, LegacyUser),
+/// ```
+///
+/// When the primary key column isn't named `id`, name it explicitly after the table. This
+/// implements the same kind of `LoadFrom` but filters on the given column instead. An error
+/// about a nonexistent column points at the macro invocation like any other Diesel compile
+/// error.
+///
+/// ```text
+/// (i32, i32) -> (employments.(user_id, company_id), Employment),
+/// ```
+///
+/// This syntax is for tables with composite primary keys. It implements
+/// `LoadFrom<(i32, i32)> for Employment`, filtering on the tuple set in a single query.
+/// Diesel's DSL has no row-value `IN`, so the generated filter is an OR chain of per-key
+/// conjunctions — `(user_id = $1 AND company_id = $2) OR ...` — which Postgres plans the same
+/// way. Two key columns are supported; wider keys need a handwritten [`LoadFrom`].
+///
+/// Note that the generated code is Postgres-only and won't compile against other backends:
+/// this syntax boxes its filter for `diesel::pg::Pg` specifically, and the other syntaxes use
+/// Postgres' `= ANY(...)` through `diesel::pg::expression::dsl::any`. For other backends write
+/// the [`LoadFrom`] impls by hand.
 ///
 /// ```text
 /// User.id -> (employments.user_id, Employment),
@@ -233,6 +271,86 @@ macro_rules! __impl_load_from_for_diesel_inner {
         }
     };
 
+    (
+        error = $error:path,
+        connection = $connection:path,
+        $id_ty:ident -> ($table:ident . $id_column:ident, $ty:ident),
+        $( $rest:tt )*
+    ) => {
+        impl juniper_eager_loading::LoadFrom<$id_ty> for $ty {
+            type Error = $error;
+            type Connection = $connection;
+
+            fn load(
+                ids: &[$id_ty],
+                db: &Self::Connection,
+            ) -> Result<Vec<Self>, Self::Error> {
+                use diesel::pg::expression::dsl::any;
+
+                $table::table
+                    .filter($table::$id_column.eq(any(ids)))
+                    .load::<$ty>(db)
+                    .map_err(From::from)
+            }
+        }
+
+        $crate::__impl_load_from_for_diesel_inner! {
+            error = $error,
+            connection = $connection,
+            $($rest)*
+        }
+    };
+
+    (
+        error = $error:path,
+        connection = $connection:path,
+        ($id_a:ty, $id_b:ty) -> ($table:ident . ($col_a:ident, $col_b:ident), $ty:ident),
+        $( $rest:tt )*
+    ) => {
+        impl juniper_eager_loading::LoadFrom<($id_a, $id_b)> for $ty {
+            type Error = $error;
+            type Connection = $connection;
+
+            fn load(
+                ids: &[($id_a, $id_b)],
+                db: &Self::Connection,
+            ) -> Result<Vec<Self>, Self::Error> {
+                // Diesel's DSL has no row-value `IN`, so the tuple set becomes a chain of
+                // per-key conjunctions — `(a = $1 AND b = $2) OR ...` — which Postgres plans
+                // the same way. Still a single query per batch of ids.
+                let mut filter: Option<Box<dyn diesel::BoxableExpression<
+                    $table::table,
+                    diesel::pg::Pg,
+                    SqlType = diesel::sql_types::Bool,
+                >>> = None;
+
+                for (a, b) in ids {
+                    let key_matches = $table::$col_a
+                        .eq(a.clone())
+                        .and($table::$col_b.eq(b.clone()));
+                    filter = Some(match filter {
+                        Some(prev) => Box::new(prev.or(key_matches)),
+                        None => Box::new(key_matches),
+                    });
+                }
+
+                match filter {
+                    Some(filter) => $table::table
+                        .filter(filter)
+                        .load::<$ty>(db)
+                        .map_err(From::from),
+                    None => Ok(Vec::new()),
+                }
+            }
+        }
+
+        $crate::__impl_load_from_for_diesel_inner! {
+            error = $error,
+            connection = $connection,
+            $($rest)*
+        }
+    };
+
     (
         error = $error:path,
         connection = $connection:path,
@@ -264,3 +382,115 @@ macro_rules! __impl_load_from_for_diesel_inner {
         }
     };
 }
+
+/// Assert that an edge is loaded and holds the expected value.
+///
+/// Works for any edge type through [`try_unwrap`](struct.HasOne.html#method.try_unwrap). The
+/// failure message includes the actual edge state: the loaded value when the values differ, or
+/// the error when the edge isn't loaded.
+///
+/// ```
+/// use juniper_eager_loading::{assert_loaded, HasOne};
+///
+/// let mut country = HasOne::default();
+/// country.loaded("Denmark");
+///
+/// assert_loaded!(country, "Denmark");
+/// ```
+#[macro_export]
+macro_rules! assert_loaded {
+    ($edge:expr, $expected:expr $(,)?) => {
+        match $edge.try_unwrap() {
+            Ok(actual) => {
+                if *actual != $expected {
+                    panic!(
+                        "`{}` is loaded with a different value\n  actual: `{:?}`\nexpected: `{:?}`",
+                        stringify!($edge),
+                        actual,
+                        $expected,
+                    );
+                }
+            }
+            Err(err) => panic!("`{}` should be loaded, but {}", stringify!($edge), err),
+        }
+    };
+}
+
+/// Assert that an [`OptionHasOne`](struct.OptionHasOne.html) is loaded with `None`.
+///
+/// ```
+/// use juniper_eager_loading::{assert_loaded_none, OptionHasOne};
+///
+/// let nickname = OptionHasOne::<String>::default();
+///
+/// assert_loaded_none!(nickname);
+/// ```
+#[macro_export]
+macro_rules! assert_loaded_none {
+    ($edge:expr $(,)?) => {
+        match $edge.try_unwrap() {
+            Ok(None) => {}
+            Ok(Some(actual)) => panic!(
+                "`{}` should be loaded with `None`, but was loaded with `{:?}`",
+                stringify!($edge),
+                actual,
+            ),
+            Err(err) => panic!("`{}` should be loaded, but {}", stringify!($edge), err),
+        }
+    };
+}
+
+/// Assert that a [`HasMany`](struct.HasMany.html) or
+/// [`HasManyThrough`](struct.HasManyThrough.html) is loaded with exactly the given items, in
+/// order.
+///
+/// ```
+/// use juniper_eager_loading::{assert_loaded_items, HasMany};
+///
+/// let mut cars = HasMany::default();
+/// cars.loaded("Saab");
+/// cars.loaded("Volvo");
+///
+/// assert_loaded_items!(cars, ["Saab", "Volvo"]);
+/// ```
+#[macro_export]
+macro_rules! assert_loaded_items {
+    ($edge:expr, [$($expected:expr),* $(,)?] $(,)?) => {
+        match $edge.try_unwrap() {
+            Ok(actual) => {
+                let expected = [$($expected),*];
+                if *actual != expected {
+                    panic!(
+                        "`{}` is loaded with different items\n  actual: `{:?}`\nexpected: `{:?}`",
+                        stringify!($edge),
+                        actual,
+                        expected,
+                    );
+                }
+            }
+            Err(err) => panic!("`{}` should be loaded, but {}", stringify!($edge), err),
+        }
+    };
+}
+
+/// Assert that an edge is not loaded.
+///
+/// ```
+/// use juniper_eager_loading::{assert_not_loaded, HasOne};
+///
+/// let country = HasOne::<String>::default();
+///
+/// assert_not_loaded!(country);
+/// ```
+#[macro_export]
+macro_rules! assert_not_loaded {
+    ($edge:expr $(,)?) => {
+        if let Ok(actual) = $edge.try_unwrap() {
+            panic!(
+                "`{}` should not be loaded, but was loaded with `{:?}`",
+                stringify!($edge),
+                actual,
+            );
+        }
+    };
+}
@@ -0,0 +1,124 @@
+//! An adapter for the [`cached`] crate that memoizes loader calls across requests.
+//!
+//! [`cached`]: https://crates.io/crates/cached
+
+use crate::{
+    small_vec::{unique_ids, IdBuffer},
+    CacheStorage, LoadFrom, MaybeSend,
+};
+use cached::{Cached, TimedCache};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+/// A loader that memoizes [`LoadFrom`](trait.LoadFrom.html) calls per id across requests.
+///
+/// Each loaded model is stored in a [`cached::TimedCache`] under its id, so a later load of the
+/// same id — typically from a different request — skips the database entirely. This gives you
+/// loader-level memoization without adopting the full
+/// [`SharedCache`](struct.SharedCache.html) machinery.
+///
+/// # Interplay with the per-request [`Cache`](struct.Cache.html)
+///
+/// [`load`](#method.load) takes the per-request cache as an argument and consults it first, so
+/// within a request the per-request cache always wins. The memoized cache is only probed for ids
+/// the request hasn't seen yet, and everything that gets loaded is inserted into both caches.
+/// This means [`invalidate`](#method.invalidate) only affects future requests; a request that
+/// already loaded the id keeps serving its own copy, which is the consistency you want within a
+/// single query execution.
+///
+/// [`cached::TimedCache`]: https://docs.rs/cached/0.26/cached/stores/struct.TimedCache.html
+pub struct MemoizedLoader<Id, T, F> {
+    cache: Mutex<TimedCache<Id, T>>,
+    key_for_model: F,
+}
+
+impl<Id, T, F> fmt::Debug for MemoizedLoader<Id, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MemoizedLoader").finish()
+    }
+}
+
+impl<Id, T, F> MemoizedLoader<Id, T, F>
+where
+    Id: 'static + Hash + Eq + Clone,
+    T: LoadFrom<Id> + Clone + MaybeSend + 'static,
+    F: Fn(&T) -> Id,
+{
+    /// Create a new loader whose memoized entries expire after `lifespan`.
+    ///
+    /// `key_for_model` extracts the id from a loaded model, normally `|model| model.id`, and
+    /// determines the key each model is memoized under.
+    pub fn new(lifespan: Duration, key_for_model: F) -> Self {
+        MemoizedLoader {
+            cache: Mutex::new(TimedCache::with_lifespan(lifespan.as_secs())),
+            key_for_model,
+        }
+    }
+
+    /// Load the models for the given ids, going to the database only for ids found in neither
+    /// the per-request cache nor the memoized cache.
+    ///
+    /// Models are returned in id order, with ids that match no model skipped, just like a
+    /// [`LoadFrom`](trait.LoadFrom.html) implementation would.
+    ///
+    /// The cache argument is generic over [`CacheStorage`](trait.CacheStorage.html), so both
+    /// the per-request [`Cache`](struct.Cache.html) and a
+    /// [`SharedCache`](struct.SharedCache.html) work.
+    pub fn load(
+        &self,
+        ids: &[Id],
+        db: &T::Connection,
+        cache: &mut impl CacheStorage<Id>,
+    ) -> Result<Vec<T>, T::Error> {
+        let mut results = vec![None; ids.len()];
+        let mut missing_ids = IdBuffer::new();
+
+        {
+            let mut memo = self.lock();
+            for (position, id) in ids.iter().enumerate() {
+                if let Some(model) = cache.get::<T>(id.clone()) {
+                    results[position] = Some(model);
+                } else if let Some(model) = memo.cache_get(id).cloned() {
+                    cache.insert(id.clone(), model.clone());
+                    results[position] = Some(model);
+                } else {
+                    missing_ids.push(id.clone());
+                }
+            }
+        }
+
+        if !missing_ids.is_empty() {
+            let missing_ids = unique_ids(&missing_ids);
+            let loaded = T::load(&missing_ids, db)?;
+
+            let mut loaded_by_id = HashMap::with_capacity(loaded.len());
+            let mut memo = self.lock();
+            for model in loaded {
+                let id = (self.key_for_model)(&model);
+                memo.cache_set(id.clone(), model.clone());
+                cache.insert(id.clone(), model.clone());
+                loaded_by_id.insert(id, model);
+            }
+
+            for (position, id) in ids.iter().enumerate() {
+                if results[position].is_none() {
+                    results[position] = loaded_by_id.get(id).cloned();
+                }
+            }
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Remove the memoized model for the given id, if any, so the next request loads it fresh.
+    pub fn invalidate(&self, id: &Id) {
+        self.lock().cache_remove(id);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TimedCache<Id, T>> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
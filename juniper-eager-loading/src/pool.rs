@@ -0,0 +1,119 @@
+//! Loading through async connection pools.
+//!
+//! Async apps hold a connection pool, not a connection, and checking one connection out for the
+//! whole query execution serializes sibling loads. The abstractions here let the pool itself be
+//! the "connection" threaded through eager loading: each loader call checks out its own
+//! connection via [`ConnectionPool`](trait.ConnectionPool.html), so sibling associations are
+//! free to load concurrently.
+//!
+//! Adapter impls for `deadpool` and `bb8` pools are available behind features of the same names.
+//!
+//! # Transaction semantics
+//!
+//! Because every [`load_from_pool`](fn.load_from_pool.html) call checks out its own connection,
+//! the loads for different associations don't share a transaction and may observe different
+//! snapshots of the database. If you need all loads inside one transaction, don't pass a pool:
+//! pass a transaction object as the connection type instead and implement your loaders against
+//! that.
+
+use std::future::Future;
+
+/// An async pool of database connections.
+///
+/// This is the small common denominator of pool crates like `deadpool` and `bb8`: something you
+/// can check a connection out of. Impls for those two crates ship behind the `deadpool` and
+/// `bb8` features; implementing the trait for other pools is a few lines.
+pub trait ConnectionPool {
+    /// The checked out connection type, including whatever guard returns it to the pool on drop.
+    type Connection;
+
+    /// The error type checkout can fail with.
+    type Error;
+
+    /// The future returned by [`checkout`](#tymethod.checkout).
+    type Future: Future<Output = Result<Self::Connection, Self::Error>>;
+
+    /// Check one connection out of the pool.
+    fn checkout(&self) -> Self::Future;
+}
+
+/// Like [`LoadFrom`](trait.LoadFrom.html), but async and loading through a connection checked
+/// out of a [`ConnectionPool`](trait.ConnectionPool.html).
+///
+/// You normally don't call this directly but through
+/// [`load_from_pool`](fn.load_from_pool.html), which handles the checkout and maps checkout
+/// errors into your error type.
+pub trait AsyncLoadFrom<Id>: Sized {
+    /// The error type. It must absorb the pool's checkout error, see
+    /// [`load_from_pool`](fn.load_from_pool.html).
+    type Error;
+
+    /// The pool connections are checked out of.
+    type Pool: ConnectionPool;
+
+    /// The future returned by [`load`](#tymethod.load).
+    type Future: Future<Output = Result<Vec<Self>, Self::Error>>;
+
+    /// Load the models for the given ids using a connection checked out of the pool.
+    ///
+    /// The connection is owned by the returned future and goes back to the pool when it is
+    /// dropped.
+    fn load(ids: &[Id], connection: <Self::Pool as ConnectionPool>::Connection) -> Self::Future;
+}
+
+/// Check a connection out of `pool` and load the models for the given ids with it.
+///
+/// Checkout errors are mapped into the loader's error type through the `From` bound.
+pub async fn load_from_pool<T, Id>(ids: &[Id], pool: &T::Pool) -> Result<Vec<T>, T::Error>
+where
+    T: AsyncLoadFrom<Id>,
+    T::Error: From<<T::Pool as ConnectionPool>::Error>,
+{
+    let connection = pool.checkout().await?;
+    T::load(ids, connection).await
+}
+
+#[cfg(feature = "deadpool")]
+mod deadpool_impl {
+    use super::ConnectionPool;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    impl<M, W> ConnectionPool for deadpool::managed::Pool<M, W>
+    where
+        M: deadpool::managed::Manager + 'static,
+        M::Type: Send,
+        M::Error: Send,
+        W: From<deadpool::managed::Object<M>> + Send + 'static,
+    {
+        type Connection = W;
+        type Error = deadpool::managed::PoolError<M::Error>;
+        type Future = Pin<Box<dyn Future<Output = Result<Self::Connection, Self::Error>> + Send>>;
+
+        fn checkout(&self) -> Self::Future {
+            let pool = self.clone();
+            Box::pin(async move { pool.get().await })
+        }
+    }
+}
+
+#[cfg(feature = "bb8")]
+mod bb8_impl {
+    use super::ConnectionPool;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    impl<M> ConnectionPool for bb8::Pool<M>
+    where
+        M: bb8::ManageConnection,
+    {
+        type Connection = bb8::PooledConnection<'static, M>;
+        type Error = bb8::RunError<M::Error>;
+        type Future = Pin<Box<dyn Future<Output = Result<Self::Connection, Self::Error>> + Send>>;
+
+        fn checkout(&self) -> Self::Future {
+            let pool = self.clone();
+            Box::pin(async move { pool.get_owned().await })
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Support for associations that load from a remote service instead of a database.
+
+use std::fmt;
+use std::future::Future;
+
+/// Like [`LoadFrom`](trait.LoadFrom.html), but for models that live behind a remote service —
+/// say `UserProfileService.batchGet(ids)` over gRPC or REST — rather than in a database.
+///
+/// The differences from `LoadFrom` are that loading is async, and that the loader receives a
+/// [`Client`](#associatedtype.Client) instead of a `Connection`.
+///
+/// You normally don't call this directly but through [`load_remote`](fn.load_remote.html), which
+/// adds retry handling around the call.
+pub trait RemoteLoadFrom<Id>: Sized {
+    /// The error type the remote call can fail with.
+    type Error;
+
+    /// The client used to talk to the remote service.
+    type Client;
+
+    /// The future returned by [`load`](#tymethod.load).
+    type Future: Future<Output = Result<Vec<Self>, Self::Error>>;
+
+    /// Perform one batched remote call loading the models for the given ids.
+    ///
+    /// Ids without a corresponding model should simply be absent from the result, not errors.
+    fn load(ids: &[Id], client: &Self::Client) -> Self::Future;
+}
+
+/// Hooks invoked around remote loader calls by [`load_remote`](fn.load_remote.html).
+///
+/// Remote calls fail in ways database queries don't — transient network errors, deadlines — so
+/// the retry policy is yours to define. [`RetryLimit`](struct.RetryLimit.html) covers the common
+/// "retry N times" case; implement this trait yourself for anything fancier, such as giving up
+/// when a request deadline has passed. Per-attempt timeouts belong inside the future your client
+/// returns, since only your runtime can provide a timer.
+pub trait RemoteCallHooks<E> {
+    /// Called before each attempt. Attempts are numbered from 1. Defaults to doing nothing.
+    fn on_attempt(&self, attempt: usize) {
+        let _ = attempt;
+    }
+
+    /// Called after a failed attempt. Return `true` to retry, `false` to give up and surface the
+    /// error.
+    fn retry_after_error(&self, attempt: usize, error: &E) -> bool;
+}
+
+/// A [`RemoteCallHooks`](trait.RemoteCallHooks.html) implementation that allows up to a fixed
+/// number of attempts and never inspects the error.
+#[derive(Debug, Copy, Clone)]
+pub struct RetryLimit(usize);
+
+impl RetryLimit {
+    /// Allow up to `max_attempts` attempts in total. `RetryLimit::new(1)` never retries.
+    pub fn new(max_attempts: usize) -> Self {
+        RetryLimit(max_attempts)
+    }
+}
+
+impl<E> RemoteCallHooks<E> for RetryLimit {
+    fn retry_after_error(&self, attempt: usize, _error: &E) -> bool {
+        attempt < self.0
+    }
+}
+
+/// Load models from a remote service, retrying failed calls according to `hooks`.
+///
+/// Each attempt issues one batched [`RemoteLoadFrom::load`](trait.RemoteLoadFrom.html) call for
+/// the full id set.
+pub async fn load_remote<T, Id, H>(
+    ids: &[Id],
+    client: &T::Client,
+    hooks: &H,
+) -> Result<Vec<T>, T::Error>
+where
+    T: RemoteLoadFrom<Id>,
+    H: RemoteCallHooks<T::Error>,
+{
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        hooks.on_attempt(attempt);
+        match T::load(ids, client).await {
+            Ok(models) => return Ok(models),
+            Err(error) => {
+                if !hooks.retry_after_error(attempt, &error) {
+                    return Err(error);
+                }
+            }
+        }
+    }
+}
+
+/// A connection type bundling a database connection with a remote service client, so one node
+/// tree can mix database backed and remote backed associations.
+///
+/// The eager loading machinery threads a single `Connection` value through every association, so
+/// mixing backends means the connection type has to carry both. Set
+/// `Connection = DbAndRemote<YourDb, YourClient>` on the node types, have database backed
+/// [`LoadFrom`](trait.LoadFrom.html) impls read `connection.db`, and have remote backed ones
+/// call [`load_remote`](fn.load_remote.html) with `connection.remote`, blocking on the future
+/// with your runtime.
+pub struct DbAndRemote<Db, Client> {
+    /// The database connection.
+    pub db: Db,
+    /// The remote service client.
+    pub remote: Client,
+}
+
+impl<Db, Client> DbAndRemote<Db, Client> {
+    /// Bundle a database connection and a remote client.
+    pub fn new(db: Db, remote: Client) -> Self {
+        DbAndRemote { db, remote }
+    }
+}
+
+impl<Db, Client> fmt::Debug for DbAndRemote<Db, Client> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DbAndRemote").finish()
+    }
+}
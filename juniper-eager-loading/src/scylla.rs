@@ -0,0 +1,100 @@
+//! Helpers for loading children from ScyllaDB/Cassandra. Requires the `scylla` feature.
+//!
+//! Tables partitioned by the parent id map naturally onto the has-many flow: loading the
+//! children of N parents means querying N partitions. The helper here runs those per-partition
+//! queries concurrently — with a configurable bound so a huge parent batch doesn't flood the
+//! cluster — and merges the results into the flat model list the has-many grouping step expects.
+//!
+//! The session is abstracted behind [`ScyllaSession`](trait.ScyllaSession.html) so the helper
+//! works with whatever driver you use, and so tests can substitute a fake.
+
+use futures::stream::{self, StreamExt};
+
+/// A session capable of executing a per-partition query.
+///
+/// Implement this for your Scylla/Cassandra driver of choice, binding `parent_id` to the
+/// statement's single placeholder and collecting the rows into models.
+pub trait ScyllaSession<Id> {
+    /// The model type a partition's rows are collected into.
+    type Model;
+
+    /// The error type returned by the session.
+    type Error;
+
+    /// The future returned by [`execute`](#tymethod.execute).
+    type Future: std::future::Future<Output = Result<Vec<Self::Model>, Self::Error>>;
+
+    /// Execute `statement` against the partition identified by `parent_id`.
+    fn execute(&self, statement: &str, parent_id: &Id) -> Self::Future;
+}
+
+/// The outcome of loading a batch of partitions with
+/// [`load_partitions`](fn.load_partitions.html).
+///
+/// Failures are reported per partition rather than failing the whole batch, so tolerant callers
+/// can mark only the affected parents `LoadFailed` while still attaching the children that did
+/// load. Strict callers can collapse the outcome with [`into_result`](#method.into_result).
+#[derive(Debug)]
+pub struct PartitionResults<Id, Model, Error> {
+    /// The models from every partition that loaded successfully, merged in parent id order.
+    pub models: Vec<Model>,
+    /// The parent ids whose partition query failed, with the error, in parent id order.
+    pub failures: Vec<(Id, Error)>,
+}
+
+impl<Id, Model, Error> PartitionResults<Id, Model, Error> {
+    /// Collapse into a `Result`, returning the first per-partition error if any partition
+    /// failed.
+    pub fn into_result(self) -> Result<Vec<Model>, Error> {
+        match self.failures.into_iter().next() {
+            Some((_, error)) => Err(error),
+            None => Ok(self.models),
+        }
+    }
+}
+
+/// Load the children of `parent_ids` by querying one partition per parent, at most
+/// `max_concurrency` partitions at a time.
+///
+/// `statement_template` is your prepared statement text with `{parent_id_column}` standing in
+/// for the partition key column, for example:
+///
+/// ```text
+/// SELECT * FROM activity_events WHERE {parent_id_column} = ?
+/// ```
+///
+/// The placeholder is substituted with `parent_id_column` once, and the resulting statement is
+/// executed against each parent's partition. Results are merged in parent id order so the
+/// has-many grouping step sees a deterministic model list.
+pub async fn load_partitions<Id, Session>(
+    session: &Session,
+    statement_template: &str,
+    parent_id_column: &str,
+    parent_ids: &[Id],
+    max_concurrency: usize,
+) -> PartitionResults<Id, Session::Model, Session::Error>
+where
+    Id: Clone,
+    Session: ScyllaSession<Id>,
+{
+    let statement = statement_template.replace("{parent_id_column}", parent_id_column);
+
+    let outcomes = stream::iter(parent_ids.iter().map(|parent_id| {
+        let query = session.execute(&statement, parent_id);
+        async move { (parent_id.clone(), query.await) }
+    }))
+    .buffered(std::cmp::max(max_concurrency, 1))
+    .collect::<Vec<_>>()
+    .await;
+
+    let mut models = Vec::new();
+    let mut failures = Vec::new();
+    for (parent_id, outcome) in outcomes {
+        match outcome {
+            Ok(partition_models) => models.extend(partition_models),
+            Err(error) => failures.push((parent_id, error)),
+        }
+    }
+
+    PartitionResults { models, failures }
+}
@@ -0,0 +1,72 @@
+//! Small-size-optimized buffers for collecting ids.
+//!
+//! Most loads touch a handful of ids, so the intermediate id buffers the crate builds internally
+//! would spend more time in the allocator than anywhere else. With the default-on `smallvec`
+//! feature the first [`INLINE_CAPACITY`] ids live inline on the stack and only larger loads fall
+//! back to the heap. Disabling the feature swaps the buffers back to plain `Vec`s.
+//!
+//! Everything user-facing keeps taking `&[Id]` slices, which both buffer types deref to.
+
+use std::hash::Hash;
+
+/// How many ids fit in an [`IdBuffer`] before it spills to the heap.
+pub(crate) const INLINE_CAPACITY: usize = 8;
+
+#[cfg(feature = "smallvec")]
+pub(crate) type IdBuffer<T> = smallvec::SmallVec<[T; INLINE_CAPACITY]>;
+
+#[cfg(not(feature = "smallvec"))]
+pub(crate) type IdBuffer<T> = Vec<T>;
+
+/// Remove duplicates from a slice of ids, preserving the order in which they first appear.
+///
+/// The small-slice counterpart to [`unique`](crate::unique): for inputs that fit
+/// [`INLINE_CAPACITY`] the dedup is a linear scan over the buffer and allocates nothing, which
+/// covers the extremely common one-to-four id case. Larger inputs use a `HashSet` like `unique`
+/// does.
+pub(crate) fn unique_ids<T: Hash + Eq + Clone>(ids: &[T]) -> IdBuffer<T> {
+    let mut buffer = IdBuffer::new();
+
+    if ids.len() <= INLINE_CAPACITY {
+        for id in ids {
+            if !buffer.contains(id) {
+                buffer.push(id.clone());
+            }
+        }
+    } else {
+        let mut seen = std::collections::HashSet::with_capacity(ids.len());
+        for id in ids {
+            if seen.insert(id) {
+                buffer.push(id.clone());
+            }
+        }
+    }
+
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedups_while_preserving_first_appearance_order() {
+        assert_eq!(unique_ids(&[3, 1, 3, 2, 1])[..], [3, 1, 2]);
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn small_id_sets_stay_on_the_stack() {
+        let buffer = unique_ids(&[1, 2, 3, 4]);
+        assert!(!buffer.spilled());
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn large_id_sets_fall_back_to_the_heap() {
+        let ids = (0..100).collect::<Vec<_>>();
+        let buffer = unique_ids(&ids);
+        assert!(buffer.spilled());
+        assert_eq!(buffer.len(), 100);
+    }
+}
@@ -0,0 +1,144 @@
+//! Eager loading for subscription style streams of model batches.
+
+use crate::cache::MaybeSend;
+use crate::cache::SharedCache;
+use crate::{EagerLoadAllChildren, GraphqlNodeForModel};
+use std::fmt;
+use std::hash::Hash;
+
+/// Eager loads batches of models as they arrive on a stream, reusing fully loaded nodes via a
+/// [`SharedCache`].
+///
+/// GraphQL subscriptions push lists of nodes to the client on every event. Using the normal entry
+/// points for that means redoing the whole eager load for every event, even when most of the
+/// nodes didn't change between events. `SubscriptionLoader` avoids that by caching the fully
+/// loaded nodes by id: models already in the cache skip both node construction and child
+/// loading entirely.
+///
+/// The loader itself is executor agnostic. [`load_batch`](#method.load_batch) loads one batch and
+/// can be called from whatever task or stream combinator drives your subscription, and
+/// [`load_batches`](#method.load_batches) adapts anything iterable (such as a channel receiver)
+/// into an iterator of loaded batches.
+///
+/// Because the cache outlives a single event, you should normally construct the [`SharedCache`]
+/// with a TTL ([`SharedCache::with_ttl`]) or call [`invalidate`](#method.invalidate) when you
+/// know a model changed, so long-lived subscriptions don't serve stale children forever.
+///
+/// You can find a complete example [here](https://github.com/davidpdrsn/juniper-eager-loading/tree/master/juniper-eager-loading/examples/subscriptions.rs).
+///
+/// [`SharedCache`]: struct.SharedCache.html
+/// [`SharedCache::with_ttl`]: struct.SharedCache.html#method.with_ttl
+pub struct SubscriptionLoader<'a, T, F>
+where
+    T: GraphqlNodeForModel,
+{
+    db: &'a T::Connection,
+    cache: SharedCache<T::Id>,
+    key_for_model: F,
+}
+
+impl<'a, T, F> fmt::Debug for SubscriptionLoader<'a, T, F>
+where
+    T: GraphqlNodeForModel,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SubscriptionLoader").finish()
+    }
+}
+
+impl<'a, T, F> SubscriptionLoader<'a, T, F>
+where
+    T: GraphqlNodeForModel + Clone + MaybeSend + 'static,
+    T::Id: Hash + Eq + Clone,
+    F: Fn(&T::Model) -> T::Id,
+{
+    /// Create a new loader.
+    ///
+    /// `key_for_model` extracts the id a model should be cached under, normally `|model|
+    /// model.id`.
+    pub fn new(db: &'a T::Connection, cache: SharedCache<T::Id>, key_for_model: F) -> Self {
+        Self {
+            db,
+            cache,
+            key_for_model,
+        }
+    }
+
+    /// Eager load a single batch of models into nodes.
+    ///
+    /// Models whose id is already in the cache reuse the previously loaded node. The remaining
+    /// models are turned into nodes with
+    /// [`from_db_models`](trait.GraphqlNodeForModel.html#method.from_db_models), eager loaded
+    /// with
+    /// [`eager_load_all_children_for_each`](trait.EagerLoadAllChildren.html#tymethod.eager_load_all_children_for_each),
+    /// and stored in the cache. The returned nodes are in the same order as `models`.
+    pub fn load_batch<Q>(&self, models: &[T::Model], trail: &Q) -> Result<Vec<T>, T::Error>
+    where
+        T: EagerLoadAllChildren<Q>,
+    {
+        let mut batch = Vec::<Option<T>>::with_capacity(models.len());
+        let mut fresh_models = Vec::with_capacity(models.len());
+        let mut fresh_positions = Vec::with_capacity(models.len());
+
+        for (position, model) in models.iter().enumerate() {
+            let key = (self.key_for_model)(model);
+            match self.cache.get::<T>(key) {
+                Some(node) => batch.push(Some(node)),
+                None => {
+                    batch.push(None);
+                    fresh_models.push(model.clone());
+                    fresh_positions.push(position);
+                }
+            }
+        }
+
+        if !fresh_models.is_empty() {
+            let mut nodes = T::from_db_models(&fresh_models);
+            T::eager_load_all_children_for_each(&mut nodes, &fresh_models, self.db, trail)?;
+
+            for ((position, node), model) in
+                fresh_positions.into_iter().zip(nodes).zip(&fresh_models)
+            {
+                let key = (self.key_for_model)(model);
+                self.cache.insert(key, node.clone());
+                batch[position] = Some(node);
+            }
+        }
+
+        Ok(batch
+            .into_iter()
+            .map(|node| node.expect("all positions filled"))
+            .collect())
+    }
+
+    /// Eager load a stream of model batches, yielding the loaded node batches.
+    ///
+    /// Anything iterable works as the stream, for example the receiving end of a channel:
+    ///
+    /// ```text
+    /// for nodes in loader.load_batches(receiver, &trail) {
+    ///     let nodes = nodes?;
+    ///     // push `nodes` to the client
+    /// }
+    /// ```
+    pub fn load_batches<'s, Q, S>(
+        &'s self,
+        batches: S,
+        trail: &'s Q,
+    ) -> impl Iterator<Item = Result<Vec<T>, T::Error>> + 's
+    where
+        T: EagerLoadAllChildren<Q>,
+        S: IntoIterator<Item = Vec<T::Model>>,
+        S::IntoIter: 's,
+    {
+        batches
+            .into_iter()
+            .map(move |batch| self.load_batch(&batch, trail))
+    }
+
+    /// Remove the cached node for the given id, forcing the next batch containing it to be
+    /// loaded fresh.
+    pub fn invalidate(&self, id: T::Id) {
+        self.cache.invalidate::<T>(id);
+    }
+}
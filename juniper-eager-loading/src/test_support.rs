@@ -0,0 +1,760 @@
+//! Test helpers for writing eager loading tests without a real database.
+//!
+//! This module is behind the `test-helpers` feature.
+//!
+//! The centerpiece is [`MockStore`]: register rows per model type, hand the
+//! [`MockConnection`] to your eager loading code as the `Connection` associated type, and
+//! [`LoadFrom`] is implemented automatically for every model that implements [`MockModel`].
+//! The store also records every load call, so tests can assert on how many loads happened and
+//! with which ids, and can inject failures to exercise error paths.
+//!
+//! ```
+//! use juniper_eager_loading::test_support::{MockModel, MockStore};
+//!
+//! #[derive(Clone, Debug)]
+//! struct Car {
+//!     id: i32,
+//! }
+//!
+//! impl MockModel for Car {
+//!     type Id = i32;
+//!
+//!     fn id(&self) -> Self::Id {
+//!         self.id
+//!     }
+//! }
+//!
+//! let store = MockStore::new();
+//! store.insert(vec![Car { id: 1 }, Car { id: 2 }]);
+//! let db = store.connection();
+//! // `db` can now be used wherever a `Connection` is expected.
+//! ```
+//!
+//! [`MockStore`]: struct.MockStore.html
+//! [`MockConnection`]: struct.MockConnection.html
+//! [`MockModel`]: trait.MockModel.html
+//! [`LoadFrom`]: ../trait.LoadFrom.html
+
+use crate::{EagerLoadAllChildren, GraphqlNodeForModel, LoadFrom};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// A model type that can be stored in a [`MockStore`](struct.MockStore.html).
+///
+/// Implement this for your models in tests to get [`LoadFrom`](../trait.LoadFrom.html) backed
+/// by the registered rows for free.
+pub trait MockModel: Clone + Send + 'static {
+    /// The id type rows are looked up by.
+    type Id: Eq + Clone + fmt::Debug + 'static;
+
+    /// The id rows are looked up by.
+    ///
+    /// Usually the row's own id. For has-many children that get loaded by their parent's id,
+    /// return the foreign key instead.
+    fn id(&self) -> Self::Id;
+}
+
+/// An in-memory store of rows, keyed by model type.
+///
+/// See the [module docs](index.html) for an overview. Cloning the store is cheap and all
+/// clones, as well as the connections handed out by [`connection`][], share the same rows and
+/// load log.
+///
+/// [`connection`]: struct.MockStore.html#method.connection
+#[derive(Clone)]
+pub struct MockStore {
+    inner: Arc<Mutex<Inner>>,
+}
+
+/// The connection handle for a [`MockStore`](struct.MockStore.html).
+///
+/// Use this as the `Connection` associated type in tests.
+#[derive(Clone)]
+pub struct MockConnection {
+    inner: Arc<Mutex<Inner>>,
+}
+
+/// One recorded load call.
+///
+/// Returned by [`MockStore::load_log`](struct.MockStore.html#method.load_log).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LoadCall {
+    /// The name of the model type that was loaded, without its module path.
+    pub model: &'static str,
+    /// The `Debug` representations of the ids that were requested, in request order.
+    pub ids: Vec<String>,
+}
+
+/// The error returned by loads that had a failure injected with
+/// [`MockStore::fail_next_load`](struct.MockStore.html#method.fail_next_load).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MockStoreError {
+    message: String,
+}
+
+#[derive(Default)]
+struct Inner {
+    rows: HashMap<TypeId, Box<dyn Any + Send>>,
+    log: Vec<LoadCall>,
+    failures: HashMap<TypeId, String>,
+    hooks: Option<RecordingHooks>,
+}
+
+impl MockStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        MockStore {
+            inner: Arc::new(Mutex::new(Inner::default())),
+        }
+    }
+
+    /// Register rows for a model type. Appends to any rows already registered for that type.
+    pub fn insert<T: MockModel>(&self, rows: Vec<T>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .rows
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Vec::<T>::new()))
+            .downcast_mut::<Vec<T>>()
+            .expect("rows stored under a `TypeId` are always of that type")
+            .extend(rows);
+    }
+
+    /// Get a connection handle that shares this store's rows and load log.
+    pub fn connection(&self) -> MockConnection {
+        MockConnection {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+
+    /// Every load call made so far, in order.
+    pub fn load_log(&self) -> Vec<LoadCall> {
+        self.inner.lock().unwrap().log.clone()
+    }
+
+    /// Record an [`Event`](enum.Event.html) into the given recorder for every load, in
+    /// addition to the plain [`load_log`](struct.MockStore.html#method.load_log).
+    pub fn record_events(&self, hooks: &RecordingHooks) {
+        self.inner.lock().unwrap().hooks = Some(hooks.clone());
+    }
+
+    /// Make the next load for the model type fail with the given message.
+    ///
+    /// Only that one load fails, loads after it behave normally again.
+    pub fn fail_next_load<T: MockModel>(&self, message: &str) {
+        self.inner
+            .lock()
+            .unwrap()
+            .failures
+            .insert(TypeId::of::<T>(), message.to_owned());
+    }
+}
+
+impl Default for MockStore {
+    fn default() -> Self {
+        MockStore::new()
+    }
+}
+
+impl fmt::Debug for MockStore {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MockStore").finish()
+    }
+}
+
+impl fmt::Debug for MockConnection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MockConnection").finish()
+    }
+}
+
+impl MockConnection {
+    fn load<T: MockModel>(&self, ids: &[T::Id]) -> Result<Vec<T>, MockStoreError> {
+        let mut inner = self.inner.lock().unwrap();
+
+        inner.log.push(LoadCall {
+            model: short_type_name::<T>(),
+            ids: ids.iter().map(|id| format!("{:?}", id)).collect(),
+        });
+
+        if let Some(hooks) = &inner.hooks {
+            hooks.loader_call::<T, _>(ids);
+        }
+
+        if let Some(message) = inner.failures.remove(&TypeId::of::<T>()) {
+            return Err(MockStoreError { message });
+        }
+
+        let rows = inner
+            .rows
+            .get(&TypeId::of::<T>())
+            .and_then(|rows| rows.downcast_ref::<Vec<T>>());
+
+        Ok(match rows {
+            Some(rows) => rows
+                .iter()
+                .filter(|row| ids.contains(&row.id()))
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        })
+    }
+}
+
+impl<T: MockModel> LoadFrom<T::Id> for T {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = MockConnection;
+
+    fn load(ids: &[T::Id], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.load::<T>(ids).map_err(Into::into)
+    }
+}
+
+impl fmt::Display for MockStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for MockStoreError {}
+
+fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// A connection wrapper that counts load calls, for asserting things like "this query performs
+/// exactly 4 loads".
+///
+/// Use it as the `Connection` associated type and route loads through it:
+///
+/// - Wrapping a [`MockConnection`](struct.MockConnection.html), call [`counted_load`][] in
+///   your `load_children`/`LoadFrom` code. It records the call and looks the rows up in the
+///   mock store.
+/// - Wrapping any other connection, like Diesel's, call
+///   [`record_load`](struct.CountingConnection.html#method.record_load) and then run the real
+///   query against [`inner`](struct.CountingConnection.html#method.inner).
+///
+/// Then assert with [`load_calls`](struct.CountingConnection.html#method.load_calls) and
+/// [`load_calls_for`](struct.CountingConnection.html#method.load_calls_for):
+///
+/// ```
+/// use juniper_eager_loading::test_support::{CountingConnection, MockModel, MockStore};
+///
+/// #[derive(Clone)]
+/// struct Car {
+///     id: i32,
+/// }
+///
+/// impl MockModel for Car {
+///     type Id = i32;
+///
+///     fn id(&self) -> Self::Id {
+///         self.id
+///     }
+/// }
+///
+/// let store = MockStore::new();
+/// store.insert(vec![Car { id: 1 }]);
+/// let db = CountingConnection::new(store.connection());
+///
+/// let cars: Vec<Car> = db.counted_load(&[1]).unwrap();
+///
+/// assert_eq!(cars.len(), 1);
+/// assert_eq!(db.load_calls(), 1);
+/// assert_eq!(db.load_calls_for::<Car>(), 1);
+/// ```
+///
+/// Cloning shares the counts, so tests can keep a handle for assertions while eager loading
+/// owns another.
+///
+/// [`counted_load`]: struct.CountingConnection.html#method.counted_load
+#[derive(Clone)]
+pub struct CountingConnection<C> {
+    inner: C,
+    counts: Arc<Mutex<HashMap<TypeId, usize>>>,
+}
+
+impl<C> CountingConnection<C> {
+    /// Wrap a connection.
+    pub fn new(inner: C) -> Self {
+        CountingConnection {
+            inner,
+            counts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// The wrapped connection.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// The total number of load calls recorded so far.
+    pub fn load_calls(&self) -> usize {
+        self.counts.lock().unwrap().values().sum()
+    }
+
+    /// The number of load calls recorded for one model type.
+    pub fn load_calls_for<T: 'static>(&self) -> usize {
+        self.counts
+            .lock()
+            .unwrap()
+            .get(&TypeId::of::<T>())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Record a load call for a model type without performing one.
+    ///
+    /// [`counted_load`](struct.CountingConnection.html#method.counted_load) calls this for
+    /// you. When wrapping a real connection, call this before running the query against
+    /// [`inner`](struct.CountingConnection.html#method.inner).
+    pub fn record_load<T: 'static>(&self) {
+        *self
+            .counts
+            .lock()
+            .unwrap()
+            .entry(TypeId::of::<T>())
+            .or_insert(0) += 1;
+    }
+}
+
+impl CountingConnection<MockConnection> {
+    /// Record a load call and look the rows up in the wrapped mock store.
+    pub fn counted_load<T: MockModel>(&self, ids: &[T::Id]) -> Result<Vec<T>, MockStoreError> {
+        self.record_load::<T>();
+        self.inner.load::<T>(ids)
+    }
+}
+
+impl<C> fmt::Debug for CountingConnection<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CountingConnection").finish()
+    }
+}
+
+/// A query trail that says every field was selected.
+///
+/// Hand-written [`EagerLoadAllChildren`](../trait.EagerLoadAllChildren.html) implementations in
+/// tests can be written against this instead of a real `QueryTrail`, which needs a schema and a
+/// parsed query.
+#[derive(Debug, Copy, Clone)]
+pub struct EverythingTrail;
+
+impl<T> crate::GenericQueryTrail<T, juniper_from_schema::Walked> for EverythingTrail {}
+
+/// A builder that wires up everything an eager loading test needs: a mock store with
+/// registered rows, root nodes built from models, and the eager load itself.
+///
+/// ```
+/// use juniper_eager_loading::test_support::{MockModel, Scenario};
+/// # use juniper_eager_loading::test_support::{EverythingTrail, MockConnection};
+/// # use juniper_eager_loading::prelude::*;
+///
+/// # #[derive(Clone, Debug)]
+/// # struct UserModel { id: i32 }
+/// # #[derive(Clone, Debug)]
+/// # struct User { user: UserModel }
+/// # impl GraphqlNodeForModel for User {
+/// #     type Model = UserModel;
+/// #     type Id = i32;
+/// #     type Connection = MockConnection;
+/// #     type Error = Box<dyn std::error::Error>;
+/// #     fn new_from_model(model: &Self::Model) -> Self {
+/// #         Self { user: model.clone() }
+/// #     }
+/// # }
+/// # impl EagerLoadAllChildren<EverythingTrail> for User {
+/// #     fn eager_load_all_children_for_each(
+/// #         _: &mut [Self],
+/// #         _: &[Self::Model],
+/// #         _: &Self::Connection,
+/// #         _: &EverythingTrail,
+/// #     ) -> Result<(), Self::Error> {
+/// #         Ok(())
+/// #     }
+/// # }
+/// let scenario = Scenario::new();
+/// let users: Vec<User> = scenario
+///     .eager_load(&[UserModel { id: 1 }])
+///     .unwrap();
+///
+/// assert_eq!(users.len(), 1);
+/// ```
+///
+/// Rows are registered with [`insert`][], the load is run with [`eager_load`][] against an
+/// [`EverythingTrail`](struct.EverythingTrail.html), and what got loaded along the way is
+/// available from [`load_log`](struct.Scenario.html#method.load_log).
+///
+/// [`insert`]: struct.Scenario.html#method.insert
+/// [`eager_load`]: struct.Scenario.html#method.eager_load
+#[derive(Debug, Clone, Default)]
+pub struct Scenario {
+    store: MockStore,
+}
+
+impl Scenario {
+    /// Create a scenario with an empty store.
+    pub fn new() -> Self {
+        Scenario {
+            store: MockStore::new(),
+        }
+    }
+
+    /// Register rows for a model type.
+    pub fn insert<T: MockModel>(self, rows: Vec<T>) -> Self {
+        self.store.insert(rows);
+        self
+    }
+
+    /// Build nodes from the given root models and eager load all their children, walking every
+    /// association.
+    pub fn eager_load<N>(&self, root_models: &[N::Model]) -> Result<Vec<N>, N::Error>
+    where
+        N: GraphqlNodeForModel<Connection = MockConnection> + EagerLoadAllChildren<EverythingTrail>,
+    {
+        let mut nodes = N::from_db_models(root_models);
+        N::eager_load_all_children_for_each(
+            &mut nodes,
+            root_models,
+            &self.store.connection(),
+            &EverythingTrail,
+        )?;
+        Ok(nodes)
+    }
+
+    /// Every load call made so far, in order.
+    pub fn load_log(&self) -> Vec<LoadCall> {
+        self.store.load_log()
+    }
+
+    /// The underlying store, for anything the builder doesn't cover, like failure injection.
+    pub fn store(&self) -> &MockStore {
+        &self.store
+    }
+}
+
+/// One recorded operation during a load.
+///
+/// Recorded by [`RecordingHooks`](struct.RecordingHooks.html). Ids and keys are normalized to
+/// their `Debug` representations so events compare with plain `==` regardless of the id types
+/// involved.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Event {
+    /// A loader was called for a model type with these ids.
+    LoaderCall {
+        /// The name of the model type, without its module path.
+        model: &'static str,
+        /// The requested ids, in request order.
+        ids: Vec<String>,
+    },
+    /// A cache lookup found a value.
+    CacheHit {
+        /// The name of the model type, without its module path.
+        model: &'static str,
+        /// The key that was looked up.
+        key: String,
+    },
+    /// A cache lookup found nothing.
+    CacheMiss {
+        /// The name of the model type, without its module path.
+        model: &'static str,
+        /// The key that was looked up.
+        key: String,
+    },
+}
+
+/// Records the exact sequence of operations during a load, for tests that need more than
+/// counts.
+///
+/// Attach it to a [`MockStore`](struct.MockStore.html) with
+/// [`record_events`](struct.MockStore.html#method.record_events) to get a
+/// [`LoaderCall`](enum.Event.html#variant.LoaderCall) per load, and call the recording methods
+/// yourself from custom loaders or cache code. Clones share the event list.
+///
+/// Assert on the result with [`events`](struct.RecordingHooks.html#method.events) and
+/// [`assert_events_contain`](fn.assert_events_contain.html).
+#[derive(Clone, Default)]
+pub struct RecordingHooks {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+impl RecordingHooks {
+    /// Create a recorder with no events.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a loader call for a model type.
+    pub fn loader_call<T, K: fmt::Debug>(&self, ids: &[K]) {
+        self.push(Event::LoaderCall {
+            model: short_type_name::<T>(),
+            ids: ids.iter().map(|id| format!("{:?}", id)).collect(),
+        });
+    }
+
+    /// Record a cache hit for a model type.
+    pub fn cache_hit<T, K: fmt::Debug>(&self, key: &K) {
+        self.push(Event::CacheHit {
+            model: short_type_name::<T>(),
+            key: format!("{:?}", key),
+        });
+    }
+
+    /// Record a cache miss for a model type.
+    pub fn cache_miss<T, K: fmt::Debug>(&self, key: &K) {
+        self.push(Event::CacheMiss {
+            model: short_type_name::<T>(),
+            key: format!("{:?}", key),
+        });
+    }
+
+    /// The events recorded so far, in order.
+    pub fn events(&self) -> Vec<Event> {
+        self.events.lock().unwrap().clone()
+    }
+
+    fn push(&self, event: Event) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+impl fmt::Debug for RecordingHooks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RecordingHooks").finish()
+    }
+}
+
+/// Assert that `expected` occurs in `events` as an ordered subsequence.
+///
+/// Other events may appear before, between, and after the expected ones, but the expected
+/// events must all be present in the given order. Panics with both sequences when they don't.
+///
+/// # Panics
+///
+/// When `expected` is not an ordered subsequence of `events`.
+pub fn assert_events_contain(events: &[Event], expected: &[Event]) {
+    let mut remaining = expected.iter();
+    let mut next = remaining.next();
+    for event in events {
+        match next {
+            Some(expected_event) if expected_event == event => next = remaining.next(),
+            _ => {}
+        }
+    }
+
+    if next.is_some() {
+        panic!(
+            "expected events to occur in order, but they didn't\n  events: {:#?}\nexpected: {:#?}",
+            events, expected,
+        );
+    }
+}
+
+/// A connection wrapper that makes loads fail on purpose, for testing error handling.
+///
+/// Resolvers that panic on [`Error::LoadFailed`](../enum.Error.html#variant.LoadFailed) tend
+/// to survive review because that path never runs in tests. This is the recommended way to
+/// run it deterministically: wrap the connection, program a policy per model type, and the
+/// affected loads misbehave accordingly:
+///
+/// - [`fail_always`](struct.FailingLoader.html#method.fail_always): every load for the type
+///   errors.
+/// - [`fail_ids`](struct.FailingLoader.html#method.fail_ids): the given ids are dropped from
+///   every load, the rest load normally — exactly what a dangling foreign key looks like.
+/// - [`fail_nth_call`](struct.FailingLoader.html#method.fail_nth_call): one specific call
+///   errors, calls before and after work.
+/// - [`return_at_most`](struct.FailingLoader.html#method.return_at_most): loads are capped to
+///   the first few ids, producing partial results.
+///
+/// Wrapping a [`MockConnection`](struct.MockConnection.html), call
+/// [`load`](struct.FailingLoader.html#method.load) from your `LoadFrom`/`load_children` code.
+/// Any other loader, sync or async, can apply the same policies by filtering its id batch
+/// through [`surviving_ids`](struct.FailingLoader.html#method.surviving_ids) before loading.
+///
+/// Clones share the policies and call counts.
+#[derive(Clone)]
+pub struct FailingLoader<C> {
+    inner: C,
+    state: Arc<Mutex<FailState>>,
+}
+
+#[derive(Default)]
+struct FailState {
+    policies: HashMap<TypeId, FailPolicy>,
+    calls: HashMap<TypeId, usize>,
+}
+
+#[derive(Debug, Clone)]
+enum FailPolicy {
+    FailAlways(String),
+    FailIds(Vec<String>),
+    FailNthCall(usize, String),
+    ReturnAtMost(usize),
+}
+
+impl<C> FailingLoader<C> {
+    /// Wrap a connection. Without any policies all loads pass through unchanged.
+    pub fn new(inner: C) -> Self {
+        FailingLoader {
+            inner,
+            state: Arc::new(Mutex::new(FailState::default())),
+        }
+    }
+
+    /// The wrapped connection.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Make every load for the model type fail with the given message.
+    pub fn fail_always<T: 'static>(&self, message: &str) {
+        self.set_policy::<T>(FailPolicy::FailAlways(message.to_owned()));
+    }
+
+    /// Drop the given ids from every load for the model type. The remaining ids load
+    /// normally, so parents pointing at a dropped id see a failed load while the others are
+    /// unaffected.
+    pub fn fail_ids<T: 'static, K: fmt::Debug>(&self, ids: &[K]) {
+        self.set_policy::<T>(FailPolicy::FailIds(
+            ids.iter().map(|id| format!("{:?}", id)).collect(),
+        ));
+    }
+
+    /// Make the `n`th load (1-based) for the model type fail with the given message. Calls
+    /// before and after it work normally.
+    pub fn fail_nth_call<T: 'static>(&self, n: usize, message: &str) {
+        self.set_policy::<T>(FailPolicy::FailNthCall(n, message.to_owned()));
+    }
+
+    /// Cap every load for the model type to its first `count` ids, producing partial results.
+    pub fn return_at_most<T: 'static>(&self, count: usize) {
+        self.set_policy::<T>(FailPolicy::ReturnAtMost(count));
+    }
+
+    /// Apply the policy for the model type to a batch of ids.
+    ///
+    /// Returns the ids that should actually be loaded, or the injected error. Custom loaders
+    /// — including async ones — get the whole policy surface by calling this first and then
+    /// loading only the surviving ids.
+    pub fn surviving_ids<T: 'static, K: fmt::Debug + Clone>(
+        &self,
+        ids: &[K],
+    ) -> Result<Vec<K>, MockStoreError> {
+        let mut state = self.state.lock().unwrap();
+        let call = state.calls.entry(TypeId::of::<T>()).or_insert(0);
+        *call += 1;
+        let call = *call;
+
+        match state.policies.get(&TypeId::of::<T>()) {
+            None => Ok(ids.to_vec()),
+            Some(FailPolicy::FailAlways(message)) => Err(MockStoreError {
+                message: message.clone(),
+            }),
+            Some(FailPolicy::FailIds(failing)) => Ok(ids
+                .iter()
+                .filter(|id| !failing.contains(&format!("{:?}", id)))
+                .cloned()
+                .collect()),
+            Some(FailPolicy::FailNthCall(n, message)) if *n == call => Err(MockStoreError {
+                message: message.clone(),
+            }),
+            Some(FailPolicy::FailNthCall(..)) => Ok(ids.to_vec()),
+            Some(FailPolicy::ReturnAtMost(count)) => Ok(ids.iter().take(*count).cloned().collect()),
+        }
+    }
+
+    fn set_policy<T: 'static>(&self, policy: FailPolicy) {
+        self.state
+            .lock()
+            .unwrap()
+            .policies
+            .insert(TypeId::of::<T>(), policy);
+    }
+}
+
+impl FailingLoader<MockConnection> {
+    /// Apply the policy for the model type and load the surviving ids from the wrapped mock
+    /// store.
+    pub fn load<T: MockModel>(&self, ids: &[T::Id]) -> Result<Vec<T>, MockStoreError> {
+        let ids = self.surviving_ids::<T, _>(ids)?;
+        self.inner.load::<T>(&ids)
+    }
+}
+
+impl<C> fmt::Debug for FailingLoader<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FailingLoader").finish()
+    }
+}
+
+/// What [`execute_query`](fn.execute_query.html) returns: the GraphQL response plus what it
+/// cost to produce.
+#[derive(Debug)]
+pub struct ExecutedQuery {
+    /// The `data` part of the GraphQL response.
+    pub data: juniper::Value,
+    /// Every load the query performed, in order. Loads made before the query — for example
+    /// while seeding the store — are not included.
+    pub load_log: Vec<LoadCall>,
+}
+
+impl ExecutedQuery {
+    /// How many loads the query performed in total — the number to compare against a loader
+    /// call budget.
+    pub fn load_calls(&self) -> usize {
+        self.load_log.len()
+    }
+
+    /// How many loads the query performed for one model type, by its short type name.
+    pub fn load_calls_for(&self, model: &str) -> usize {
+        self.load_log
+            .iter()
+            .filter(|call| call.model == model)
+            .count()
+    }
+}
+
+/// Execute a GraphQL query against a schema and report what it cost.
+///
+/// This is the whole "does my schema eager load properly" test in one call: it builds a
+/// context around a connection to the given store, executes the query, and returns both the
+/// response and the loads it took to produce it. Asserting on
+/// [`load_calls`](struct.ExecutedQuery.html#method.load_calls) is what actually pins the N+1
+/// behavior — a response can look right while quietly performing a load per row.
+///
+/// `make_context` receives a [`MockConnection`](struct.MockConnection.html) to the store and
+/// builds your juniper context around it. Anything else your context carries per request —
+/// root models, a cache — is installed there too.
+///
+/// Panics if the query fails to parse or resolves with errors, so tests don't have to check
+/// for that separately. See `tests/execute_query.rs` for a complete example.
+pub fn execute_query<QueryT, MutationT, CtxT, F>(
+    root_node: &juniper::RootNode<'_, QueryT, MutationT>,
+    query: &str,
+    store: &MockStore,
+    make_context: F,
+) -> ExecutedQuery
+where
+    QueryT: juniper::GraphQLType<juniper::DefaultScalarValue, Context = CtxT>,
+    MutationT: juniper::GraphQLType<juniper::DefaultScalarValue, Context = CtxT>,
+    F: FnOnce(MockConnection) -> CtxT,
+{
+    let calls_before = store.load_log().len();
+    let context = make_context(store.connection());
+
+    let (data, errors) =
+        juniper::execute(query, None, root_node, &juniper::Variables::new(), &context)
+            .unwrap_or_else(|err| panic!("the GraphQL query failed to execute\n{:#?}", err));
+
+    if !errors.is_empty() {
+        panic!("the GraphQL query resolved with errors\n{:#?}", errors);
+    }
+
+    let mut load_log = store.load_log();
+    let load_log = load_log.split_off(calls_before);
+
+    ExecutedQuery { data, load_log }
+}
@@ -0,0 +1,352 @@
+//! Measures how many allocations one `eager_load_children` pass over a user → country →
+//! continent chain performs, via a counting allocator. The intermediate vectors are pre-sized,
+//! reused, or flow through the traversal unchanged, so the number of allocations should stay
+//! flat as the parent count grows — any reallocation-on-growth or per-level buffer rebuild
+//! creeping back in makes the count scale with the input and fails the assertions here.
+
+use juniper_eager_loading::{
+    prelude::*, unique, Cache, GenericQueryTrail, HasOne, LoadFrom, LoadResult,
+};
+use juniper_from_schema::Walked;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.load(Ordering::SeqCst)
+}
+
+// The tests here each measure a delta of the global allocation counter, so they can't run
+// concurrently with each other.
+static SERIAL: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+fn serial() -> std::sync::MutexGuard<'static, ()> {
+    SERIAL.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+        pub continent_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Continent {
+        pub id: i32,
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+    continents: Vec<models::Continent>,
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LoadFrom<i32> for models::Continent {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .continents
+            .iter()
+            .filter(|continent| ids.contains(&continent.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    country: HasOne<Country>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    country: models::Country,
+    continent: HasOne<Continent>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Continent {
+    continent: models::Continent,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+            continent: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Continent {
+    type Model = models::Continent;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            continent: model.clone(),
+        }
+    }
+}
+
+pub struct CountryContinentContext;
+
+impl EagerLoadChildrenOfType<Continent, EverythingTrail, CountryContinentContext, ()> for Country {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Continent, ())>, Self::Error> {
+        let ids = models
+            .iter()
+            .map(|model| model.continent_id)
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Continent>, Self::Error> {
+        <models::Continent as LoadFrom<i32>>::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Continent, &())) -> bool {
+        node.country.continent_id == (child.0).continent.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Continent) {
+        node.continent.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.continent.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Continent, _, CountryContinentContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Continent {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+struct UserCountryContext;
+
+impl EagerLoadChildrenOfType<Country, EverythingTrail, UserCountryContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+        let ids = models
+            .iter()
+            .map(|model| model.country_id)
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Country>, Self::Error> {
+        <models::Country as LoadFrom<i32>>::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+        node.user.country_id == (child.0).country.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.country.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+fn eager_load_allocations(parents: i32) -> usize {
+    let distinct_children = 10;
+    let distinct_continents = 5;
+    let db = Db {
+        countries: (0..distinct_children)
+            .map(|id| models::Country {
+                id,
+                continent_id: id % distinct_continents,
+            })
+            .collect(),
+        continents: (0..distinct_continents)
+            .map(|id| models::Continent { id })
+            .collect(),
+    };
+    let user_models = (0..parents)
+        .map(|id| models::User {
+            id,
+            country_id: id % distinct_children,
+        })
+        .collect::<Vec<_>>();
+
+    let mut users = User::from_db_models(&user_models);
+
+    let before = allocations();
+    User::eager_load_all_children_for_each(&mut users, &user_models, &db, &EverythingTrail)
+        .unwrap();
+    let after = allocations();
+
+    after - before
+}
+
+#[test]
+fn allocation_count_stays_flat_as_the_parent_count_grows() {
+    let _guard = serial();
+
+    // Warm up so one-time lazy allocations (runtime machinery and the like) don't end up
+    // counted against the first measured run.
+    eager_load_allocations(100);
+
+    let small = eager_load_allocations(100);
+    let large = eager_load_allocations(10_000);
+
+    // The same fixed set of intermediate vectors should be allocated regardless of input size.
+    // Reallocation-on-growth would make the large run scale with the parent count.
+    assert_eq!(small, large, "allocations scale with the number of parents");
+    assert!(
+        small < 64,
+        "expected a small fixed number of allocations, got {}",
+        small
+    );
+}
+
+#[test]
+fn probing_a_cache_into_reused_buffers_does_not_allocate() {
+    let _guard = serial();
+
+    let ids = (0..100_000).collect::<Vec<i32>>();
+    let mut cache = Cache::<i32>::new();
+    for id in ids.iter().filter(|id| *id % 2 == 0) {
+        cache.insert(*id, *id);
+    }
+
+    let mut found = Vec::new();
+    let mut missing = Vec::new();
+
+    // First probe grows the buffers to size.
+    cache.get_many_into::<i32>(&ids, &mut found, &mut missing);
+
+    found.clear();
+    missing.clear();
+
+    let before = allocations();
+    cache.get_many_into::<i32>(&ids, &mut found, &mut missing);
+    let after = allocations();
+
+    assert_eq!(found.len(), 50_000);
+    assert_eq!(missing.len(), 50_000);
+    assert_eq!(after - before, 0, "reused buffers should not allocate");
+}
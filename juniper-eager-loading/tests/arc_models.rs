@@ -0,0 +1,189 @@
+//! Storing the model behind `Arc` with `#[eager_loading(model = "Arc<models::Country>")]`.
+//! When hundreds of parents point at the same child, each parent's edge then shares one model
+//! allocation instead of holding an independent clone — the pointer equality assertions pin
+//! that down. Loading goes through the blanket `LoadFrom` impl for `Arc<T>`, so the loader
+//! code is written against the plain model type.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasOne, LoadFrom};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+use std::sync::Arc;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        country: Country!
+    }
+
+    type Country {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+
+        let mut users = User::from_db_models(&ctx.users);
+        User::eager_load_all_children_for_each(&mut users, &ctx.users, &ctx.db, trail)?;
+
+        // Parents sharing a child share its model allocation; distinct children don't.
+        let country = |user: &User| Arc::clone(&user.country.try_unwrap().unwrap().country);
+        assert!(Arc::ptr_eq(&country(&users[0]), &country(&users[1])));
+        assert!(!Arc::ptr_eq(&country(&users[0]), &country(&users[2])));
+
+        Ok(users)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        Ok(self.country.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(
+    connection = "Db",
+    error = "Box<dyn std::error::Error>",
+    model = "Arc<models::Country>"
+)]
+pub struct Country {
+    country: Arc<models::Country>,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.country.id)
+    }
+}
+
+#[test]
+fn parents_sharing_a_child_share_its_model_allocation() {
+    let ctx = Context {
+        db: Db {
+            countries: vec![models::Country { id: 1 }, models::Country { id: 2 }],
+        },
+        users: vec![
+            models::User {
+                id: 1,
+                country_id: 1,
+            },
+            models::User {
+                id: 2,
+                country_id: 1,
+            },
+            models::User {
+                id: 3,
+                country_id: 2,
+            },
+        ],
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id country { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_eq!(
+        json!({
+            "users": [
+                { "id": 1, "country": { "id": 1 } },
+                { "id": 2, "country": { "id": 1 } },
+                { "id": 3, "country": { "id": 2 } },
+            ],
+        }),
+        json,
+    );
+}
@@ -0,0 +1,186 @@
+//! `has_many` with `ids_field`: the parent model carries an array column of child ids. The
+//! ids are flattened and deduplicated across parents, the children are loaded in one query,
+//! and each parent gets its children back in the order of its own array.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasMany, LoadFrom};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+use std::sync::Mutex;
+
+static LOADED_IDS: Mutex<Vec<Vec<i32>>> = Mutex::new(Vec::new());
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      posts: [Post!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type Post {
+        id: Int!
+        tags: [Tag!]!
+    }
+
+    type Tag {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    tags: Vec<models::Tag>,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Post {
+        pub id: i32,
+        pub tag_ids: Vec<i32>,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Tag {
+        pub id: i32,
+    }
+}
+
+impl LoadFrom<i32> for models::Tag {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        LOADED_IDS.lock().unwrap().push(ids.to_vec());
+        Ok(db
+            .tags
+            .iter()
+            .filter(|tag| ids.contains(&tag.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    posts: Vec<models::Post>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_posts<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, Post, Walked>,
+    ) -> FieldResult<Vec<Post>> {
+        let ctx = executor.context();
+
+        let mut posts = Post::from_db_models(&ctx.posts);
+        Post::eager_load_all_children_for_each(&mut posts, &ctx.posts, &ctx.db, trail)?;
+
+        Ok(posts)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Post {
+    post: models::Post,
+
+    #[has_many(ids_field = "tag_ids", root_model_field = "tag")]
+    tags: HasMany<Tag>,
+}
+
+impl PostFields for Post {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.post.id)
+    }
+
+    fn field_tags(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Tag, Walked>,
+    ) -> FieldResult<&Vec<Tag>> {
+        Ok(self.tags.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Tag {
+    tag: models::Tag,
+}
+
+impl TagFields for Tag {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.tag.id)
+    }
+}
+
+#[test]
+fn array_foreign_keys_load_once_and_keep_each_parents_order() {
+    let ctx = Context {
+        db: Db {
+            tags: (1..=3).map(|id| models::Tag { id }).collect(),
+        },
+        posts: vec![
+            models::Post {
+                id: 1,
+                tag_ids: vec![2, 1],
+            },
+            models::Post {
+                id: 2,
+                tag_ids: vec![1, 3, 2],
+            },
+            models::Post {
+                id: 3,
+                tag_ids: vec![],
+            },
+        ],
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ posts { id tags { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+
+    // Each post's tags come back in the order of its own array.
+    assert_json_eq!(
+        json!({
+            "posts": [
+                { "id": 1, "tags": [{ "id": 2 }, { "id": 1 }] },
+                { "id": 2, "tags": [{ "id": 1 }, { "id": 3 }, { "id": 2 }] },
+                { "id": 3, "tags": [] },
+            ],
+        }),
+        json,
+    );
+
+    // One query, overlapping arrays deduplicated, first-seen order.
+    assert_eq!(*LOADED_IDS.lock().unwrap(), [vec![2, 1, 3]]);
+}
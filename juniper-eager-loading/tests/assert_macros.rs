@@ -0,0 +1,101 @@
+//! The assertion macros should pass for the states they assert and give readable failure
+//! messages, naming the edge and showing its actual state, when they don't.
+
+use juniper_eager_loading::{
+    assert_loaded, assert_loaded_items, assert_loaded_none, assert_not_loaded, HasMany,
+    HasManyThrough, HasOne, OptionHasOne,
+};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+fn panic_message<F: FnOnce()>(f: F) -> String {
+    let err = catch_unwind(AssertUnwindSafe(f)).expect_err("should have panicked");
+    err.downcast_ref::<String>()
+        .cloned()
+        .expect("panic message should be a `String`")
+}
+
+#[test]
+fn passing_assertions() {
+    let mut country = HasOne::default();
+    country.loaded("Denmark");
+    assert_loaded!(country, "Denmark");
+
+    let nickname = OptionHasOne::<String>::default();
+    assert_loaded_none!(nickname);
+
+    let mut cars = HasMany::default();
+    cars.loaded("Saab");
+    cars.loaded("Volvo");
+    assert_loaded_items!(cars, ["Saab", "Volvo"]);
+
+    let mut companies = HasManyThrough::default();
+    companies.loaded("ACME");
+    assert_loaded_items!(companies, ["ACME"]);
+
+    let secret = HasOne::<String>::default();
+    assert_not_loaded!(secret);
+}
+
+#[test]
+fn a_not_loaded_edge_fails_assert_loaded_with_the_error() {
+    let country = HasOne::<&str>::default();
+
+    let message = panic_message(|| assert_loaded!(country, "Denmark"));
+
+    assert_eq!(
+        message,
+        "`country` should be loaded, but `HasOne` should have been eager loaded, but wasn't",
+    );
+}
+
+#[test]
+fn a_different_value_fails_assert_loaded_with_both_values() {
+    let mut country = HasOne::default();
+    country.loaded("Sweden");
+
+    let message = panic_message(|| assert_loaded!(country, "Denmark"));
+
+    assert_eq!(
+        message,
+        "`country` is loaded with a different value\n  actual: `\"Sweden\"`\nexpected: `\"Denmark\"`",
+    );
+}
+
+#[test]
+fn a_loaded_value_fails_assert_loaded_none() {
+    let mut nickname = OptionHasOne::default();
+    nickname.loaded("Dave");
+
+    let message = panic_message(|| assert_loaded_none!(nickname));
+
+    assert_eq!(
+        message,
+        "`nickname` should be loaded with `None`, but was loaded with `\"Dave\"`",
+    );
+}
+
+#[test]
+fn different_items_fail_assert_loaded_items_with_both_lists() {
+    let mut cars = HasMany::default();
+    cars.loaded("Saab");
+
+    let message = panic_message(|| assert_loaded_items!(cars, ["Saab", "Volvo"]));
+
+    assert_eq!(
+        message,
+        "`cars` is loaded with different items\n  actual: `[\"Saab\"]`\nexpected: `[\"Saab\", \"Volvo\"]`",
+    );
+}
+
+#[test]
+fn a_loaded_edge_fails_assert_not_loaded() {
+    let mut secret = HasOne::default();
+    secret.loaded("hunter2");
+
+    let message = panic_message(|| assert_not_loaded!(secret));
+
+    assert_eq!(
+        message,
+        "`secret` should not be loaded, but was loaded with `\"hunter2\"`",
+    );
+}
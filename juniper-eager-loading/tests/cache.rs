@@ -0,0 +1,511 @@
+use juniper_eager_loading::{Cache, CacheStorage, LayeredCache, SharedCache};
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Car {
+    pub id: i32,
+    pub user_id: i32,
+}
+
+fn car(id: i32, user_id: i32) -> Car {
+    Car { id, user_id }
+}
+
+#[test]
+fn vecs_are_stored_and_borrowed_back_without_cloning() {
+    let mut cache = Cache::<i32>::new();
+    cache.insert_vec(1, vec![car(10, 1), car(11, 1)]);
+
+    let cars = cache.get_vec::<Car>(1).unwrap();
+    assert_eq!(cars, [car(10, 1), car(11, 1)]);
+
+    assert_eq!(cache.get_vec::<Car>(2), None);
+    assert_eq!(cache.hits(), 1);
+    assert_eq!(cache.misses(), 1);
+}
+
+#[test]
+fn types_sharing_an_id_space_do_not_collide() {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    let mut cache = Cache::<i32>::new();
+    cache.insert(1, car(1, 1));
+    cache.insert(1, Country { id: 1 });
+
+    // Both models use the same numeric id but each type only sees its own entry.
+    assert_eq!(cache.get::<Car>(1), Some(car(1, 1)));
+    assert_eq!(cache.get::<Country>(1), Some(Country { id: 1 }));
+
+    cache.insert_vec(1, vec![car(10, 1)]);
+    assert_eq!(cache.get_vec::<Country>(1), None);
+}
+
+#[test]
+fn vec_entries_do_not_collide_with_plain_entries() {
+    let mut cache = Cache::<i32>::new();
+    cache.insert(1, vec![car(10, 1)]);
+    cache.insert_vec(1, vec![car(20, 1)]);
+
+    // The generic entry and the vec entry live side by side.
+    assert_eq!(cache.get::<Vec<Car>>(1), Some(vec![car(10, 1)]));
+    assert_eq!(cache.get_vec::<Car>(1), Some(&[car(20, 1)][..]));
+}
+
+#[test]
+fn get_many_partitions_exactly_like_the_per_id_path() {
+    let mut cache = Cache::<i32>::new();
+    for id in 0..10 {
+        if id % 3 == 0 {
+            cache.insert(id, car(id, 1));
+        }
+    }
+    let ids = (0..10).collect::<Vec<_>>();
+
+    let (found, missing) = cache.get_many::<Car>(&ids);
+
+    let mut found_per_id = Vec::new();
+    let mut missing_per_id = Vec::new();
+    for id in &ids {
+        match cache.get::<Car>(*id) {
+            Some(car) => found_per_id.push(car),
+            None => missing_per_id.push(*id),
+        }
+    }
+
+    assert_eq!(found, found_per_id);
+    assert_eq!(missing, missing_per_id);
+}
+
+#[test]
+fn get_many_updates_the_counters_with_aggregate_counts() {
+    let mut cache = Cache::<i32>::new();
+    cache.insert(1, car(10, 1));
+    cache.insert(2, car(11, 2));
+
+    let (found, missing) = cache.get_many::<Car>(&[1, 2, 3, 4, 5]);
+
+    assert_eq!(found.len(), 2);
+    assert_eq!(missing, [3, 4, 5]);
+    assert_eq!(cache.hits(), 2);
+    assert_eq!(cache.misses(), 3);
+}
+
+#[test]
+fn inserting_a_vec_again_replaces_the_previous_one() {
+    let mut cache = Cache::<i32>::new();
+    cache.insert_vec(1, vec![car(10, 1)]);
+    cache.insert_vec(1, vec![car(11, 1), car(12, 1)]);
+
+    assert_eq!(cache.get_vec::<Car>(1).map(|cars| cars.len()), Some(2));
+}
+
+#[test]
+fn probing_does_not_touch_the_counters() {
+    let mut cache = Cache::<i32>::new();
+    cache.insert(1, car(10, 1));
+
+    // A cold-start scan through `probe` should leave the stats reflecting real reuse only.
+    for id in 0..100 {
+        cache.probe::<Car>(id);
+    }
+
+    assert_eq!(cache.probe::<Car>(1), Some(car(10, 1)));
+    assert_eq!(cache.hits(), 0);
+    assert_eq!(cache.misses(), 0);
+
+    // Warm reuse through `get` still counts.
+    assert_eq!(cache.get::<Car>(1), Some(car(10, 1)));
+    assert_eq!(cache.hits(), 1);
+}
+
+#[test]
+fn a_bounded_cache_never_exceeds_its_size_under_load() {
+    let mut cache = Cache::<i32>::with_max_entries(10);
+
+    for id in 0..10_000 {
+        cache.insert(id, car(id, 1));
+        assert!(cache.len() <= 10, "cache grew to {} entries", cache.len());
+    }
+    assert_eq!(cache.len(), 10);
+
+    // The survivors are the ten most recently inserted entries.
+    assert_eq!(cache.get::<Car>(9_999), Some(car(9_999, 1)));
+    assert_eq!(cache.get::<Car>(0), None);
+}
+
+#[test]
+fn eviction_picks_the_least_recently_used_entry() {
+    let mut cache = Cache::<i32>::with_max_entries(3);
+    cache.insert(1, car(1, 1));
+    cache.insert(2, car(2, 1));
+    cache.insert(3, car(3, 1));
+
+    // Touch 1 so 2 becomes the oldest, then overflow.
+    assert_eq!(cache.get::<Car>(1), Some(car(1, 1)));
+    cache.insert(4, car(4, 1));
+
+    assert_eq!(cache.get::<Car>(1), Some(car(1, 1)));
+    assert_eq!(cache.get::<Car>(3), Some(car(3, 1)));
+    assert_eq!(cache.get::<Car>(4), Some(car(4, 1)));
+    // The evicted entry is an ordinary miss when requested again.
+    let misses_before = cache.misses();
+    assert_eq!(cache.get::<Car>(2), None);
+    assert_eq!(cache.misses(), misses_before + 1);
+}
+
+#[test]
+fn replacing_an_entry_does_not_evict_anything() {
+    let mut cache = Cache::<i32>::with_max_entries(2);
+    cache.insert(1, car(1, 1));
+    cache.insert(2, car(2, 1));
+
+    // Same type and key: a replacement, not a new entry.
+    cache.insert(1, car(1, 9));
+
+    assert_eq!(cache.get::<Car>(1), Some(car(1, 9)));
+    assert_eq!(cache.get::<Car>(2), Some(car(2, 1)));
+}
+
+#[test]
+fn insert_many_behaves_like_one_insert_per_pair() {
+    let mut batched = Cache::<i32>::new();
+    batched.insert_many((0..100).map(|id| (id, car(id, 1))));
+
+    let mut one_by_one = Cache::<i32>::new();
+    for id in 0..100 {
+        one_by_one.insert(id, car(id, 1));
+    }
+
+    for id in 0..100 {
+        assert_eq!(batched.get::<Car>(id), one_by_one.get::<Car>(id));
+    }
+    assert_eq!(batched.len(), one_by_one.len());
+
+    // A batched insert replaces earlier values of the same key, like `insert` does.
+    batched.insert_many(vec![(0, car(0, 9))]);
+    assert_eq!(batched.get::<Car>(0), Some(car(0, 9)));
+}
+
+// Stress for the map internals (key shape, hasher): lots of keys across several types, with
+// every lookup checked against what was inserted.
+#[test]
+fn many_keys_across_many_types_round_trip_exactly() {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    let mut cache = Cache::<i32>::new();
+    for id in 0..10_000 {
+        cache.insert(id, car(id, id % 7));
+        if id % 2 == 0 {
+            cache.insert(id, Country { id });
+        }
+        if id % 3 == 0 {
+            cache.insert_vec(id, vec![car(id, 0), car(id + 1, 0)]);
+        }
+    }
+
+    for id in 0..10_000 {
+        assert_eq!(cache.get::<Car>(id), Some(car(id, id % 7)));
+        let country = cache.get::<Country>(id);
+        if id % 2 == 0 {
+            assert_eq!(country, Some(Country { id }));
+        } else {
+            assert_eq!(country, None);
+        }
+        let cars = cache.get_vec::<Car>(id);
+        if id % 3 == 0 {
+            assert_eq!(cars, Some(&[car(id, 0), car(id + 1, 0)][..]));
+        } else {
+            assert_eq!(cars, None);
+        }
+    }
+
+    assert_eq!(cache.hits(), 10_000 + 5_000 + 3_334);
+    assert_eq!(cache.misses(), 5_000 + 6_666);
+}
+
+#[test]
+fn stats_by_type_breaks_the_counters_down_per_type() {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    let mut cache = Cache::<i32>::new();
+    cache.insert(1, car(1, 1));
+    cache.insert(2, car(2, 1));
+
+    assert_eq!(cache.get::<Car>(1), Some(car(1, 1)));
+    assert_eq!(cache.get::<Car>(3), None);
+    // `Country` was never inserted; its misses should still be attributed to it.
+    assert_eq!(cache.get::<Country>(1), None);
+    assert_eq!(cache.get::<Country>(2), None);
+
+    let stats = cache.stats_by_type();
+    let of = |name: &str| {
+        *stats
+            .iter()
+            .find(|stats| stats.type_name.ends_with(name))
+            .unwrap()
+    };
+
+    let cars = of("Car");
+    assert_eq!((cars.hits, cars.misses, cars.entries), (1, 1, 2));
+    let countries = of("Country");
+    assert_eq!(
+        (countries.hits, countries.misses, countries.entries),
+        (0, 2, 0)
+    );
+
+    // The global counters are the sums of the per-type ones.
+    assert_eq!(
+        cache.hits(),
+        stats.iter().map(|stats| stats.hits).sum::<usize>()
+    );
+    assert_eq!(
+        cache.misses(),
+        stats.iter().map(|stats| stats.misses).sum::<usize>()
+    );
+}
+
+#[test]
+fn a_get_after_a_remove_is_a_miss() {
+    let mut cache = Cache::<i32>::new();
+    cache.insert(1, car(1, 1));
+    cache.insert(2, car(2, 1));
+
+    cache.remove::<Car>(1);
+
+    let misses_before = cache.misses();
+    assert_eq!(cache.get::<Car>(1), None);
+    assert_eq!(cache.misses(), misses_before + 1);
+    // Only the removed key is gone.
+    assert_eq!(cache.get::<Car>(2), Some(car(2, 1)));
+}
+
+#[test]
+fn clearing_a_type_leaves_other_types_untouched() {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    let mut cache = Cache::<i32>::new();
+    cache.insert(1, car(1, 1));
+    cache.insert(2, car(2, 1));
+    cache.insert(1, Country { id: 1 });
+    cache.insert_vec(1, vec![car(10, 1)]);
+
+    cache.clear_type::<Car>();
+
+    assert_eq!(cache.get::<Car>(1), None);
+    assert_eq!(cache.get::<Car>(2), None);
+    assert_eq!(cache.get::<Country>(1), Some(Country { id: 1 }));
+    // Vec entries are stored under their own type, so they survive `clear_type::<Car>`.
+    assert_eq!(cache.get_vec::<Car>(1), Some(&[car(10, 1)][..]));
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+// One test so the updates to the shared clock aren't interleaved by concurrently running tests.
+#[test]
+fn entries_expire_after_the_ttl() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+
+    static NOW_MILLIS: AtomicU64 = AtomicU64::new(0);
+    fn test_clock() -> Duration {
+        Duration::from_millis(NOW_MILLIS.load(Ordering::SeqCst))
+    }
+
+    let mut cache = Cache::<i32>::with_ttl_and_clock(Duration::from_millis(100), test_clock);
+    cache.insert(1, car(1, 1));
+
+    NOW_MILLIS.store(99, Ordering::SeqCst);
+    assert_eq!(cache.get::<Car>(1), Some(car(1, 1)));
+
+    // At the TTL the entry reads as an ordinary miss, though it still occupies memory until
+    // pruned.
+    NOW_MILLIS.store(100, Ordering::SeqCst);
+    let misses_before = cache.misses();
+    assert_eq!(cache.get::<Car>(1), None);
+    assert_eq!(cache.misses(), misses_before + 1);
+    assert_eq!(cache.probe::<Car>(1), None);
+    assert_eq!(cache.len(), 1);
+
+    cache.prune_expired();
+    assert!(cache.is_empty());
+
+    // Expiry is measured from each entry's own insert time.
+    cache.insert(2, car(2, 1));
+    NOW_MILLIS.store(150, Ordering::SeqCst);
+    cache.insert(3, car(3, 1));
+    NOW_MILLIS.store(210, Ordering::SeqCst);
+    assert_eq!(cache.get::<Car>(2), None);
+    assert_eq!(cache.get::<Car>(3), Some(car(3, 1)));
+
+    // A TTL composed with a size limit prunes expired entries before evicting live ones.
+    NOW_MILLIS.store(0, Ordering::SeqCst);
+    let mut bounded = Cache::<i32>::with_ttl_and_clock(Duration::from_millis(100), test_clock);
+    bounded.set_max_entries(2);
+    bounded.insert(1, car(1, 1));
+    NOW_MILLIS.store(150, Ordering::SeqCst);
+    bounded.insert(2, car(2, 1));
+    bounded.insert(3, car(3, 1));
+    // Entry 1 had expired, so it was pruned instead of the still-live entry 2.
+    assert_eq!(bounded.get::<Car>(2), Some(car(2, 1)));
+    assert_eq!(bounded.get::<Car>(3), Some(car(3, 1)));
+    assert_eq!(bounded.len(), 2);
+
+    // An unconfigured cache never expires anything, whatever the wall clock does.
+    let mut forever = Cache::<i32>::new();
+    forever.insert(1, car(1, 1));
+    assert_eq!(forever.get::<Car>(1), Some(car(1, 1)));
+}
+
+// The kind of helper `CacheStorage` exists for: works against whichever cache the caller has.
+fn get_or_insert(cache: &mut impl CacheStorage<i32>, id: i32) -> Car {
+    if let Some(car) = cache.get::<Car>(id) {
+        return car;
+    }
+    let loaded = car(id, 1);
+    cache.insert(id, loaded.clone());
+    loaded
+}
+
+#[test]
+fn cache_storage_is_generic_over_both_cache_kinds() {
+    let mut cache = Cache::<i32>::new();
+    assert_eq!(get_or_insert(&mut cache, 1), car(1, 1));
+    assert_eq!(cache.get::<Car>(1), Some(car(1, 1)));
+
+    let shared = SharedCache::<i32>::new();
+    let mut handle = shared.clone();
+    assert_eq!(get_or_insert(&mut handle, 1), car(1, 1));
+    // The insert went through the clone, but both handles share the storage.
+    assert_eq!(shared.get::<Car>(1), Some(car(1, 1)));
+}
+
+#[test]
+fn a_layered_cache_hits_across_requests_through_its_second_level() {
+    let shared = SharedCache::<i32>::new();
+
+    // Request one loads the model and writes through to both levels.
+    let mut first_request = LayeredCache::new(Cache::new(), shared.clone());
+    first_request.insert(1, car(1, 1));
+    assert_eq!(first_request.get::<Car>(1), Some(car(1, 1)));
+
+    // Request two starts with a cold front but still hits, via the shared second level.
+    let second_request = LayeredCache::new(Cache::new(), shared.clone());
+    assert_eq!(second_request.get::<Car>(1), Some(car(1, 1)));
+    // The front never answered, so its counters don't claim the hit.
+    assert_eq!(second_request.front().hits(), 0);
+
+    // Invalidation goes through both levels.
+    let mut second_request = second_request;
+    second_request.remove::<Car>(1);
+    assert_eq!(second_request.get::<Car>(1), None);
+    assert_eq!(shared.get::<Car>(1), None);
+}
+
+// A stand-in for an external second level (Redis, moka, ...): any `CacheStorage` works.
+#[test]
+fn a_custom_backend_only_sees_lookups_the_front_missed() {
+    let mut layered = LayeredCache::new(Cache::new(), Cache::<i32>::new());
+    layered.insert(1, car(1, 1));
+
+    assert_eq!(layered.get::<Car>(1), Some(car(1, 1)));
+    assert_eq!(layered.get::<Car>(1), Some(car(1, 1)));
+
+    // Both lookups were answered by the front, so the backend's counters never moved.
+    let backend = layered.into_backend();
+    assert_eq!(backend.hits(), 0);
+    assert_eq!(backend.misses(), 0);
+}
+
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn the_per_request_cache_can_move_between_threads() {
+    let mut cache = Cache::<i32>::new();
+    cache.insert(1, car(10, 1));
+
+    let cache = std::thread::spawn(move || cache).join().unwrap();
+
+    assert_eq!(cache.get::<Car>(1), Some(car(10, 1)));
+}
+
+#[test]
+fn interned_cache_round_trips_string_keys() {
+    let mut cache = Cache::with_interner();
+    cache.insert("a", car(10, 1));
+    cache.insert("b", car(11, 2));
+
+    assert_eq!(cache.get::<Car>("a"), Some(car(10, 1)));
+    assert_eq!(cache.get::<Car>("b"), Some(car(11, 2)));
+    assert_eq!(cache.get::<Car>("c"), None);
+    assert_eq!(cache.hits(), 2);
+    assert_eq!(cache.misses(), 1);
+}
+
+#[test]
+fn interned_keys_separate_values_by_type() {
+    let mut cache = Cache::with_interner();
+    cache.insert("a", car(10, 1));
+    cache.insert("a", "name".to_string());
+
+    assert_eq!(cache.get::<Car>("a"), Some(car(10, 1)));
+    assert_eq!(cache.get::<String>("a"), Some("name".to_string()));
+}
+
+#[test]
+fn string_keyed_caches_can_be_looked_up_with_a_str() {
+    let mut cache = Cache::<String>::new();
+    cache.insert("denmark".to_string(), car(1, 1));
+
+    // No owned `String` has to be built for the lookup.
+    assert_eq!(cache.get_by::<Car, str>("denmark"), Some(car(1, 1)));
+    assert_eq!(cache.get_by::<Car, str>("sweden"), None);
+    assert_eq!(cache.hits(), 1);
+    assert_eq!(cache.misses(), 1);
+
+    // The owned-key path still works and sees the same entries.
+    assert_eq!(cache.get::<Car>("denmark".to_string()), Some(car(1, 1)));
+}
+
+#[test]
+fn counter_totals_are_exact_across_get_and_get_many() {
+    let mut cache = Cache::<i32>::new();
+    for id in 0..50 {
+        cache.insert(id, car(id, 1));
+    }
+
+    for id in 0..100 {
+        cache.get::<Car>(id);
+    }
+    let ids = (0..100).collect::<Vec<_>>();
+    cache.get_many::<Car>(&ids);
+
+    assert_eq!(cache.hits(), 100);
+    assert_eq!(cache.misses(), 100);
+}
+
+#[test]
+fn get_many_into_appends_to_the_given_buffers() {
+    let mut cache = Cache::<i32>::new();
+    cache.insert(1, car(10, 1));
+
+    let mut found = vec![car(99, 9)];
+    let mut missing = vec![42];
+
+    cache.get_many_into::<Car>(&[1, 2], &mut found, &mut missing);
+
+    assert_eq!(found, [car(99, 9), car(10, 1)]);
+    assert_eq!(missing, [42, 2]);
+    assert_eq!(cache.hits(), 1);
+    assert_eq!(cache.misses(), 1);
+}
@@ -0,0 +1,9 @@
+//! Compile-fail coverage for the derive's diagnostics: misusing `#[derive(EagerLoading)]`
+//! should produce a spanned error saying what was expected, not a panic inside the proc macro
+//! or a trait-bound error pointing at the `#[derive]` line.
+
+#[test]
+fn derive_misuse_produces_spanned_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile_fail/*.rs");
+}
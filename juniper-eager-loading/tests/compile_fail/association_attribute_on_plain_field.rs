@@ -0,0 +1,22 @@
+use juniper_eager_loading::EagerLoading;
+
+pub struct Db;
+
+pub mod models {
+    #[derive(Clone)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+}
+
+#[derive(Clone, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(root_model_field = "country")]
+    country_id: i32,
+}
+
+fn main() {}
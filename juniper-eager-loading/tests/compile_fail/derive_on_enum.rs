@@ -0,0 +1,12 @@
+use juniper_eager_loading::EagerLoading;
+
+pub struct Db;
+
+#[derive(EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub enum User {
+    Admin,
+    Regular,
+}
+
+fn main() {}
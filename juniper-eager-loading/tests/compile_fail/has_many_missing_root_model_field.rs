@@ -0,0 +1,24 @@
+use juniper_eager_loading::{EagerLoading, HasMany};
+
+pub struct Db;
+
+pub mod models {
+    #[derive(Clone)]
+    pub struct User {
+        pub id: i32,
+    }
+}
+
+#[derive(Clone)]
+pub struct Car;
+
+#[derive(Clone, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_many()]
+    cars: HasMany<Car>,
+}
+
+fn main() {}
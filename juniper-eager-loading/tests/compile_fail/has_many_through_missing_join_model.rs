@@ -0,0 +1,24 @@
+use juniper_eager_loading::{EagerLoading, HasManyThrough};
+
+pub struct Db;
+
+pub mod models {
+    #[derive(Clone)]
+    pub struct User {
+        pub id: i32,
+    }
+}
+
+#[derive(Clone)]
+pub struct Company;
+
+#[derive(Clone, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_many_through(model_field = "company")]
+    companies: HasManyThrough<Company>,
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+use juniper_eager_loading::{EagerLoading, OptionHasOne};
+
+pub struct Db;
+
+pub mod models {
+    #[derive(Clone)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: Option<i32>,
+    }
+}
+
+#[derive(Clone)]
+pub struct Country;
+
+#[derive(Clone, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[option_has_one(root_model_field = "country", on_missing = "explode")]
+    country: OptionHasOne<Country>,
+}
+
+fn main() {}
@@ -0,0 +1,187 @@
+//! A guard against reintroducing quadratic behavior in the default eager loading methods.
+//!
+//! The workload — ~3k parents with ~200 has-many children each, 600k child rows in total —
+//! comes from a real report of multi-second response times. The threshold is deliberately
+//! generous so the test isn't flaky on slow machines; it exists to catch the order-of-magnitude
+//! regressions that per-parent scans or per-level clone storms cause, not small constant
+//! factors.
+//!
+//! Run it explicitly, in release mode, since it's far too slow for the regular debug test runs:
+//!
+//! ```text
+//! cargo test --release --test complexity_guard -- --ignored
+//! ```
+
+use juniper_eager_loading::{prelude::*, GenericQueryTrail, HasMany, LoadFrom, LoadResult};
+use juniper_from_schema::Walked;
+use std::time::{Duration, Instant};
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+}
+
+pub struct Db {
+    cars: Vec<models::Car>,
+}
+
+impl LoadFrom<models::User> for models::Car {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(_users: &[models::User], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db.cars.clone())
+    }
+}
+
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    cars: HasMany<Car>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Car {
+    car: models::Car,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            cars: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Car {
+    type Model = models::Car;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self { car: model.clone() }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Car {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCarsContext;
+
+impl EagerLoadChildrenOfType<Car, EverythingTrail, UserCarsContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Car, ())>, Self::Error> {
+        let models = <models::Car as LoadFrom<models::User>>::load(models, db)?;
+        Ok(LoadResult::Models(
+            models.into_iter().map(|model| (model, ())).collect(),
+        ))
+    }
+
+    fn load_children(
+        _ids: &[Self::ChildId],
+        _db: &Self::Connection,
+    ) -> Result<Vec<models::Car>, Self::Error> {
+        unreachable!("`child_ids` always returns models")
+    }
+
+    fn is_child_of(node: &Self, child: &(Car, &())) -> bool {
+        node.user.id == (child.0).car.user_id
+    }
+
+    fn loaded_child(node: &mut Self, child: Car) {
+        node.cars.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.cars.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Car, _, UserCarsContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+const PARENTS: i32 = 3_000;
+const CHILDREN_PER_PARENT: i32 = 200;
+const THRESHOLD: Duration = Duration::from_secs(10);
+
+#[test]
+#[ignore]
+fn three_thousand_parents_with_two_hundred_children_each_stays_under_threshold() {
+    let user_models = (0..PARENTS)
+        .map(|id| models::User { id })
+        .collect::<Vec<_>>();
+    let db = Db {
+        cars: (0..PARENTS * CHILDREN_PER_PARENT)
+            .map(|id| models::Car {
+                id,
+                user_id: id % PARENTS,
+            })
+            .collect(),
+    };
+
+    let started = Instant::now();
+
+    let mut users = User::from_db_models(&user_models);
+    User::eager_load_all_children_for_each(&mut users, &user_models, &db, &EverythingTrail)
+        .unwrap();
+
+    let elapsed = started.elapsed();
+
+    for user in &users {
+        assert_eq!(
+            user.cars.try_unwrap().unwrap().len(),
+            CHILDREN_PER_PARENT as usize
+        );
+    }
+
+    assert!(
+        elapsed < THRESHOLD,
+        "eager loading {}x{} took {:?}, threshold is {:?}",
+        PARENTS,
+        CHILDREN_PER_PARENT,
+        elapsed,
+        THRESHOLD
+    );
+}
@@ -0,0 +1,197 @@
+//! Composite primary keys: `GraphqlNodeForModel::Id` only requires `Hash + Eq`, so a tuple
+//! works as an id — and `#[eager_loading(id = "(i32, i32)")]` accepts the tuple type directly.
+//! The children here key on `(org_id, user_id)` and still load in one batched query.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasOne, LoadFrom};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static EMPLOYMENT_LOADS: AtomicUsize = AtomicUsize::new(0);
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        employment: Employment!
+    }
+
+    type Employment {
+        orgId: Int!
+        userId: Int!
+    }
+}
+
+pub struct Db {
+    employments: Vec<models::Employment>,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub employment_id: (i32, i32),
+    }
+
+    // A legacy table keyed on `(org_id, user_id)`; the `id` field mirrors the composite key.
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Employment {
+        pub id: (i32, i32),
+    }
+}
+
+impl LoadFrom<(i32, i32)> for models::Employment {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[(i32, i32)], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        EMPLOYMENT_LOADS.fetch_add(1, Ordering::SeqCst);
+        Ok(db
+            .employments
+            .iter()
+            .filter(|employment| ids.contains(&employment.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+
+        let mut users = User::from_db_models(&ctx.users);
+        User::eager_load_all_children_for_each(&mut users, &ctx.users, &ctx.db, trail)?;
+
+        Ok(users)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    employment: HasOne<Employment>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_employment(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Employment, Walked>,
+    ) -> FieldResult<&Employment> {
+        Ok(self.employment.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(
+    connection = "Db",
+    error = "Box<dyn std::error::Error>",
+    id = "(i32, i32)"
+)]
+pub struct Employment {
+    employment: models::Employment,
+}
+
+impl EmploymentFields for Employment {
+    fn field_org_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.employment.id.0)
+    }
+
+    fn field_user_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.employment.id.1)
+    }
+}
+
+#[test]
+fn composite_key_children_load_in_one_query() {
+    let ctx = Context {
+        db: Db {
+            employments: vec![
+                models::Employment { id: (1, 1) },
+                models::Employment { id: (1, 2) },
+                models::Employment { id: (2, 1) },
+            ],
+        },
+        users: vec![
+            models::User {
+                id: 1,
+                employment_id: (1, 1),
+            },
+            models::User {
+                id: 2,
+                employment_id: (1, 2),
+            },
+            models::User {
+                id: 3,
+                employment_id: (2, 1),
+            },
+        ],
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id employment { orgId userId } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+
+    assert_eq!(EMPLOYMENT_LOADS.load(Ordering::SeqCst), 1);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_eq!(
+        json!({
+            "users": [
+                { "id": 1, "employment": { "orgId": 1, "userId": 1 } },
+                { "id": 2, "employment": { "orgId": 1, "userId": 2 } },
+                { "id": 3, "employment": { "orgId": 2, "userId": 1 } },
+            ],
+        }),
+        json,
+    );
+}
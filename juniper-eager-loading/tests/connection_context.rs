@@ -0,0 +1,217 @@
+//! `DbAndContext` threads request-scoped context into loaders through the one channel the
+//! eager loading machinery already has: the connection. The loaders here apply row-level
+//! authorization — the viewer only sees their own cars — and bump a request-scoped metrics
+//! counter, all without a bespoke connection type.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, DbAndContext, EagerLoading, HasMany};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        cars: [Car!]! @juniper(ownership: "owned")
+    }
+
+    type Car {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    cars: Vec<models::Car>,
+}
+
+pub struct RequestContext {
+    viewer_id: i32,
+    loads: Arc<AtomicUsize>,
+}
+
+type Conn = DbAndContext<Db, RequestContext>;
+
+pub mod models {
+    use std::sync::atomic::Ordering;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+
+    // Required by the `EagerLoadChildrenOfType` impl, but the has-many flow never calls it:
+    // children are loaded from the parent models below.
+    impl juniper_eager_loading::LoadFrom<i32> for Car {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Conn;
+
+        fn load(ids: &[i32], connection: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            Ok(connection
+                .db
+                .cars
+                .iter()
+                .filter(|car| ids.contains(&car.id))
+                .cloned()
+                .collect())
+        }
+    }
+
+    impl juniper_eager_loading::LoadFrom<User> for Car {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Conn;
+
+        fn load(users: &[User], connection: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            connection.ctx.loads.fetch_add(1, Ordering::SeqCst);
+            let user_ids = users.iter().map(|user| user.id).collect::<Vec<_>>();
+            Ok(connection
+                .db
+                .cars
+                .iter()
+                .filter(|car| user_ids.contains(&car.user_id))
+                // Row-level authorization straight from the request context: the viewer only
+                // sees their own cars.
+                .filter(|car| car.user_id == connection.ctx.viewer_id)
+                .cloned()
+                .collect())
+        }
+    }
+}
+
+pub struct Context {
+    connection: Conn,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+
+        let mut users = User::from_db_models(&ctx.users);
+        User::eager_load_all_children_for_each(&mut users, &ctx.users, &ctx.connection, trail)?;
+
+        Ok(users)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Conn", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_many(root_model_field = "car")]
+    cars: HasMany<Car>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_cars(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Car, Walked>,
+    ) -> FieldResult<Vec<Car>> {
+        Ok(self.cars.try_unwrap()?.clone())
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Conn", error = "Box<dyn std::error::Error>")]
+pub struct Car {
+    car: models::Car,
+}
+
+impl CarFields for Car {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.car.id)
+    }
+}
+
+fn run_as(viewer_id: i32, loads: Arc<AtomicUsize>) -> serde_json::Value {
+    let users = vec![models::User { id: 1 }, models::User { id: 2 }];
+    let cars = vec![
+        models::Car { id: 10, user_id: 1 },
+        models::Car { id: 11, user_id: 1 },
+        models::Car { id: 20, user_id: 2 },
+    ];
+    let ctx = Context {
+        connection: DbAndContext::new(Db { cars }, RequestContext { viewer_id, loads }),
+        users,
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id cars { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+
+    serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap()
+}
+
+#[test]
+fn the_viewer_only_sees_their_own_rows() {
+    let json = run_as(1, Arc::new(AtomicUsize::new(0)));
+
+    assert_json_eq!(
+        json!({
+            "users": [
+                { "id": 1, "cars": [{ "id": 10 }, { "id": 11 }] },
+                { "id": 2, "cars": [] },
+            ],
+        }),
+        json,
+    );
+
+    let json = run_as(2, Arc::new(AtomicUsize::new(0)));
+    assert_json_eq!(json!([{ "id": 20 }]), &json["users"][1]["cars"]);
+}
+
+#[test]
+fn the_request_scoped_metrics_handle_is_reachable_from_loaders() {
+    let loads = Arc::new(AtomicUsize::new(0));
+    run_as(1, Arc::clone(&loads));
+
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,293 @@
+//! `foreign_key_field` with legacy column names that don't follow the `{name}_id` convention —
+//! `owning_company`, `fk_country`, `owned_by` — across `has_one`, `option_has_one`, and
+//! `has_many`.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasMany, HasOne, LoadFrom, OptionHasOne};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        company: Company!
+        country: Country
+        cars: [Car!]!
+    }
+
+    type Company {
+        id: Int!
+    }
+
+    type Country {
+        id: Int!
+    }
+
+    type Car {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    companies: Vec<models::Company>,
+    countries: Vec<models::Country>,
+    cars: Vec<models::Car>,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub owning_company: i32,
+        pub fk_country: Option<i32>,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Company {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub owned_by: i32,
+    }
+}
+
+impl LoadFrom<i32> for models::Company {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .companies
+            .iter()
+            .filter(|company| ids.contains(&company.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LoadFrom<i32> for models::Car {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .cars
+            .iter()
+            .filter(|car| ids.contains(&car.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LoadFrom<models::User> for models::Car {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(users: &[models::User], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        let user_ids = users.iter().map(|user| user.id).collect::<Vec<_>>();
+        Ok(db
+            .cars
+            .iter()
+            .filter(|car| user_ids.contains(&car.owned_by))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+        Ok(User::eager_load(&ctx.users, &ctx.db, trail)?)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(foreign_key_field = "owning_company", root_model_field = "company")]
+    company: HasOne<Company>,
+
+    #[option_has_one(foreign_key_field = "fk_country", root_model_field = "country")]
+    country: OptionHasOne<Country>,
+
+    #[has_many(foreign_key_field = "owned_by", root_model_field = "car")]
+    cars: HasMany<Car>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_company(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Company, Walked>,
+    ) -> FieldResult<&Company> {
+        Ok(self.company.try_unwrap()?)
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Option<Country>> {
+        Ok(self.country.try_unwrap()?)
+    }
+
+    fn field_cars(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Car, Walked>,
+    ) -> FieldResult<&Vec<Car>> {
+        Ok(self.cars.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Company {
+    company: models::Company,
+}
+
+impl CompanyFields for Company {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.company.id)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.country.id)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Car {
+    car: models::Car,
+}
+
+impl CarFields for Car {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.car.id)
+    }
+}
+
+#[test]
+fn legacy_column_names_load_through_foreign_key_field() {
+    let ctx = Context {
+        db: Db {
+            companies: vec![models::Company { id: 5 }],
+            countries: vec![models::Country { id: 7 }],
+            cars: vec![
+                models::Car {
+                    id: 10,
+                    owned_by: 1,
+                },
+                models::Car {
+                    id: 11,
+                    owned_by: 1,
+                },
+                models::Car {
+                    id: 12,
+                    owned_by: 999,
+                },
+            ],
+        },
+        users: vec![models::User {
+            id: 1,
+            owning_company: 5,
+            fk_country: Some(7),
+        }],
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id company { id } country { id } cars { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_eq!(
+        json!({
+            "users": [{
+                "id": 1,
+                "company": { "id": 5 },
+                "country": { "id": 7 },
+                "cars": [{ "id": 10 }, { "id": 11 }],
+            }],
+        }),
+        json,
+    );
+}
@@ -0,0 +1,186 @@
+//! Eager loading results must not depend on `HashMap` iteration order, which changes run to run
+//! with the hasher's random seed. Every fresh `HashMap`/`HashSet`/`Cache` gets its own random
+//! seed, so repeating the same load with fresh state and comparing the debug output catches any
+//! ordering that leaks out of a map.
+
+use juniper_eager_loading::{
+    prelude::*, unique, Cache, GenericQueryTrail, HasMany, LoadFrom, LoadResult,
+};
+use juniper_from_schema::Walked;
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+}
+
+pub struct Db {
+    cars: Vec<models::Car>,
+}
+
+impl LoadFrom<i32> for models::Car {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    // Returns cars in the order the ids were requested, like a backend that preserves request
+    // order. If the requested ids are in hash order, so is the result.
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(ids
+            .iter()
+            .filter_map(|id| db.cars.iter().find(|car| car.id == *id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    cars: HasMany<Car>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Car {
+    car: models::Car,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            cars: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Car {
+    type Model = models::Car;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self { car: model.clone() }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Car {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCarsContext;
+
+impl EagerLoadChildrenOfType<Car, EverythingTrail, UserCarsContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Car, ())>, Self::Error> {
+        // Every user references every car, with duplicates, exactly like the derived
+        // has-many code builds its id list before deduplicating.
+        let ids = models
+            .iter()
+            .flat_map(|_| (0..50).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Car>, Self::Error> {
+        LoadFrom::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Car, &())) -> bool {
+        node.user.id == (child.0).car.user_id
+    }
+
+    fn loaded_child(node: &mut Self, child: Car) {
+        node.cars.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.cars.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Car, _, UserCarsContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+fn run_once(db: &Db) -> String {
+    let user_models = (0..5).map(|id| models::User { id }).collect::<Vec<_>>();
+    let mut users = User::from_db_models(&user_models);
+    User::eager_load_all_children_for_each(&mut users, &user_models, db, &EverythingTrail).unwrap();
+
+    // A per-request cache gets a randomized hasher each run; probing it mustn't influence the
+    // output either.
+    let mut cache = Cache::<i32>::new();
+    for user in &users {
+        for car in user.cars.try_unwrap().unwrap() {
+            cache.insert(car.car.id, car.car.clone());
+        }
+    }
+    let cached = cache
+        .get_many::<models::Car>(&(0..50).collect::<Vec<_>>())
+        .0;
+
+    format!("{:?} {:?}", users, cached)
+}
+
+#[test]
+fn fifty_runs_give_byte_identical_results() {
+    let db = Db {
+        cars: (0..50)
+            .map(|id| models::Car {
+                id,
+                user_id: id % 5,
+            })
+            .collect(),
+    };
+
+    let first = run_once(&db);
+    for _ in 0..49 {
+        assert_eq!(run_once(&db), first);
+    }
+}
+
+#[test]
+fn unique_keeps_the_first_occurrence_of_each_item() {
+    assert_eq!(unique(vec![3, 1, 3, 2, 1, 4]), [3, 1, 2, 4]);
+}
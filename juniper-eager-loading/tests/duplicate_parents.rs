@@ -0,0 +1,305 @@
+//! `nodes` and `models` correspond index-wise, and duplicates are allowed — the same model can
+//! appear more than once (say, from a join that repeats rows). Every duplicate node must
+//! independently receive its children, even though the ids get deduplicated before loading.
+
+use juniper_eager_loading::{prelude::*, GenericQueryTrail, HasMany, HasOne, LoadFrom, LoadResult};
+use juniper_from_schema::Walked;
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+    cars: Vec<models::Car>,
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LoadFrom<i32> for models::Car {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .cars
+            .iter()
+            .filter(|car| ids.contains(&car.user_id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    country: HasOne<Country>,
+    cars: HasMany<Car>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    country: models::Country,
+}
+
+#[derive(Clone, Debug)]
+pub struct Car {
+    car: models::Car,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+            cars: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Car {
+    type Model = models::Car;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self { car: model.clone() }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Car {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCountryContext;
+
+impl EagerLoadChildrenOfType<Country, EverythingTrail, UserCountryContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+        Ok(LoadResult::Ids(
+            models.iter().map(|model| model.country_id).collect(),
+        ))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Country>, Self::Error> {
+        LoadFrom::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+        node.user.country_id == (child.0).country.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.country.assert_loaded_otherwise_failed();
+    }
+}
+
+pub struct UserCarsContext;
+
+impl EagerLoadChildrenOfType<Car, EverythingTrail, UserCarsContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Car, ())>, Self::Error> {
+        Ok(LoadResult::Ids(
+            models.iter().map(|model| model.id).collect(),
+        ))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Car>, Self::Error> {
+        LoadFrom::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Car, &())) -> bool {
+        node.user.id == (child.0).car.user_id
+    }
+
+    fn loaded_child(node: &mut Self, child: Car) {
+        node.cars.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.cars.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        EagerLoadChildrenOfType::<Car, _, UserCarsContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+fn eager_load_users(user_models: &[models::User], db: &Db) -> Vec<User> {
+    let mut users = User::from_db_models(user_models);
+    User::eager_load_all_children_for_each(&mut users, user_models, db, &EverythingTrail).unwrap();
+    users
+}
+
+#[test]
+fn each_duplicate_gets_the_has_one_child() {
+    let user = models::User {
+        id: 1,
+        country_id: 10,
+    };
+    let user_models = vec![user.clone(), user.clone(), user];
+    let db = Db {
+        countries: vec![models::Country { id: 10 }],
+        cars: vec![],
+    };
+
+    let users = eager_load_users(&user_models, &db);
+
+    assert_eq!(users.len(), 3);
+    for user in &users {
+        assert_eq!(user.country.try_unwrap().unwrap().country.id, 10);
+    }
+}
+
+#[test]
+fn each_duplicate_gets_all_the_has_many_children() {
+    let user = models::User {
+        id: 1,
+        country_id: 10,
+    };
+    let user_models = vec![user.clone(), user];
+    let db = Db {
+        countries: vec![models::Country { id: 10 }],
+        cars: vec![
+            models::Car { id: 1, user_id: 1 },
+            models::Car { id: 2, user_id: 1 },
+        ],
+    };
+
+    let users = eager_load_users(&user_models, &db);
+
+    for user in &users {
+        let car_ids = user
+            .cars
+            .try_unwrap()
+            .unwrap()
+            .iter()
+            .map(|car| car.car.id)
+            .collect::<Vec<_>>();
+        assert_eq!(car_ids, [1, 2]);
+    }
+}
+
+#[test]
+fn duplicates_mixed_with_other_parents_stay_index_aligned() {
+    let repeated = models::User {
+        id: 1,
+        country_id: 10,
+    };
+    let other = models::User {
+        id: 2,
+        country_id: 20,
+    };
+    let user_models = vec![repeated.clone(), other, repeated];
+    let db = Db {
+        countries: vec![models::Country { id: 10 }, models::Country { id: 20 }],
+        cars: vec![],
+    };
+
+    let users = eager_load_users(&user_models, &db);
+
+    let country_ids = users
+        .iter()
+        .map(|user| user.country.try_unwrap().unwrap().country.id)
+        .collect::<Vec<_>>();
+    assert_eq!(country_ids, [10, 20, 10]);
+}
@@ -0,0 +1,197 @@
+//! `Connection` can be a trait object (`Box<dyn Trait + Send + Sync>`): the associated type has
+//! no bounds and the machinery only ever takes `&Self::Connection`. This pins that down by
+//! running the derived eager loading end to end through a boxed fake, so no concrete-type
+//! assumption can creep back into the generated code.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasOne};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        country: Country!
+    }
+
+    type Country {
+        id: Int!
+    }
+}
+
+pub trait Database {
+    fn countries(&self, ids: &[i32]) -> Result<Vec<models::Country>, Box<dyn std::error::Error>>;
+}
+
+pub type DynConnection = Box<dyn Database + Send + Sync>;
+
+pub struct FakeDatabase {
+    countries: Vec<models::Country>,
+    country_loads: Arc<AtomicUsize>,
+}
+
+impl Database for FakeDatabase {
+    fn countries(&self, ids: &[i32]) -> Result<Vec<models::Country>, Box<dyn std::error::Error>> {
+        self.country_loads.fetch_add(1, Ordering::SeqCst);
+        Ok(self
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub mod models {
+    use super::DynConnection;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    impl juniper_eager_loading::LoadFrom<i32> for Country {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = DynConnection;
+
+        fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            db.countries(ids)
+        }
+    }
+}
+
+pub struct Context {
+    db: DynConnection,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+
+        let mut users = User::from_db_models(&ctx.users);
+        User::eager_load_all_children_for_each(&mut users, &ctx.users, &ctx.db, trail)?;
+
+        Ok(users)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "DynConnection", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        Ok(self.country.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "DynConnection", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.country.id)
+    }
+}
+
+#[test]
+fn the_derived_code_eager_loads_through_a_boxed_connection() {
+    let country_loads = Arc::new(AtomicUsize::new(0));
+    let db: DynConnection = Box::new(FakeDatabase {
+        countries: vec![models::Country { id: 10 }, models::Country { id: 20 }],
+        country_loads: Arc::clone(&country_loads),
+    });
+    let ctx = Context {
+        db,
+        users: vec![
+            models::User {
+                id: 1,
+                country_id: 10,
+            },
+            models::User {
+                id: 2,
+                country_id: 20,
+            },
+        ],
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id country { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_eq!(
+        json!({
+            "users": [
+                { "id": 1, "country": { "id": 10 } },
+                { "id": 2, "country": { "id": 20 } },
+            ],
+        }),
+        json,
+    );
+
+    // Still one batched load, trait object or not.
+    assert_eq!(country_loads.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,185 @@
+//! `EagerLoadAllChildren::eager_load` builds the nodes and loads all their children in one
+//! call, and returns an empty list without touching the database when there are no models.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasOne, LoadFrom};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        country: Country!
+    }
+
+    type Country {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+    country_loads: AtomicUsize,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.country_loads.fetch_add(1, Ordering::SeqCst);
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+        Ok(User::eager_load(&ctx.users, &ctx.db, trail)?)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        Ok(self.country.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.country.id)
+    }
+}
+
+fn run(users: Vec<models::User>) -> (serde_json::Value, usize) {
+    let ctx = Context {
+        db: Db {
+            countries: vec![models::Country { id: 10 }],
+            country_loads: AtomicUsize::new(0),
+        },
+        users,
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id country { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+
+    let json = serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    (json, ctx.db.country_loads.load(Ordering::SeqCst))
+}
+
+#[test]
+fn builds_nodes_and_loads_children() {
+    let (json, country_loads) = run(vec![
+        models::User {
+            id: 1,
+            country_id: 10,
+        },
+        models::User {
+            id: 2,
+            country_id: 10,
+        },
+    ]);
+
+    assert_json_eq!(
+        json!({
+            "users": [
+                { "id": 1, "country": { "id": 10 } },
+                { "id": 2, "country": { "id": 10 } },
+            ],
+        }),
+        json,
+    );
+    assert_eq!(country_loads, 1);
+}
+
+#[test]
+fn no_models_means_no_queries() {
+    let (json, country_loads) = run(Vec::new());
+
+    assert_json_eq!(json!({ "users": [] }), json);
+    assert_eq!(country_loads, 0);
+}
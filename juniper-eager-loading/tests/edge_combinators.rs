@@ -0,0 +1,167 @@
+//! `map`, `as_ref`, and the state predicates transform and inspect edges without going through
+//! `try_unwrap` and manual matching. The important property is that they preserve the edge's
+//! state: mapping a not-loaded or failed edge never turns it into a loaded one.
+
+use juniper_eager_loading::{
+    AssociationType, Error, HasMany, HasManyThrough, HasOne, LoadFailedDetails, OptionHasOne,
+};
+
+#[test]
+fn mapping_a_loaded_has_one_maps_the_value() {
+    let mut edge = HasOne::default();
+    edge.loaded(2);
+
+    let mapped = edge.map(|n| n * 10);
+
+    assert!(mapped.is_loaded());
+    assert_eq!(mapped.try_unwrap().unwrap(), &20);
+}
+
+#[test]
+fn mapping_preserves_not_loaded_and_failed_states() {
+    let not_loaded = HasOne::<i32>::default();
+    let mapped = not_loaded.map(|n| n * 10);
+    assert!(mapped.is_not_loaded());
+    assert!(matches!(
+        mapped.try_unwrap(),
+        Err(Error::NotLoaded(AssociationType::HasOne, None)),
+    ));
+
+    let mut failed = HasOne::<i32>::default();
+    failed.assert_loaded_otherwise_failed_with(|| LoadFailedDetails::new("Country", &1, &10));
+    let mapped = failed.map(|n| n * 10);
+    assert!(mapped.is_failed());
+    // The recorded details survive the map too.
+    match mapped.try_unwrap() {
+        Err(Error::LoadFailedForIds(_, details)) => {
+            assert_eq!(details, LoadFailedDetails::new("Country", &1, &10));
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn as_ref_borrows_without_consuming_the_edge() {
+    let mut edge = HasOne::default();
+    edge.loaded("country".to_owned());
+
+    let borrowed: HasOne<&String> = edge.as_ref();
+    let lengths = borrowed.map(|value| value.len());
+
+    assert_eq!(lengths.try_unwrap().unwrap(), &7);
+    // The original edge is untouched.
+    assert!(edge.is_loaded());
+}
+
+#[test]
+fn has_one_state_predicates() {
+    let mut edge = HasOne::<i32>::default();
+    assert!(edge.is_not_loaded());
+    assert!(!edge.is_loaded());
+    assert!(!edge.is_failed());
+
+    edge.loaded(1);
+    assert!(edge.is_loaded());
+    assert!(!edge.is_not_loaded());
+
+    let mut failed = HasOne::<i32>::default();
+    failed.assert_loaded_otherwise_failed();
+    assert!(failed.is_failed());
+    assert!(!failed.is_not_loaded());
+
+    // A skipped edge is neither not-loaded (it was skipped on purpose) nor failed.
+    let mut skipped = HasOne::<i32>::default();
+    skipped.not_requested();
+    assert!(!skipped.is_not_loaded());
+    assert!(!skipped.is_failed());
+    assert!(!skipped.is_loaded());
+}
+
+#[test]
+fn option_has_one_maps_over_the_inner_option() {
+    let mut edge = OptionHasOne::default();
+    edge.loaded(2);
+    assert_eq!(edge.map(|n| n * 10).try_unwrap().unwrap(), &Some(20));
+
+    let mut none = OptionHasOne::<i32>::default();
+    none.loaded_none();
+    let mapped = none.map(|n| n * 10);
+    assert!(mapped.is_loaded());
+    assert_eq!(mapped.try_unwrap().unwrap(), &None);
+
+    let mut failed = OptionHasOne::<i32>::default();
+    failed.assert_loaded_otherwise_failed();
+    let mapped = failed.map(|n| n * 10);
+    assert!(mapped.is_failed());
+    assert!(matches!(
+        mapped.try_unwrap(),
+        Err(Error::LoadFailed(AssociationType::OptionHasOne, None)),
+    ));
+}
+
+#[test]
+fn option_has_one_as_ref_and_predicates() {
+    let mut edge = OptionHasOne::default();
+    edge.loaded("city".to_owned());
+
+    assert_eq!(
+        edge.as_ref().map(|value| value.len()).try_unwrap().unwrap(),
+        &Some(4)
+    );
+    assert!(edge.is_loaded());
+    assert!(!edge.is_not_loaded());
+    assert!(OptionHasOne::<i32>::default().is_not_loaded());
+}
+
+#[test]
+fn has_many_maps_over_the_elements() {
+    let mut edge = HasMany::default();
+    edge.loaded(1);
+    edge.loaded(2);
+
+    let mapped = edge.map(|n| n * 10);
+    assert_eq!(mapped.try_unwrap().unwrap(), &vec![10, 20]);
+    assert!(mapped.is_loaded());
+}
+
+#[test]
+fn has_many_preserves_the_failed_state() {
+    let mut edge = HasMany::<i32>::default();
+    edge.load_failed();
+
+    let mapped = edge.map(|n| n * 10);
+    assert!(mapped.is_failed());
+    assert!(matches!(
+        mapped.try_unwrap(),
+        Err(Error::LoadFailed(AssociationType::HasMany, None)),
+    ));
+}
+
+#[test]
+fn has_many_as_ref_and_predicates() {
+    let mut edge = HasMany::default();
+    edge.loaded("car".to_owned());
+
+    let lengths = edge.as_ref().map(|value| value.len());
+    assert_eq!(lengths.try_unwrap().unwrap(), &vec![3]);
+
+    assert!(edge.is_loaded());
+    assert!(HasMany::<i32>::default().is_not_loaded());
+    assert!(!HasMany::<i32>::default().is_failed());
+}
+
+#[test]
+fn has_many_through_behaves_like_has_many() {
+    let mut edge = HasManyThrough::default();
+    edge.loaded(1);
+    assert_eq!(
+        edge.as_ref().map(|n| n * 10).try_unwrap().unwrap(),
+        &vec![10]
+    );
+    assert!(edge.is_loaded());
+
+    let mut failed = HasManyThrough::<i32>::default();
+    failed.load_failed();
+    assert!(failed.map(|n| n * 10).is_failed());
+    assert!(HasManyThrough::<i32>::default().is_not_loaded());
+}
@@ -0,0 +1,203 @@
+//! Edge types implement `PartialEq`/`Eq`/`Hash` (conditionally on `T`), so whole derived node
+//! trees can be compared in snapshot tests. Equality considers the edge's state — a loaded
+//! edge never equals a not-loaded or failed one — but not the diagnostic field name the
+//! derived code records, so derived nodes compare equal to hand-built expected values.
+
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasMany, HasOne};
+use juniper_from_schema::graphql_schema;
+use std::collections::HashSet;
+
+graphql_schema! {
+    schema { query: Query }
+
+    type Query { noop: Boolean! @juniper(ownership: "owned") }
+
+    type User {
+        id: Int!
+        country: Country!
+        cars: [Car!]! @juniper(ownership: "owned")
+    }
+
+    type Country {
+        id: Int!
+    }
+
+    type Car {
+        id: Int!
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<bool> {
+        unimplemented!()
+    }
+}
+
+pub struct Db;
+
+pub struct Context;
+
+impl juniper::Context for Context {}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+
+    impl juniper_eager_loading::LoadFrom<i32> for Country {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(_ids: &[i32], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            unimplemented!()
+        }
+    }
+
+    impl juniper_eager_loading::LoadFrom<i32> for Car {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(_ids: &[i32], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            unimplemented!()
+        }
+    }
+
+    impl juniper_eager_loading::LoadFrom<User> for Car {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(_users: &[User], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            unimplemented!()
+        }
+    }
+}
+
+// The point of the test: a derived parent struct containing edges can itself derive
+// `PartialEq`.
+#[derive(Clone, Debug, PartialEq, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+
+    #[has_many(root_model_field = "car")]
+    cars: HasMany<Car>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        unimplemented!()
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        unimplemented!()
+    }
+
+    fn field_cars(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Car, Walked>,
+    ) -> FieldResult<Vec<Car>> {
+        unimplemented!()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        unimplemented!()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Car {
+    car: models::Car,
+}
+
+impl CarFields for Car {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        unimplemented!()
+    }
+}
+
+fn user_model() -> models::User {
+    models::User {
+        id: 1,
+        country_id: 10,
+    }
+}
+
+#[test]
+fn derived_nodes_compare_equal_when_their_edges_match() {
+    let a = User::new_from_model(&user_model());
+    let b = User::new_from_model(&user_model());
+
+    assert_eq!(a, b);
+}
+
+#[test]
+fn equality_considers_the_edge_state() {
+    let a = User::new_from_model(&user_model());
+    let mut b = User::new_from_model(&user_model());
+    b.country
+        .loaded(Country::new_from_model(&models::Country { id: 10 }));
+
+    // Loaded vs not loaded differ, even with identical models.
+    assert_ne!(a, b);
+
+    let mut c = User::new_from_model(&user_model());
+    c.country.assert_loaded_otherwise_failed();
+    assert_ne!(a, c);
+}
+
+#[test]
+fn derived_edges_compare_equal_to_hand_built_ones() {
+    // The derived code builds edges with `new_for_field`; the recorded field name must not
+    // take part in equality, or hand-built expected values would never match.
+    let node = User::new_from_model(&user_model());
+
+    assert_eq!(node.country, HasOne::default());
+    assert_eq!(node.cars, HasMany::default());
+}
+
+#[test]
+fn edges_can_be_hashed() {
+    let mut loaded = HasOne::default();
+    loaded.loaded(1);
+
+    let mut set = HashSet::new();
+    set.insert(HasOne::<i32>::default());
+    set.insert(loaded.clone());
+    set.insert(loaded);
+
+    // The two loaded copies collapse to one entry, the not-loaded edge stays separate.
+    assert_eq!(set.len(), 2);
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "elasticsearch")]
+
+use juniper_eager_loading::elasticsearch::{load_from_elasticsearch, MgetResponse, MgetTransport};
+use serde::Deserialize;
+use serde_json::json;
+use std::cell::RefCell;
+use std::future::{ready, Ready};
+
+#[derive(Clone, Eq, PartialEq, Debug, Deserialize)]
+struct Bio {
+    id: i32,
+    text: String,
+}
+
+struct FakeTransport {
+    response: serde_json::Value,
+    calls: RefCell<Vec<(String, Vec<String>)>>,
+}
+
+impl FakeTransport {
+    fn new(response: serde_json::Value) -> Self {
+        FakeTransport {
+            response,
+            calls: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl MgetTransport for FakeTransport {
+    type Error = Box<dyn std::error::Error>;
+    type Future = Ready<Result<MgetResponse, Self::Error>>;
+
+    fn mget(&self, index: &str, ids: &[String]) -> Self::Future {
+        self.calls
+            .borrow_mut()
+            .push((index.to_string(), ids.to_vec()));
+        ready(serde_json::from_value(self.response.clone()).map_err(Into::into))
+    }
+}
+
+#[test]
+fn deserializes_found_docs_and_skips_missing_ones() {
+    let transport = FakeTransport::new(json!({
+        "docs": [
+            { "_id": "1", "found": true, "_source": { "id": 1, "text": "one" } },
+            { "_id": "2", "found": false },
+            { "_id": "3", "found": true, "_source": { "id": 3, "text": "three" } },
+        ]
+    }));
+
+    let bios: Vec<Bio> =
+        futures::executor::block_on(load_from_elasticsearch(&transport, "bios", &[1, 2, 3]))
+            .unwrap();
+
+    assert_eq!(
+        bios,
+        vec![
+            Bio {
+                id: 1,
+                text: "one".to_string()
+            },
+            Bio {
+                id: 3,
+                text: "three".to_string()
+            },
+        ]
+    );
+
+    let calls = transport.calls.borrow();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].0, "bios");
+    assert_eq!(calls[0].1, vec!["1", "2", "3"]);
+}
+
+#[test]
+fn empty_id_sets_do_not_touch_the_transport() {
+    let transport = FakeTransport::new(json!({ "docs": [] }));
+
+    let bios: Vec<Bio> =
+        futures::executor::block_on(load_from_elasticsearch(&transport, "bios", &[] as &[i32]))
+            .unwrap();
+
+    assert!(bios.is_empty());
+    assert!(transport.calls.borrow().is_empty());
+}
+
+#[test]
+fn source_that_does_not_match_the_model_is_an_error() {
+    let transport = FakeTransport::new(json!({
+        "docs": [
+            { "_id": "1", "found": true, "_source": { "unexpected": "shape" } },
+        ]
+    }));
+
+    let result: Result<Vec<Bio>, _> =
+        futures::executor::block_on(load_from_elasticsearch(&transport, "bios", &[1]));
+
+    assert!(result.is_err());
+}
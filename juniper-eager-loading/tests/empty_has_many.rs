@@ -0,0 +1,160 @@
+//! A parent that legitimately has zero children must resolve to an empty list after eager
+//! loading, not to an error. `HasMany` can't even represent "not loaded" separately from
+//! "loaded nothing", so this pins the guarantee against regressions if that ever changes.
+
+use juniper_eager_loading::test_support::{EverythingTrail, MockConnection, Scenario};
+use juniper_eager_loading::{prelude::*, HasMany, LoadFrom, LoadResult};
+
+mod models {
+    use juniper_eager_loading::test_support::MockModel;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+
+    impl MockModel for Car {
+        type Id = i32;
+
+        // Cars are loaded by their owner's id, so that's the lookup key.
+        #[allow(clippy::misnamed_getters)]
+        fn id(&self) -> Self::Id {
+            self.user_id
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    cars: HasMany<Car>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Car {
+    car: models::Car,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = MockConnection;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            cars: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Car {
+    type Model = models::Car;
+    type Id = i32;
+    type Connection = MockConnection;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self { car: model.clone() }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Car {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCarsContext;
+
+impl EagerLoadChildrenOfType<Car, EverythingTrail, UserCarsContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Car, ())>, Self::Error> {
+        Ok(LoadResult::Ids(
+            models.iter().map(|model| model.id).collect(),
+        ))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Car>, Self::Error> {
+        LoadFrom::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Car, &())) -> bool {
+        node.user.id == (child.0).car.user_id
+    }
+
+    fn loaded_child(node: &mut Self, child: Car) {
+        node.cars.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.cars.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Car, _, UserCarsContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+#[test]
+fn a_parent_without_children_resolves_to_an_empty_list() {
+    let user_models = (1..=3).map(|id| models::User { id }).collect::<Vec<_>>();
+    // User 2 has no cars, the others have two each.
+    let scenario = Scenario::new().insert(vec![
+        models::Car { id: 1, user_id: 1 },
+        models::Car { id: 2, user_id: 1 },
+        models::Car { id: 3, user_id: 3 },
+        models::Car { id: 4, user_id: 3 },
+    ]);
+
+    let users: Vec<User> = scenario.eager_load(&user_models).unwrap();
+
+    let car_counts = users
+        .iter()
+        .map(|user| user.cars.try_unwrap().unwrap().len())
+        .collect::<Vec<_>>();
+    assert_eq!(car_counts, [2, 0, 2]);
+}
+
+#[test]
+fn the_scenario_exposes_what_was_loaded() {
+    let scenario = Scenario::new().insert(vec![models::Car { id: 1, user_id: 1 }]);
+
+    let _users: Vec<User> = scenario
+        .eager_load(&[models::User { id: 1 }, models::User { id: 2 }])
+        .unwrap();
+
+    let log = scenario.load_log();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].model, "Car");
+    assert_eq!(log[0].ids, ["1", "2"]);
+}
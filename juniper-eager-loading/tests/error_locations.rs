@@ -0,0 +1,148 @@
+//! Errors produced by derived edges name where they happened — the GraphQL type and field —
+//! both in their message and in the `FieldError` extensions. Hand-built edges don't know
+//! where they live and keep the old messages.
+
+use juniper::{Executor, FieldResult, IntoFieldError};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasOne};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+
+graphql_schema! {
+    schema { query: Query }
+
+    type Query { noop: Boolean! @juniper(ownership: "owned") }
+
+    type User {
+        id: Int!
+        country: Country!
+    }
+
+    type Country {
+        id: Int!
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<bool> {
+        unimplemented!()
+    }
+}
+
+pub struct Db;
+
+pub struct Context;
+
+impl juniper::Context for Context {}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    impl juniper_eager_loading::LoadFrom<i32> for Country {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(_ids: &[i32], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            unimplemented!()
+        }
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        unimplemented!()
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        unimplemented!()
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        unimplemented!()
+    }
+}
+
+fn user_node() -> User {
+    User::new_from_model(&models::User {
+        id: 1,
+        country_id: 10,
+    })
+}
+
+#[test]
+fn a_not_loaded_error_names_the_type_and_field() {
+    let node = user_node();
+
+    let err = node.country.try_unwrap().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`User.country` should have been eager loaded, but wasn't",
+    );
+}
+
+#[test]
+fn a_load_failed_error_names_the_type_and_field() {
+    let mut node = user_node();
+    node.country.assert_loaded_otherwise_failed();
+
+    let err = node.country.try_unwrap().unwrap_err();
+    assert_eq!(err.to_string(), "Failed to load `User.country`");
+}
+
+#[test]
+fn the_location_ends_up_in_the_field_error_extensions() {
+    let node = user_node();
+
+    let error: juniper::FieldError = node.country.try_unwrap().unwrap_err().into_field_error();
+    assert_eq!(
+        serde_json::to_value(error.extensions()).unwrap(),
+        json!({
+            "code": "NOT_LOADED",
+            "association": "HasOne",
+            "parentType": "User",
+            "fieldName": "country",
+        })
+    );
+}
+
+#[test]
+fn hand_built_edges_keep_the_location_free_messages() {
+    let edge = HasOne::<i32>::default();
+
+    let err = edge.try_unwrap().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`HasOne` should have been eager loaded, but wasn't",
+    );
+}
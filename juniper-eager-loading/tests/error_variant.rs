@@ -0,0 +1,123 @@
+//! The `error_variant` struct attribute makes the derive generate
+//! `From<juniper_eager_loading::Error>` for the app's error enum, so `?` works directly on
+//! `try_unwrap` in resolvers instead of a `map_err` per call site.
+
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, AssociationType, EagerLoading, HasOne};
+use juniper_from_schema::graphql_schema;
+
+graphql_schema! {
+    schema { query: Query }
+
+    type Query { noop: Boolean! @juniper(ownership: "owned") }
+
+    type User {
+        id: Int!
+        country: Country!
+    }
+
+    type Country {
+        id: Int!
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<bool> {
+        unimplemented!()
+    }
+}
+
+pub struct Db;
+
+pub struct Context;
+
+impl juniper::Context for Context {}
+
+#[derive(Debug)]
+pub enum MyError {
+    EagerLoading(juniper_eager_loading::Error),
+    #[allow(dead_code)]
+    Db(String),
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    impl juniper_eager_loading::LoadFrom<i32> for Country {
+        type Error = super::MyError;
+        type Connection = super::Db;
+
+        fn load(_ids: &[i32], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            unimplemented!()
+        }
+    }
+}
+
+// `error_variant` is set here and nowhere else: the generated `From` impl can only exist once
+// per error type.
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "MyError", error_variant = "EagerLoading")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        unimplemented!()
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        unimplemented!()
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "MyError")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        unimplemented!()
+    }
+}
+
+// The kind of resolver helper the attribute exists for: `?` converts the crate's error.
+fn country_of(user: &User) -> Result<&Country, MyError> {
+    Ok(user.country.try_unwrap()?)
+}
+
+#[test]
+fn the_question_mark_operator_converts_into_the_app_error() {
+    let user = User::new_from_model(&models::User {
+        id: 1,
+        country_id: 10,
+    });
+
+    match country_of(&user) {
+        Err(MyError::EagerLoading(juniper_eager_loading::Error::NotLoaded(
+            AssociationType::HasOne,
+            _,
+        ))) => {}
+        other => panic!("expected a wrapped `NotLoaded` error, got {:?}", other),
+    }
+}
@@ -0,0 +1,84 @@
+//! `Error` is `#[non_exhaustive]`, carries structured payloads, and wraps underlying loader
+//! errors so `source()` chains work.
+
+use juniper_eager_loading::{AssociationType, Error, LoadFailedDetails, MissingChildren};
+use std::error::Error as _;
+
+#[test]
+fn load_errors_expose_their_source() {
+    let io_error = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "db went away");
+    let error = Error::load(io_error);
+
+    let source = error.source().expect("should have a source");
+    let source = source
+        .downcast_ref::<std::io::Error>()
+        .expect("source should be the io error");
+    assert_eq!(source.kind(), std::io::ErrorKind::ConnectionRefused);
+
+    assert_eq!(
+        error.to_string(),
+        "Loading associated data failed: db went away"
+    );
+}
+
+#[test]
+fn load_accepts_plain_messages_too() {
+    let error = Error::load("the loader timed out");
+
+    assert_eq!(
+        error.to_string(),
+        "Loading associated data failed: the loader timed out"
+    );
+    assert!(error.source().is_some());
+}
+
+#[test]
+fn the_other_variants_have_no_source() {
+    let errors = [
+        Error::NotLoaded(AssociationType::HasOne, None),
+        Error::NotRequested(AssociationType::HasOne, None),
+        Error::LoadFailed(AssociationType::HasOne, None),
+        Error::LoadFailedForIds(
+            AssociationType::HasOne,
+            LoadFailedDetails::new("Country", &1, &10),
+        ),
+        Error::MissingChildren(MissingChildren::new("Country", vec!["10".to_owned()])),
+    ];
+
+    for error in &errors {
+        assert!(error.source().is_none(), "{} had a source", error);
+    }
+}
+
+#[test]
+fn display_output_for_each_variant() {
+    assert_eq!(
+        Error::NotLoaded(AssociationType::HasMany, None).to_string(),
+        "`HasMany` should have been eager loaded, but wasn't"
+    );
+    assert_eq!(
+        Error::NotRequested(AssociationType::HasOne, None).to_string(),
+        "`HasOne` wasn't eager loaded because the GraphQL field wasn't selected by the query"
+    );
+    assert_eq!(
+        Error::LoadFailed(AssociationType::HasOne, None).to_string(),
+        "Failed to load `HasOne`"
+    );
+    assert_eq!(
+        Error::LoadFailedForIds(
+            AssociationType::HasOne,
+            LoadFailedDetails::new("Country", &1, &10),
+        )
+        .to_string(),
+        "Failed to load `Country` for `HasOne`: \
+         parent with id `1` pointed at child id `10`, which produced no model"
+    );
+    assert_eq!(
+        Error::MissingChildren(MissingChildren::new(
+            "Country",
+            vec!["10".to_owned(), "11".to_owned()],
+        ))
+        .to_string(),
+        "No `Country` found for ids `10`, `11`, but the association requires them to exist"
+    );
+}
@@ -0,0 +1,244 @@
+//! Call counts say how much work happened, events say exactly what happened in what order.
+//! This pins the batching and dedup behavior down to the precise loader calls made.
+
+use juniper_eager_loading::test_support::{
+    assert_events_contain, Event, EverythingTrail, MockConnection, RecordingHooks, Scenario,
+};
+use juniper_eager_loading::{prelude::*, unique, HasMany, LoadFrom, LoadResult};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+mod models {
+    use juniper_eager_loading::test_support::MockModel;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+
+    impl MockModel for Car {
+        type Id = i32;
+
+        // Cars are loaded by their owner's id, so that's the lookup key.
+        #[allow(clippy::misnamed_getters)]
+        fn id(&self) -> Self::Id {
+            self.user_id
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    cars: HasMany<Car>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Car {
+    car: models::Car,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = MockConnection;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            cars: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Car {
+    type Model = models::Car;
+    type Id = i32;
+    type Connection = MockConnection;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self { car: model.clone() }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Car {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCarsContext;
+
+impl EagerLoadChildrenOfType<Car, EverythingTrail, UserCarsContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Car, ())>, Self::Error> {
+        // Deduplicate like the derived code does.
+        Ok(LoadResult::Ids(unique(
+            models.iter().map(|model| model.id).collect(),
+        )))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Car>, Self::Error> {
+        LoadFrom::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Car, &())) -> bool {
+        node.user.id == (child.0).car.user_id
+    }
+
+    fn loaded_child(node: &mut Self, child: Car) {
+        node.cars.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.cars.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Car, _, UserCarsContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+#[test]
+fn duplicate_parents_produce_one_deduplicated_loader_call() {
+    let scenario = Scenario::new().insert(vec![
+        models::Car { id: 1, user_id: 2 },
+        models::Car { id: 2, user_id: 1 },
+    ]);
+    let hooks = RecordingHooks::new();
+    scenario.store().record_events(&hooks);
+
+    // User 2 appears twice, and before user 1: the ids must be deduplicated but keep the
+    // first-occurrence order.
+    let user_models = [2, 2, 1, 2]
+        .iter()
+        .map(|&id| models::User { id })
+        .collect::<Vec<_>>();
+    let _users: Vec<User> = scenario.eager_load(&user_models).unwrap();
+
+    assert_eq!(
+        hooks.events(),
+        [Event::LoaderCall {
+            model: "Car",
+            ids: vec!["2".to_owned(), "1".to_owned()],
+        }],
+    );
+}
+
+#[test]
+fn expected_events_may_be_interleaved_with_others() {
+    let events = [
+        Event::CacheMiss {
+            model: "Car",
+            key: "1".to_owned(),
+        },
+        Event::LoaderCall {
+            model: "Car",
+            ids: vec!["1".to_owned()],
+        },
+        Event::CacheHit {
+            model: "Car",
+            key: "1".to_owned(),
+        },
+    ];
+
+    assert_events_contain(
+        &events,
+        &[
+            Event::CacheMiss {
+                model: "Car",
+                key: "1".to_owned(),
+            },
+            Event::CacheHit {
+                model: "Car",
+                key: "1".to_owned(),
+            },
+        ],
+    );
+}
+
+#[test]
+fn out_of_order_events_fail_the_assertion() {
+    let events = [
+        Event::CacheHit {
+            model: "Car",
+            key: "1".to_owned(),
+        },
+        Event::CacheMiss {
+            model: "Car",
+            key: "1".to_owned(),
+        },
+    ];
+    let expected = [
+        Event::CacheMiss {
+            model: "Car",
+            key: "1".to_owned(),
+        },
+        Event::CacheHit {
+            model: "Car",
+            key: "1".to_owned(),
+        },
+    ];
+
+    let err = catch_unwind(AssertUnwindSafe(|| {
+        assert_events_contain(&events, &expected)
+    }))
+    .expect_err("should have panicked");
+    let message = err
+        .downcast_ref::<String>()
+        .expect("panic message should be a `String`");
+    assert!(message.starts_with("expected events to occur in order, but they didn't"));
+}
+
+#[test]
+fn manually_recorded_events_mix_with_loader_calls() {
+    let scenario = Scenario::new().insert(vec![models::Car { id: 1, user_id: 1 }]);
+    let hooks = RecordingHooks::new();
+    scenario.store().record_events(&hooks);
+
+    hooks.cache_miss::<models::Car, _>(&1);
+    let _users: Vec<User> = scenario.eager_load(&[models::User { id: 1 }]).unwrap();
+
+    assert_events_contain(
+        &hooks.events(),
+        &[
+            Event::CacheMiss {
+                model: "Car",
+                key: "1".to_owned(),
+            },
+            Event::LoaderCall {
+                model: "Car",
+                ids: vec!["1".to_owned()],
+            },
+        ],
+    );
+}
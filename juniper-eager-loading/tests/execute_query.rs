@@ -0,0 +1,217 @@
+//! The most convincing eager loading test: execute a real GraphQL query against the schema and
+//! assert both the response and how many loads it took. `execute_query` does the plumbing.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::test_support::{execute_query, MockConnection, MockStore};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasOne};
+use juniper_from_schema::graphql_schema;
+use serde_json::{json, Value};
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        country: Country!
+    }
+
+    type Country {
+        id: Int!
+    }
+}
+
+mod models {
+    use juniper_eager_loading::test_support::MockModel;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    impl MockModel for Country {
+        type Id = i32;
+
+        fn id(&self) -> Self::Id {
+            self.id
+        }
+    }
+}
+
+pub struct Context {
+    db: MockConnection,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+
+        let mut users = User::from_db_models(&ctx.users);
+        User::eager_load_all_children_for_each(&mut users, &ctx.users, &ctx.db, trail)?;
+
+        Ok(users)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "MockConnection", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        Ok(self.country.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "MockConnection", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.country.id)
+    }
+}
+
+fn user_models() -> Vec<models::User> {
+    vec![
+        models::User {
+            id: 1,
+            country_id: 10,
+        },
+        models::User {
+            id: 2,
+            country_id: 10,
+        },
+        models::User {
+            id: 3,
+            country_id: 20,
+        },
+    ]
+}
+
+fn response_json(data: &juniper::Value) -> Value {
+    serde_json::from_str(&serde_json::to_string(data).unwrap()).unwrap()
+}
+
+#[test]
+fn the_response_and_the_load_budget_are_asserted_together() {
+    let store = MockStore::new();
+    store.insert(vec![models::Country { id: 10 }, models::Country { id: 20 }]);
+
+    let result = execute_query(
+        &Schema::new(Query, Mutation),
+        "{ users { id country { id } } }",
+        &store,
+        |db| Context {
+            db,
+            users: user_models(),
+        },
+    );
+
+    assert_json_eq!(
+        json!({
+            "users": [
+                { "id": 1, "country": { "id": 10 } },
+                { "id": 2, "country": { "id": 10 } },
+                { "id": 3, "country": { "id": 20 } },
+            ],
+        }),
+        response_json(&result.data),
+    );
+
+    // Three users, two distinct countries, one load.
+    assert_eq!(result.load_calls(), 1);
+    assert_eq!(result.load_calls_for("Country"), 1);
+    assert_eq!(result.load_log[0].ids, ["10", "20"]);
+}
+
+#[test]
+fn fields_the_query_does_not_select_are_not_loaded() {
+    let store = MockStore::new();
+    store.insert(vec![models::Country { id: 10 }]);
+
+    let result = execute_query(
+        &Schema::new(Query, Mutation),
+        "{ users { id } }",
+        &store,
+        |db| Context {
+            db,
+            users: user_models(),
+        },
+    );
+
+    assert_eq!(result.load_calls(), 0);
+}
+
+#[test]
+fn loads_made_while_seeding_do_not_count_against_the_query() {
+    let store = MockStore::new();
+    store.insert(vec![models::Country { id: 10 }, models::Country { id: 20 }]);
+
+    // A load before the query, for example from a previous query against the same store.
+    use juniper_eager_loading::LoadFrom;
+    let _: Vec<models::Country> = LoadFrom::load(&[10], &store.connection()).unwrap();
+
+    let result = execute_query(
+        &Schema::new(Query, Mutation),
+        "{ users { id country { id } } }",
+        &store,
+        |db| Context {
+            db,
+            users: user_models(),
+        },
+    );
+
+    assert_eq!(result.load_calls(), 1);
+    assert_eq!(store.load_log().len(), 2);
+}
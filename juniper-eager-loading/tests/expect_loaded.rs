@@ -0,0 +1,93 @@
+//! `expect_loaded` is the edge-type analogue of `Option::expect`: it panics with the caller's
+//! message, the payload type, and the edge's state, so a failing test points straight at the
+//! association that was missed.
+
+use juniper_eager_loading::{HasMany, HasOne, OptionHasOne};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+fn panic_message<F: FnOnce()>(f: F) -> String {
+    let err = catch_unwind(AssertUnwindSafe(f)).expect_err("should have panicked");
+    err.downcast_ref::<String>()
+        .cloned()
+        .expect("panic message should be a `String`")
+}
+
+#[test]
+fn loaded_edges_hand_out_the_value() {
+    let mut country = HasOne::default();
+    country.loaded("Denmark");
+    assert_eq!(
+        country.expect_loaded("user should have a country"),
+        &"Denmark"
+    );
+
+    let mut city = OptionHasOne::<i32>::default();
+    city.loaded_none();
+    assert_eq!(city.expect_loaded("city should be loaded"), &None);
+
+    let mut cars = HasMany::default();
+    cars.loaded(1);
+    assert_eq!(cars.expect_loaded("user should have cars"), &vec![1]);
+}
+
+#[test]
+fn a_not_loaded_edge_panics_with_message_type_and_state() {
+    let country = HasOne::<String>::default();
+
+    let message = panic_message(|| {
+        country.expect_loaded("user should have a country");
+    });
+
+    assert_eq!(
+        message,
+        "user should have a country: `HasOne<alloc::string::String>`: \
+         `HasOne` should have been eager loaded, but wasn't",
+    );
+}
+
+#[test]
+fn a_failed_edge_panics_with_the_failure() {
+    let mut country = HasOne::<String>::default();
+    country.assert_loaded_otherwise_failed();
+
+    let message = panic_message(|| {
+        country.expect_loaded("user should have a country");
+    });
+
+    assert!(message.starts_with("user should have a country: `HasOne<"));
+    assert!(message.contains("Failed to load"));
+}
+
+#[test]
+fn option_has_one_only_panics_for_not_loaded_and_failed() {
+    let city = OptionHasOne::<i32>::default();
+    let message = panic_message(|| {
+        city.expect_loaded("city should be loaded");
+    });
+    assert_eq!(
+        message,
+        "city should be loaded: `OptionHasOne<i32>`: \
+         `OptionHasOne` should have been eager loaded, but wasn't",
+    );
+
+    let mut failed = OptionHasOne::<i32>::default();
+    failed.assert_loaded_otherwise_failed();
+    let message = panic_message(|| {
+        failed.expect_loaded("city should be loaded");
+    });
+    assert!(message.contains("Failed to load"));
+}
+
+#[test]
+fn an_empty_has_many_panics_since_nothing_was_loaded() {
+    let cars = HasMany::<i32>::default();
+
+    let message = panic_message(|| {
+        cars.expect_loaded("user should have cars");
+    });
+
+    assert_eq!(
+        message,
+        "user should have cars: `HasMany<i32>` should have been eager loaded, but is empty",
+    );
+}
@@ -0,0 +1,259 @@
+//! Error handling only works if it's been run, and loads rarely fail on their own in tests.
+//! `FailingLoader` makes them fail on demand so the `LoadFailed` paths get exercised like any
+//! other code.
+
+use juniper_eager_loading::test_support::{
+    EverythingTrail, FailingLoader, MockConnection, MockStore,
+};
+use juniper_eager_loading::{
+    prelude::*, unique, AssociationType, Error, HasOne, LoadFailedDetails, LoadResult,
+};
+
+mod models {
+    use juniper_eager_loading::test_support::MockModel;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    impl MockModel for Country {
+        type Id = i32;
+
+        fn id(&self) -> Self::Id {
+            self.id
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    country: HasOne<Country>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    country: models::Country,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = FailingLoader<MockConnection>;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = FailingLoader<MockConnection>;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+        }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCountryContext;
+
+impl EagerLoadChildrenOfType<Country, EverythingTrail, UserCountryContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+        Ok(LoadResult::Ids(unique(
+            models.iter().map(|model| model.country_id).collect(),
+        )))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Country>, Self::Error> {
+        db.load(ids).map_err(Into::into)
+    }
+
+    fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+        node.user.country_id == (child.0).country.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        let details = LoadFailedDetails::new("Country", &node.user.id, &node.user.country_id);
+        node.country.assert_loaded_otherwise_failed_with(|| details);
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+fn eager_load_users(
+    db: &FailingLoader<MockConnection>,
+    user_models: &[models::User],
+) -> Result<Vec<User>, Box<dyn std::error::Error>> {
+    let mut users = User::from_db_models(user_models);
+    User::eager_load_all_children_for_each(&mut users, user_models, db, &EverythingTrail)?;
+    Ok(users)
+}
+
+fn store_with_countries() -> MockStore {
+    let store = MockStore::new();
+    store.insert(vec![models::Country { id: 10 }, models::Country { id: 20 }]);
+    store
+}
+
+#[test]
+fn failing_every_load_surfaces_the_injected_error() {
+    let store = store_with_countries();
+    let db = FailingLoader::new(store.connection());
+    db.fail_always::<models::Country>("connection refused");
+
+    let err = eager_load_users(
+        &db,
+        &[models::User {
+            id: 1,
+            country_id: 10,
+        }],
+    )
+    .expect_err("the load should have failed");
+
+    assert_eq!(err.to_string(), "connection refused");
+}
+
+#[test]
+fn failing_specific_ids_fails_only_the_parents_pointing_at_them() {
+    let store = store_with_countries();
+    let db = FailingLoader::new(store.connection());
+    db.fail_ids::<models::Country, _>(&[10]);
+
+    let user_models = [
+        models::User {
+            id: 1,
+            country_id: 10,
+        },
+        models::User {
+            id: 2,
+            country_id: 20,
+        },
+    ];
+    let users = eager_load_users(&db, &user_models).unwrap();
+
+    // User 2's country loaded normally.
+    assert_eq!(users[1].country.try_unwrap().unwrap().country.id, 20);
+
+    // User 1's edge failed, and the error names the rows involved.
+    let err = users[0]
+        .country
+        .try_unwrap()
+        .expect_err("should have failed");
+    match err {
+        Error::LoadFailedForIds(AssociationType::HasOne, details) => {
+            assert_eq!(details, LoadFailedDetails::new("Country", &1, &10));
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn failing_the_nth_call_leaves_the_other_calls_working() {
+    let store = store_with_countries();
+    let db = FailingLoader::new(store.connection());
+    db.fail_nth_call::<models::Country>(2, "deadlock detected");
+
+    let user_models = [models::User {
+        id: 1,
+        country_id: 10,
+    }];
+
+    assert!(eager_load_users(&db, &user_models).is_ok());
+    let err = eager_load_users(&db, &user_models).expect_err("the second load should fail");
+    assert_eq!(err.to_string(), "deadlock detected");
+    assert!(eager_load_users(&db, &user_models).is_ok());
+}
+
+#[test]
+fn partial_results_fail_the_parents_whose_rows_were_dropped() {
+    let store = store_with_countries();
+    let db = FailingLoader::new(store.connection());
+    db.return_at_most::<models::Country>(1);
+
+    let user_models = [
+        models::User {
+            id: 1,
+            country_id: 10,
+        },
+        models::User {
+            id: 2,
+            country_id: 20,
+        },
+    ];
+    let users = eager_load_users(&db, &user_models).unwrap();
+
+    assert!(users[0].country.try_unwrap().is_ok());
+    assert!(matches!(
+        users[1].country.try_unwrap(),
+        Err(Error::LoadFailedForIds(..)),
+    ));
+}
+
+#[test]
+fn custom_loaders_apply_policies_by_filtering_their_ids() {
+    // Loaders that don't go through `MockConnection` — including async ones — run their ids
+    // through `surviving_ids` first and load whatever comes back.
+    let loader = FailingLoader::new(());
+    loader.fail_ids::<models::Country, _>(&[2]);
+
+    let surviving = loader
+        .surviving_ids::<models::Country, _>(&[1, 2, 3])
+        .unwrap();
+    assert_eq!(surviving, [1, 3]);
+
+    loader.fail_always::<models::Country>("boom");
+    let err = loader
+        .surviving_ids::<models::Country, _>(&[1])
+        .expect_err("should fail");
+    assert_eq!(err.to_string(), "boom");
+}
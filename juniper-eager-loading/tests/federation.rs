@@ -0,0 +1,232 @@
+use juniper_eager_loading::{
+    eager_load_entities, prelude::*, unique, GenericQueryTrail, HasOne, LoadFrom, LoadResult,
+};
+use juniper_from_schema::Walked;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+}
+
+pub struct Db {
+    users: Vec<models::User>,
+    countries: Vec<models::Country>,
+    user_loads: AtomicUsize,
+    country_loads: AtomicUsize,
+}
+
+impl LoadFrom<i32> for models::User {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.user_loads.fetch_add(1, Ordering::SeqCst);
+        Ok(db
+            .users
+            .iter()
+            .filter(|user| ids.contains(&user.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.country_loads.fetch_add(1, Ordering::SeqCst);
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+// A stand-in for the walked `QueryTrail` of the `_entities` selection.
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    country: HasOne<Country>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    country: models::Country,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+        }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+struct UserCountryContext;
+
+impl EagerLoadChildrenOfType<Country, EverythingTrail, UserCountryContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+        let ids = models
+            .iter()
+            .map(|model| model.country_id)
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Country>, Self::Error> {
+        <models::Country as LoadFrom<i32>>::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+        node.user.country_id == (child.0).country.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.country.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+fn db() -> Db {
+    Db {
+        users: vec![
+            models::User {
+                id: 1,
+                country_id: 10,
+            },
+            models::User {
+                id: 2,
+                country_id: 10,
+            },
+        ],
+        countries: vec![models::Country { id: 10 }],
+        user_loads: AtomicUsize::new(0),
+        country_loads: AtomicUsize::new(0),
+    }
+}
+
+#[test]
+fn resolves_a_federation_batch_with_mixed_found_and_missing_keys() {
+    let db = db();
+
+    let entities: Vec<Option<User>> = eager_load_entities(
+        &[2, 404, 1],
+        &db,
+        &EverythingTrail,
+        |user: &models::User| user.id,
+    )
+    .unwrap();
+
+    assert_eq!(entities.len(), 3);
+    assert_eq!(entities[0].as_ref().unwrap().user.id, 2);
+    assert!(entities[1].is_none());
+    assert_eq!(entities[2].as_ref().unwrap().user.id, 1);
+
+    // Children were eager loaded for the found entities.
+    for user in entities.iter().flatten() {
+        assert_eq!(user.country.try_unwrap().unwrap().country.id, 10);
+    }
+
+    // One query for the users, one for their countries, regardless of batch size.
+    assert_eq!(db.user_loads.load(Ordering::SeqCst), 1);
+    assert_eq!(db.country_loads.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn duplicate_keys_load_once_and_resolve_to_the_same_entity() {
+    let db = db();
+
+    let entities: Vec<Option<User>> =
+        eager_load_entities(&[1, 1], &db, &EverythingTrail, |user: &models::User| {
+            user.id
+        })
+        .unwrap();
+
+    assert_eq!(entities.len(), 2);
+    assert_eq!(entities[0].as_ref().unwrap().user.id, 1);
+    assert_eq!(entities[1].as_ref().unwrap().user.id, 1);
+    assert_eq!(db.user_loads.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn empty_batches_do_not_touch_the_database() {
+    let db = db();
+
+    let entities: Vec<Option<User>> =
+        eager_load_entities(&[], &db, &EverythingTrail, |user: &models::User| user.id).unwrap();
+
+    assert!(entities.is_empty());
+    assert_eq!(db.user_loads.load(Ordering::SeqCst), 0);
+    assert_eq!(db.country_loads.load(Ordering::SeqCst), 0);
+}
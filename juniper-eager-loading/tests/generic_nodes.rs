@@ -0,0 +1,226 @@
+//! `#[derive(EagerLoading)]` on a generic struct: the struct's type parameters and
+//! where-clause are spliced into every generated impl. The GraphQL side stays concrete
+//! through a type alias — the schema's `UserPage` is `Page<models::Stats>` — so the trail
+//! methods `graphql_schema!` generates for `UserPage` land on the instantiation the derived
+//! generic impls cover.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasOne, LoadFrom};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      userPage: UserPage! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type UserPage {
+        total: Int!
+        featured: User!
+    }
+
+    type User {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    users: Vec<models::User>,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Page<T> {
+        pub id: i32,
+        pub payload: T,
+        pub featured_user_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Stats {
+        pub total: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+}
+
+impl LoadFrom<i32> for models::User {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .users
+            .iter()
+            .filter(|user| ids.contains(&user.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    page: models::Page<models::Stats>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_user_page<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, UserPage, Walked>,
+    ) -> FieldResult<UserPage> {
+        let ctx = executor.context();
+        Ok(UserPage::eager_load_all_children_from_model(
+            &ctx.page, &ctx.db, trail,
+        )?)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+/// A page of anything `T`, with one eager-loaded edge that doesn't depend on `T`.
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(
+    model = "models::Page<T>",
+    connection = "Db",
+    error = "Box<dyn std::error::Error>",
+    root_model_field = "page"
+)]
+pub struct Page<T>
+where
+    T: Clone + std::fmt::Debug + Send + 'static,
+{
+    page: models::Page<T>,
+
+    #[has_one(foreign_key_field = "featured_user_id", root_model_field = "user")]
+    featured: HasOne<User>,
+}
+
+pub type UserPage = Page<models::Stats>;
+
+/// The derived impls are generic over `T`, so `trail.featured()` must resolve for every `T`,
+/// while `graphql_schema!` only generates the inherent method for the schema's instantiation
+/// (`QueryTrail<'a, UserPage, K>`). This supplies it for the rest, through the same
+/// look-ahead the generated method uses — possible because `QueryTrail` is generated into
+/// this module. For the concrete instantiation the inherent method still wins.
+trait FeaturedTrail<'a> {
+    fn featured(&self) -> QueryTrail<'a, User, juniper_from_schema::NotWalked>;
+}
+
+impl<'a, T, K> FeaturedTrail<'a> for QueryTrail<'a, Page<T>, K>
+where
+    T: Clone + std::fmt::Debug + Send + 'static,
+{
+    fn featured(&self) -> QueryTrail<'a, User, juniper_from_schema::NotWalked> {
+        use juniper::LookAheadMethods;
+
+        let child = self.look_ahead.and_then(|la| la.select_child("featured"));
+
+        QueryTrail {
+            look_ahead: child,
+            node_type: std::marker::PhantomData,
+            walked: juniper_from_schema::NotWalked,
+        }
+    }
+}
+
+impl UserPageFields for UserPage {
+    fn field_total(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.page.payload.total)
+    }
+
+    fn field_featured(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, User, Walked>,
+    ) -> FieldResult<&User> {
+        Ok(self.featured.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+}
+
+#[test]
+fn a_generic_struct_derives_and_loads() {
+    let ctx = Context {
+        db: Db {
+            users: vec![models::User { id: 7 }],
+        },
+        page: models::Page {
+            id: 1,
+            payload: models::Stats { total: 42 },
+            featured_user_id: 7,
+        },
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ userPage { total featured { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_eq!(
+        json!({
+            "userPage": {
+                "total": 42,
+                "featured": { "id": 7 },
+            },
+        }),
+        json,
+    );
+}
+
+#[test]
+fn the_generated_prime_cache_carries_the_generics() {
+    let mut cache = juniper_eager_loading::Cache::<i32>::new();
+    let pages = vec![models::Page {
+        id: 1,
+        payload: models::Stats { total: 42 },
+        featured_user_id: 7,
+    }];
+
+    UserPage::prime_cache(&pages, &mut cache);
+    assert_eq!(
+        cache.get::<models::Page<models::Stats>>(1),
+        Some(pages[0].clone()),
+    );
+}
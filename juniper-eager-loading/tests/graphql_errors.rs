@@ -0,0 +1,78 @@
+//! The extensions shape emitted when converting the crate's `Error` into a
+//! `juniper::FieldError` is stable, machine readable, and documented on `Error::field_error`.
+
+use juniper::{FieldError, IntoFieldError, Value};
+use juniper_eager_loading::{AssociationType, Error, LoadFailedDetails};
+use serde_json::json;
+
+fn extensions_json(error: FieldError) -> serde_json::Value {
+    serde_json::to_value(error.extensions()).unwrap()
+}
+
+#[test]
+fn not_loaded_has_a_stable_extensions_shape() {
+    let error: FieldError = Error::NotLoaded(AssociationType::HasOne, None).into_field_error();
+
+    assert_eq!(
+        error.message(),
+        "`HasOne` should have been eager loaded, but wasn't"
+    );
+    assert_eq!(
+        extensions_json(error),
+        json!({
+            "code": "NOT_LOADED",
+            "association": "HasOne",
+        })
+    );
+}
+
+#[test]
+fn load_failed_has_a_stable_extensions_shape() {
+    let error: FieldError = Error::LoadFailed(AssociationType::HasOne, None).into_field_error();
+
+    assert_eq!(error.message(), "Failed to load `HasOne`");
+    assert_eq!(
+        extensions_json(error),
+        json!({
+            "code": "EAGER_LOAD_FAILED",
+            "association": "HasOne",
+        })
+    );
+}
+
+#[test]
+fn recorded_failure_details_end_up_in_the_extensions() {
+    let error: FieldError = Error::LoadFailedForIds(
+        AssociationType::HasOne,
+        LoadFailedDetails::new("HumanNode", &1, &10),
+    )
+    .into_field_error();
+
+    assert_eq!(
+        extensions_json(error),
+        json!({
+            "code": "EAGER_LOAD_FAILED",
+            "association": "HasOne",
+            "type": "HumanNode",
+            "parentId": "1",
+            "childId": "10",
+        })
+    );
+}
+
+#[test]
+fn the_builder_lets_you_add_your_own_keys() {
+    let error: FieldError = Error::LoadFailed(AssociationType::HasOne, None)
+        .field_error()
+        .extension("requestId", Value::scalar("abc-123".to_owned()))
+        .build();
+
+    assert_eq!(
+        extensions_json(error),
+        json!({
+            "code": "EAGER_LOAD_FAILED",
+            "association": "HasOne",
+            "requestId": "abc-123",
+        })
+    );
+}
@@ -0,0 +1,63 @@
+use juniper_eager_loading::group_children_by_key;
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+}
+
+fn car(id: i32, user_id: i32) -> models::Car {
+    models::Car { id, user_id }
+}
+
+#[test]
+fn groups_children_by_their_foreign_key() {
+    let cars = vec![car(10, 1), car(11, 2), car(12, 1), car(13, 3)];
+
+    let groups = group_children_by_key(cars, |car| car.user_id);
+
+    assert_eq!(groups.get(&1), Some(&vec![car(10, 1), car(12, 1)]),);
+    assert_eq!(groups.get(&2), Some(&vec![car(11, 2)]));
+    assert_eq!(groups.get(&3), Some(&vec![car(13, 3)]));
+}
+
+#[test]
+fn children_keep_their_order_within_a_group() {
+    let cars = (0..100).map(|id| car(id, id % 2)).collect::<Vec<_>>();
+
+    let groups = group_children_by_key(cars, |car| car.user_id);
+
+    let even_ids = groups[&0].iter().map(|car| car.id).collect::<Vec<_>>();
+    let mut sorted = even_ids.clone();
+    sorted.sort();
+    assert_eq!(even_ids, sorted);
+}
+
+#[test]
+fn parents_without_children_get_empty_groups() {
+    let users = [
+        models::User { id: 1 },
+        models::User { id: 2 },
+        models::User { id: 3 },
+    ];
+    let cars = vec![car(10, 1)];
+
+    let mut groups = group_children_by_key(cars, |car| car.user_id);
+
+    // Assigning each parent its bucket, the way a custom has-many impl would.
+    let cars_per_user = users
+        .iter()
+        .map(|user| groups.remove(&user.id).unwrap_or_default())
+        .collect::<Vec<_>>();
+
+    assert_eq!(cars_per_user[0], vec![car(10, 1)]);
+    assert_eq!(cars_per_user[1], vec![]);
+    assert_eq!(cars_per_user[2], vec![]);
+}
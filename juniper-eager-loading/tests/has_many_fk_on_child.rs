@@ -0,0 +1,205 @@
+//! Has-many associations keep the foreign key on the child (`cars.user_id`), so the parent has
+//! no child ids to hand back. The derived code instead passes the parent models to
+//! `LoadFrom<{parent model}>` in one call and groups the children by their foreign key. This
+//! pins that down: one query for any number of users.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasMany};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        cars: [Car!]! @juniper(ownership: "owned")
+    }
+
+    type Car {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    cars: Vec<models::Car>,
+    car_loads: Arc<AtomicUsize>,
+}
+
+pub mod models {
+    use std::sync::atomic::Ordering;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+
+    // Required by the `EagerLoadChildrenOfType` impl, but the has-many flow never calls it:
+    // children are loaded from the parent models below.
+    impl juniper_eager_loading::LoadFrom<i32> for Car {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            Ok(db
+                .cars
+                .iter()
+                .filter(|car| ids.contains(&car.id))
+                .cloned()
+                .collect())
+        }
+    }
+
+    // The foreign key is on `Car`, so cars are loaded from the parent models: every car whose
+    // `user_id` is among the given users, in one call.
+    impl juniper_eager_loading::LoadFrom<User> for Car {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(users: &[User], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            db.car_loads.fetch_add(1, Ordering::SeqCst);
+            let user_ids = users.iter().map(|user| user.id).collect::<Vec<_>>();
+            Ok(db
+                .cars
+                .iter()
+                .filter(|car| user_ids.contains(&car.user_id))
+                .cloned()
+                .collect())
+        }
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+
+        let mut users = User::from_db_models(&ctx.users);
+        User::eager_load_all_children_for_each(&mut users, &ctx.users, &ctx.db, trail)?;
+
+        Ok(users)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_many(root_model_field = "car")]
+    cars: HasMany<Car>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_cars(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Car, Walked>,
+    ) -> FieldResult<Vec<Car>> {
+        Ok(self.cars.try_unwrap()?.clone())
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Car {
+    car: models::Car,
+}
+
+impl CarFields for Car {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.car.id)
+    }
+}
+
+#[test]
+fn any_number_of_users_costs_one_car_query() {
+    let car_loads = Arc::new(AtomicUsize::new(0));
+    let users = (1..=50).map(|id| models::User { id }).collect::<Vec<_>>();
+    let cars = users
+        .iter()
+        .flat_map(|user| {
+            vec![
+                models::Car {
+                    id: user.id * 10,
+                    user_id: user.id,
+                },
+                models::Car {
+                    id: user.id * 10 + 1,
+                    user_id: user.id,
+                },
+            ]
+        })
+        .collect::<Vec<_>>();
+    let ctx = Context {
+        db: Db {
+            cars,
+            car_loads: Arc::clone(&car_loads),
+        },
+        users,
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id cars { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+
+    assert_eq!(car_loads.load(Ordering::SeqCst), 1);
+
+    // Spot check that the grouping matched children to the right parents.
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_eq!(
+        json!([{ "id": 70 }, { "id": 71 }]),
+        &json["users"][6]["cars"],
+    );
+}
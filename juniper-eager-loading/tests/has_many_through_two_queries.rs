@@ -0,0 +1,241 @@
+//! Many-to-many associations load in two hops: the join rows for the parent ids, then the
+//! target models for the ids found in the join rows. This pins the cost of that down: exactly
+//! two queries regardless of how many parents are being loaded, and the join rows are what
+//! attributes children to the right parents.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasManyThrough};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        teams: [Team!]! @juniper(ownership: "owned")
+    }
+
+    type Team {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    memberships: Vec<models::Membership>,
+    teams: Vec<models::Team>,
+    membership_loads: Arc<AtomicUsize>,
+    team_loads: Arc<AtomicUsize>,
+}
+
+pub mod models {
+    use std::sync::atomic::Ordering;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Membership {
+        pub user_id: i32,
+        pub team_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Team {
+        pub id: i32,
+    }
+
+    // First hop: the join rows for the given users.
+    impl juniper_eager_loading::LoadFrom<User> for Membership {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(users: &[User], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            db.membership_loads.fetch_add(1, Ordering::SeqCst);
+            let user_ids = users.iter().map(|user| user.id).collect::<Vec<_>>();
+            Ok(db
+                .memberships
+                .iter()
+                .filter(|membership| user_ids.contains(&membership.user_id))
+                .cloned()
+                .collect())
+        }
+    }
+
+    // Second hop: the teams the join rows point at.
+    impl juniper_eager_loading::LoadFrom<Membership> for Team {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(
+            memberships: &[Membership],
+            db: &Self::Connection,
+        ) -> Result<Vec<Self>, Self::Error> {
+            db.team_loads.fetch_add(1, Ordering::SeqCst);
+            let team_ids = memberships
+                .iter()
+                .map(|membership| membership.team_id)
+                .collect::<Vec<_>>();
+            Ok(db
+                .teams
+                .iter()
+                .filter(|team| team_ids.contains(&team.id))
+                .cloned()
+                .collect())
+        }
+    }
+
+    // Required by the `EagerLoadChildrenOfType` impl, but the has-many-through flow never
+    // calls it: both hops happen in `child_ids` above.
+    impl juniper_eager_loading::LoadFrom<i32> for Team {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            Ok(db
+                .teams
+                .iter()
+                .filter(|team| ids.contains(&team.id))
+                .cloned()
+                .collect())
+        }
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+
+        let mut users = User::from_db_models(&ctx.users);
+        User::eager_load_all_children_for_each(&mut users, &ctx.users, &ctx.db, trail)?;
+
+        Ok(users)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_many_through(join_model = "models::Membership")]
+    teams: HasManyThrough<Team>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_teams(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Team, Walked>,
+    ) -> FieldResult<Vec<Team>> {
+        Ok(self.teams.try_unwrap()?.clone())
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Team {
+    team: models::Team,
+}
+
+impl TeamFields for Team {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.team.id)
+    }
+}
+
+#[test]
+fn any_number_of_users_costs_two_queries() {
+    let membership_loads = Arc::new(AtomicUsize::new(0));
+    let team_loads = Arc::new(AtomicUsize::new(0));
+
+    // 40 users, 3 shared teams, every user on two of them.
+    let users = (1..=40).map(|id| models::User { id }).collect::<Vec<_>>();
+    let teams = (1..=3).map(|id| models::Team { id }).collect::<Vec<_>>();
+    let memberships = users
+        .iter()
+        .flat_map(|user| {
+            vec![
+                models::Membership {
+                    user_id: user.id,
+                    team_id: 1 + user.id % 3,
+                },
+                models::Membership {
+                    user_id: user.id,
+                    team_id: 1 + (user.id + 1) % 3,
+                },
+            ]
+        })
+        .collect::<Vec<_>>();
+    let ctx = Context {
+        db: Db {
+            memberships,
+            teams,
+            membership_loads: Arc::clone(&membership_loads),
+            team_loads: Arc::clone(&team_loads),
+        },
+        users,
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id teams { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+
+    assert_eq!(membership_loads.load(Ordering::SeqCst), 1);
+    assert_eq!(team_loads.load(Ordering::SeqCst), 1);
+
+    // Spot check the join rows attributed teams to the right user: user 1 is on teams 2 and 3.
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_eq!(
+        json!([{ "id": 2 }, { "id": 3 }]),
+        &json["users"][0]["teams"]
+    );
+}
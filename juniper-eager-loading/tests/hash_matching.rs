@@ -0,0 +1,180 @@
+//! When an association supplies `parent_match_hash`/`child_match_hash` — as everything the
+//! derive generates does — the matching in `eager_load_children` groups parents by hash and
+//! only runs `is_child_of` against each child's candidate bucket. With thousands of parents
+//! that's the difference between millions of comparisons and one per child. The counter here
+//! pins that down; `complexity_guard` still covers the end-to-end cost of the fallback scan.
+
+use juniper_eager_loading::{match_hash, prelude::*, GenericQueryTrail, HasMany, LoadResult};
+use juniper_from_schema::Walked;
+use std::cell::Cell;
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+}
+
+pub struct Db {
+    cars: Vec<models::Car>,
+}
+
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+thread_local! {
+    static IS_CHILD_OF_CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    cars: HasMany<Car>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Car {
+    car: models::Car,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            cars: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Car {
+    type Model = models::Car;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self { car: model.clone() }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Car {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCarsContext;
+
+impl EagerLoadChildrenOfType<Car, EverythingTrail, UserCarsContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        _models: &[Self::Model],
+        db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Car, ())>, Self::Error> {
+        Ok(LoadResult::Models(
+            db.cars.iter().cloned().map(|car| (car, ())).collect(),
+        ))
+    }
+
+    fn load_children(
+        _ids: &[Self::ChildId],
+        _db: &Self::Connection,
+    ) -> Result<Vec<models::Car>, Self::Error> {
+        unreachable!("`child_ids` always returns models")
+    }
+
+    fn is_child_of(node: &Self, child: &(Car, &())) -> bool {
+        IS_CHILD_OF_CALLS.with(|calls| calls.set(calls.get() + 1));
+        node.user.id == (child.0).car.user_id
+    }
+
+    // Both sides hash the key `is_child_of` compares, like the derive generates.
+    fn parent_match_hash(node: &Self) -> Option<u64> {
+        Some(match_hash(&node.user.id))
+    }
+
+    fn child_match_hash(child: &(Car, &())) -> Option<u64> {
+        Some(match_hash(&(child.0).car.user_id))
+    }
+
+    fn loaded_child(node: &mut Self, child: Car) {
+        node.cars.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.cars.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Car, _, UserCarsContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+const PARENTS: i32 = 3_000;
+const CHILDREN_PER_PARENT: i32 = 3;
+
+#[test]
+fn each_child_is_only_compared_against_its_candidate_bucket() {
+    let user_models = (0..PARENTS)
+        .map(|id| models::User { id })
+        .collect::<Vec<_>>();
+    let db = Db {
+        cars: (0..PARENTS * CHILDREN_PER_PARENT)
+            .map(|id| models::Car {
+                id,
+                user_id: id % PARENTS,
+            })
+            .collect(),
+    };
+
+    IS_CHILD_OF_CALLS.with(|calls| calls.set(0));
+
+    let mut users = User::from_db_models(&user_models);
+    User::eager_load_all_children_for_each(&mut users, &user_models, &db, &EverythingTrail)
+        .unwrap();
+
+    for (user, model) in users.iter().zip(&user_models) {
+        let cars = user.cars.try_unwrap().unwrap();
+        assert_eq!(cars.len(), CHILDREN_PER_PARENT as usize);
+        assert!(cars.iter().all(|car| car.car.user_id == model.id));
+    }
+
+    let children = (PARENTS * CHILDREN_PER_PARENT) as usize;
+    let calls = IS_CHILD_OF_CALLS.with(|calls| calls.get());
+    // Every comparison is a confirmation inside a bucket. Collisions could add a handful of
+    // extra calls, but nothing near the 27 million the pairwise scan would make here.
+    assert!(
+        calls < children * 2,
+        "expected roughly one `is_child_of` call per child ({}), got {}",
+        children,
+        calls
+    );
+}
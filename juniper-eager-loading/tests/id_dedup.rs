@@ -0,0 +1,163 @@
+//! The loading flow deduplicates child ids before calling `load_children`, so loaders only
+//! ever see each id once no matter how many parents share a child. The `child_ids`
+//! implementation here deliberately returns the raw, heavily duplicated id list — what a naive
+//! implementation without a `unique` call produces — and the loader records exactly what it
+//! was handed.
+
+use juniper_eager_loading::{prelude::*, test_support::EverythingTrail, HasOne, LoadResult};
+use std::cell::RefCell;
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+    loaded_ids: RefCell<Vec<Vec<i32>>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    country: HasOne<Country>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    country: models::Country,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+        }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCountryContext;
+
+impl EagerLoadChildrenOfType<Country, EverythingTrail, UserCountryContext, ()> for User {
+    type ChildId = i32;
+
+    // No `unique` call on purpose: the flow is responsible for deduplicating.
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+        Ok(LoadResult::Ids(
+            models.iter().map(|model| model.country_id).collect(),
+        ))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Country>, Self::Error> {
+        db.loaded_ids.borrow_mut().push(ids.to_vec());
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+
+    fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+        node.user.country_id == (child.0).country.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.country.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+#[test]
+fn the_loader_receives_each_id_exactly_once() {
+    let db = Db {
+        countries: vec![
+            models::Country { id: 1 },
+            models::Country { id: 2 },
+            models::Country { id: 3 },
+        ],
+        loaded_ids: RefCell::new(Vec::new()),
+    };
+
+    // 500 users spread over 3 countries.
+    let user_models = (0..500)
+        .map(|i| models::User {
+            id: i,
+            country_id: (i % 3) + 1,
+        })
+        .collect::<Vec<_>>();
+    let mut users = User::from_db_models(&user_models);
+    User::eager_load_all_children_for_each(&mut users, &user_models, &db, &EverythingTrail)
+        .unwrap();
+
+    // One load, each id exactly once, in first-seen order.
+    assert_eq!(*db.loaded_ids.borrow(), [vec![1, 2, 3]]);
+
+    for (user, model) in users.iter().zip(&user_models) {
+        assert_eq!(
+            user.country.try_unwrap().unwrap().country.id,
+            model.country_id
+        );
+    }
+}
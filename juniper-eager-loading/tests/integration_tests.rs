@@ -1,5 +1,5 @@
 use assert_json_diff::{assert_json_eq, assert_json_include};
-use juniper::{Executor, FieldError, FieldResult};
+use juniper::{Executor, FieldResult};
 use juniper_eager_loading::{
     prelude::*, EagerLoading, HasMany, HasManyThrough, HasOne, OptionHasOne,
 };
@@ -213,7 +213,7 @@ mod models {
                 .iter()
                 .map(|country| country.id)
                 .collect::<Vec<_>>();
-            let mut cities = db
+            let cities = db
                 .cities
                 .all_values()
                 .into_iter()
@@ -311,8 +311,7 @@ impl QueryFields for Query {
         let db = &executor.context().db;
 
         let user_model = db.users.get(&id).ok_or("User not found")?.clone();
-        let user = User::new_from_model(&user_model);
-        let user = User::eager_load_all_children(user, &[user_model], db, trail)?;
+        let user = User::eager_load_all_children_from_model(&user_model, db, trail)?;
         Ok(user)
     }
 
@@ -451,7 +450,7 @@ impl UserFields for User {
 
     fn field_primary_employment(
         &self,
-        executor: &Executor<'_, Context>,
+        _executor: &Executor<'_, Context>,
         _trail: &QueryTrail<'_, Employment, Walked>,
     ) -> FieldResult<Option<Employment>> {
         let employments = self.primary_employments.try_unwrap()?;
@@ -468,7 +467,7 @@ impl UserFields for User {
 
     fn field_primary_company(
         &self,
-        executor: &Executor<'_, Context>,
+        _executor: &Executor<'_, Context>,
         _trail: &QueryTrail<'_, Company, Walked>,
     ) -> FieldResult<Option<Company>> {
         let companies = self.primary_companies.try_unwrap()?;
@@ -625,10 +624,10 @@ fn loading_user() {
     let cities = StatsHash::new("cities");
     let mut users = StatsHash::new("users");
 
-    let mut country = models::Country { id: 10 };
+    let country = models::Country { id: 10 };
     let country_id = country.id;
 
-    let other_city = models::City { id: 30, country_id };
+    let _other_city = models::City { id: 30, country_id };
 
     countries.insert(country_id, country);
 
@@ -677,10 +676,10 @@ fn loading_users() {
     let cities = StatsHash::new("cities");
     let mut users = StatsHash::new("users");
 
-    let mut country = models::Country { id: 10 };
+    let country = models::Country { id: 10 };
     let country_id = country.id;
 
-    let other_city = models::City { id: 30, country_id };
+    let _other_city = models::City { id: 30, country_id };
 
     countries.insert(country_id, country);
 
@@ -785,7 +784,7 @@ fn loading_users_and_associations() {
         models::User {
             id: 5,
             country_id: country.id,
-            city_id: Some(999),
+            city_id: None,
         },
     );
 
@@ -863,13 +862,75 @@ fn loading_users_and_associations() {
     assert_eq!(2, counts.city_reads);
 }
 
+#[test]
+fn option_has_one_dangling_foreign_key_errors_instead_of_masking_as_null() {
+    let mut countries = StatsHash::new("countries");
+    let mut users = StatsHash::new("users");
+
+    let country = models::Country { id: 10 };
+    countries.insert(country.id, country.clone());
+
+    // User 1 legitimately has no city, user 2 points at a city that doesn't exist.
+    users.insert(
+        1,
+        models::User {
+            id: 1,
+            country_id: country.id,
+            city_id: None,
+        },
+    );
+    users.insert(
+        2,
+        models::User {
+            id: 2,
+            country_id: country.id,
+            city_id: Some(999),
+        },
+    );
+
+    let db = Db {
+        users,
+        countries,
+        cities: StatsHash::new("cities"),
+        employments: StatsHash::new("employments"),
+        companies: StatsHash::new("companies"),
+        issues: StatsHash::new("issue"),
+    };
+    let ctx = Context { db };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id city { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+
+    let json: Value = serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_include!(
+        expected: json!({
+            "users": [
+                { "id": 1, "city": null },
+                { "id": 2, "city": null },
+            ]
+        }),
+        actual: json,
+    );
+
+    // The dangling foreign key surfaces as a field error naming the rows involved, instead of
+    // being indistinguishable from user 1's legitimate null.
+    let error_json = serde_json::to_string(&errors).unwrap();
+    assert!(error_json.contains("parent with id `2` pointed at child id `999`"));
+}
+
 #[test]
 fn test_caching() {
     let mut users = StatsHash::new("users");
     let mut countries = StatsHash::new("countries");
     let mut cities = StatsHash::new("cities");
 
-    let mut country = models::Country { id: 1 };
+    let country = models::Country { id: 1 };
 
     let city = models::City {
         id: 2,
@@ -948,22 +1009,22 @@ fn test_caching() {
 
 #[test]
 fn test_loading_has_many_through() {
-    let mut cities = StatsHash::new("cities");
+    let cities = StatsHash::new("cities");
     let mut companies = StatsHash::new("companies");
     let mut countries = StatsHash::new("countries");
     let mut employments = StatsHash::new("employments");
     let mut users = StatsHash::new("users");
 
-    let mut country = models::Country { id: 1 };
+    let country = models::Country { id: 1 };
     countries.insert(country.id, country.clone());
 
-    let mut tonsser = models::Company {
+    let tonsser = models::Company {
         id: 2,
         name: "Tonsser".to_string(),
     };
     companies.insert(tonsser.id, tonsser.clone());
 
-    let mut peakon = models::Company {
+    let peakon = models::Company {
         id: 3,
         name: "Peakon".to_string(),
     };
@@ -976,7 +1037,7 @@ fn test_loading_has_many_through() {
     };
     users.insert(user.id, user.clone());
 
-    let mut tonsser_employment = models::Employment {
+    let tonsser_employment = models::Employment {
         id: 5,
         user_id: user.id,
         company_id: tonsser.id,
@@ -984,7 +1045,7 @@ fn test_loading_has_many_through() {
     };
     employments.insert(tonsser_employment.id, tonsser_employment.clone());
 
-    let mut peakon_employment = models::Employment {
+    let peakon_employment = models::Employment {
         id: 6,
         user_id: user.id,
         company_id: peakon.id,
@@ -1001,7 +1062,7 @@ fn test_loading_has_many_through() {
         issues: StatsHash::new("issues"),
     };
 
-    let (json, counts) = run_query(
+    let (json, _counts) = run_query(
         r#"
         query Test {
             users {
@@ -1127,6 +1188,7 @@ fn test_loading_has_many_fk_optional() {
     );
 }
 
+#[allow(dead_code)]
 struct DbStats {
     user_reads: usize,
     country_reads: usize,
@@ -1173,6 +1235,7 @@ fn run_query(query: &str, db: Db) -> (Value, DbStats) {
 struct StatsHash<K: Hash + Eq, V> {
     map: HashMap<K, V>,
     count: AtomicUsize,
+    #[allow(dead_code)]
     name: &'static str,
 }
 
@@ -1196,10 +1259,10 @@ impl<K: Hash + Eq, V> StatsHash<K, V> {
     }
 
     #[allow(dead_code)]
-    pub fn get_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
+    pub fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut V>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: ?Sized + Hash + Eq,
     {
         self.increment_reads_count();
         self.map.get_mut(k)
@@ -1207,7 +1270,7 @@ impl<K: Hash + Eq, V> StatsHash<K, V> {
 
     fn all_values(&self) -> Vec<&V> {
         self.increment_reads_count();
-        self.map.iter().map(|(_, v)| v).collect()
+        self.map.values().collect()
     }
 
     fn reads_count(&self) -> usize {
@@ -0,0 +1,53 @@
+//! `try_iter`, `len`, and `is_empty` work on list edges without going through `try_unwrap`
+//! first. They're `Result`-flavored on purpose: a failed edge must error, not masquerade as an
+//! empty collection.
+
+use juniper_eager_loading::{AssociationType, Error, HasMany, HasManyThrough};
+
+#[test]
+fn iterating_loaded_values() {
+    let mut cars = HasMany::default();
+    cars.loaded(1);
+    cars.loaded(2);
+    cars.loaded(3);
+
+    let sum: i32 = cars.try_iter().unwrap().sum();
+    assert_eq!(sum, 6);
+    assert_eq!(cars.len().unwrap(), 3);
+    assert!(!cars.is_empty().unwrap());
+}
+
+#[test]
+fn an_empty_edge_iterates_as_empty() {
+    let cars = HasMany::<i32>::default();
+
+    assert_eq!(cars.try_iter().unwrap().count(), 0);
+    assert_eq!(cars.len().unwrap(), 0);
+    assert!(cars.is_empty().unwrap());
+}
+
+#[test]
+fn a_failed_edge_never_masquerades_as_an_empty_collection() {
+    let mut cars = HasMany::<i32>::default();
+    cars.load_failed();
+
+    assert!(matches!(
+        cars.try_iter(),
+        Err(Error::LoadFailed(AssociationType::HasMany, None)),
+    ));
+    assert!(cars.len().is_err());
+    assert!(cars.is_empty().is_err());
+}
+
+#[test]
+fn has_many_through_iterates_the_same_way() {
+    let mut companies = HasManyThrough::default();
+    companies.loaded("acme");
+    assert_eq!(companies.try_iter().unwrap().count(), 1);
+    assert_eq!(companies.len().unwrap(), 1);
+
+    let mut failed = HasManyThrough::<i32>::default();
+    failed.load_failed();
+    assert!(failed.try_iter().is_err());
+    assert!(failed.is_empty().is_err());
+}
@@ -0,0 +1,157 @@
+//! The whole point of eager loading is doing one load per association, no matter how many
+//! parents there are. `CountingConnection` lets tests pin that down as an exact number.
+
+use juniper_eager_loading::test_support::{CountingConnection, MockConnection, MockStore};
+use juniper_eager_loading::{prelude::*, GenericQueryTrail, HasMany, LoadResult};
+use juniper_from_schema::Walked;
+
+mod models {
+    use juniper_eager_loading::test_support::MockModel;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+
+    impl MockModel for Car {
+        type Id = i32;
+
+        // Cars are loaded by their owner's id, so that's the lookup key.
+        #[allow(clippy::misnamed_getters)]
+        fn id(&self) -> Self::Id {
+            self.user_id
+        }
+    }
+}
+
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    cars: HasMany<Car>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Car {
+    car: models::Car,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = CountingConnection<MockConnection>;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            cars: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Car {
+    type Model = models::Car;
+    type Id = i32;
+    type Connection = CountingConnection<MockConnection>;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self { car: model.clone() }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Car {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCarsContext;
+
+impl EagerLoadChildrenOfType<Car, EverythingTrail, UserCarsContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Car, ())>, Self::Error> {
+        Ok(LoadResult::Ids(
+            models.iter().map(|model| model.id).collect(),
+        ))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Car>, Self::Error> {
+        db.counted_load(ids).map_err(Into::into)
+    }
+
+    fn is_child_of(node: &Self, child: &(Car, &())) -> bool {
+        node.user.id == (child.0).car.user_id
+    }
+
+    fn loaded_child(node: &mut Self, child: Car) {
+        node.cars.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.cars.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Car, _, UserCarsContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+#[test]
+fn a_hundred_parents_still_perform_one_load() {
+    let store = MockStore::new();
+    store.insert((0..100).map(|id| models::Car { id, user_id: id }).collect());
+    let db = CountingConnection::new(store.connection());
+
+    let user_models = (0..100).map(|id| models::User { id }).collect::<Vec<_>>();
+    let mut users = User::from_db_models(&user_models);
+    User::eager_load_all_children_for_each(&mut users, &user_models, &db, &EverythingTrail)
+        .unwrap();
+
+    assert_eq!(db.load_calls(), 1);
+    assert_eq!(db.load_calls_for::<models::Car>(), 1);
+    assert_eq!(db.load_calls_for::<models::User>(), 0);
+}
+
+#[test]
+fn clones_share_the_counts() {
+    let store = MockStore::new();
+    let db = CountingConnection::new(store.connection());
+    let handle = db.clone();
+
+    let _cars: Vec<models::Car> = db.counted_load(&[1]).unwrap();
+
+    assert_eq!(handle.load_calls(), 1);
+}
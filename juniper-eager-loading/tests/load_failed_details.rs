@@ -0,0 +1,226 @@
+//! When a `HasOne` load fails we want the error to name the parent row and the child id it
+//! pointed at, not just "failed to load".
+
+use juniper_eager_loading::{
+    prelude::*, GenericQueryTrail, HasMany, HasOne, LoadFailedDetails, LoadFrom, LoadResult,
+};
+use juniper_from_schema::Walked;
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    country: HasOne<Country>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    country: models::Country,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+        }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCountryContext;
+
+impl EagerLoadChildrenOfType<Country, EverythingTrail, UserCountryContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+        Ok(LoadResult::Ids(
+            models.iter().map(|model| model.country_id).collect(),
+        ))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Country>, Self::Error> {
+        LoadFrom::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+        node.user.country_id == (child.0).country.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        // This mirrors what the derived code does: only build the details when the load
+        // actually failed.
+        let Self { country, user, .. } = node;
+        country.assert_loaded_otherwise_failed_with(|| {
+            LoadFailedDetails::new("Country", &user.id, &user.country_id)
+        });
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+fn eager_load_users(user_models: &[models::User], db: &Db) -> Vec<User> {
+    let mut users = User::from_db_models(user_models);
+    User::eager_load_all_children_for_each(&mut users, user_models, db, &EverythingTrail).unwrap();
+    users
+}
+
+#[test]
+fn the_single_node_entry_point_loads_without_a_vec_round_trip() {
+    let user_model = models::User {
+        id: 1,
+        country_id: 10,
+    };
+    let db = Db {
+        countries: vec![models::Country { id: 10 }],
+    };
+
+    let user = User::new_from_model(&user_model);
+    let user = User::eager_load_all_children(user, &user_model, &db, &EverythingTrail).unwrap();
+
+    assert_eq!(user.country.try_unwrap().unwrap().country.id, 10);
+}
+
+#[test]
+fn a_dangling_foreign_key_names_both_ids_in_the_error() {
+    let user_models = vec![models::User {
+        id: 1,
+        country_id: 10,
+    }];
+    let db = Db { countries: vec![] };
+
+    let users = eager_load_users(&user_models, &db);
+
+    let err = users[0].country.try_unwrap().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Failed to load `Country` for `HasOne`: \
+         parent with id `1` pointed at child id `10`, which produced no model",
+    );
+}
+
+#[test]
+fn successful_loads_record_no_details() {
+    let user_models = vec![models::User {
+        id: 1,
+        country_id: 10,
+    }];
+    let db = Db {
+        countries: vec![models::Country { id: 10 }],
+    };
+
+    let users = eager_load_users(&user_models, &db);
+
+    let country = users[0].country.try_unwrap().unwrap();
+    assert_eq!(country.country.id, 10);
+}
+
+#[test]
+fn list_edges_can_record_failure_details_too() {
+    let mut edge = HasMany::<i32>::default();
+    edge.load_failed_with(LoadFailedDetails::new("Car", &1, &7));
+
+    let err = edge.try_unwrap().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Failed to load `Car` for `HasMany`: \
+         parent with id `1` pointed at child id `7`, which produced no model",
+    );
+
+    // A successful retry clears the recorded details along with the failure.
+    edge.loaded_all(vec![7]);
+    assert_eq!(edge.try_unwrap().unwrap(), &vec![7]);
+}
+
+#[test]
+fn the_plain_assertion_keeps_the_old_error_message() {
+    let mut edge = HasOne::<i32>::default();
+    edge.assert_loaded_otherwise_failed();
+
+    let err = edge.try_unwrap().unwrap_err();
+    assert_eq!(err.to_string(), "Failed to load `HasOne`");
+}
@@ -0,0 +1,273 @@
+//! `LoadStats` accumulates one entry per association pass — which loader batches ran, how
+//! many unique ids were requested, and how many rows came back — so a test (or production
+//! logging) can assert exactly what a GraphQL query cost.
+
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasMany, HasOne, LoadFrom, LoadStats};
+use juniper_from_schema::graphql_schema;
+use std::sync::Mutex;
+
+static STATS: Mutex<Option<LoadStats>> = Mutex::new(None);
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        country: Country!
+        cars: [Car!]!
+    }
+
+    type Country {
+        id: Int!
+    }
+
+    type Car {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+    cars: Vec<models::Car>,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LoadFrom<i32> for models::Car {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .cars
+            .iter()
+            .filter(|car| ids.contains(&car.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LoadFrom<models::User> for models::Car {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(users: &[models::User], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        let user_ids = users.iter().map(|user| user.id).collect::<Vec<_>>();
+        Ok(db
+            .cars
+            .iter()
+            .filter(|car| user_ids.contains(&car.user_id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+
+        let mut users = User::from_db_models(&ctx.users);
+        let mut stats = LoadStats::default();
+        User::eager_load_all_children_for_each_with_stats(
+            &mut users, &ctx.users, &ctx.db, trail, &mut stats,
+        )?;
+        *STATS.lock().unwrap() = Some(stats);
+
+        Ok(users)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+
+    #[has_many(root_model_field = "car")]
+    cars: HasMany<Car>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        Ok(self.country.try_unwrap()?)
+    }
+
+    fn field_cars(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Car, Walked>,
+    ) -> FieldResult<&Vec<Car>> {
+        Ok(self.cars.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.country.id)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Car {
+    car: models::Car,
+}
+
+impl CarFields for Car {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.car.id)
+    }
+}
+
+fn run(query: &str) -> LoadStats {
+    let ctx = Context {
+        db: Db {
+            countries: vec![models::Country { id: 10 }],
+            cars: vec![
+                models::Car { id: 20, user_id: 1 },
+                models::Car { id: 21, user_id: 2 },
+            ],
+        },
+        users: vec![
+            models::User {
+                id: 1,
+                country_id: 10,
+            },
+            models::User {
+                id: 2,
+                country_id: 10,
+            },
+            models::User {
+                id: 3,
+                country_id: 10,
+            },
+        ],
+    };
+
+    let (_, errors) = juniper::execute(
+        query,
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+
+    STATS.lock().unwrap().take().unwrap()
+}
+
+// One test so the stashed stats aren't interleaved by concurrently running tests.
+#[test]
+fn stats_count_batches_ids_and_rows() {
+    let stats = run("{ users { id country { id } cars { id } } }");
+
+    assert_eq!(stats.associations.len(), 2);
+
+    let country = &stats.associations[0];
+    assert!(
+        country.child_type.ends_with("Country"),
+        "{}",
+        country.child_type
+    );
+    assert_eq!(country.batches_issued, 1);
+    // Three users share one country: the id is deduplicated before loading.
+    assert_eq!(country.ids_requested, 1);
+    assert_eq!(country.rows_loaded, 1);
+
+    let cars = &stats.associations[1];
+    assert!(cars.child_type.ends_with("Car"), "{}", cars.child_type);
+    // `has_many` loads through the parent models, so there are no ids to count.
+    assert_eq!(cars.batches_issued, 1);
+    assert_eq!(cars.ids_requested, 0);
+    assert_eq!(cars.rows_loaded, 2);
+
+    assert_eq!(stats.batches_issued(), 2);
+    assert_eq!(stats.rows_loaded(), 3);
+
+    // An association the query doesn't select never shows up.
+    let stats = run("{ users { id cars { id } } }");
+    assert_eq!(stats.associations.len(), 1);
+    assert!(stats.associations[0].child_type.ends_with("Car"));
+    assert_eq!(stats.batches_issued(), 1);
+}
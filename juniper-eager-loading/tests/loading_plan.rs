@@ -0,0 +1,170 @@
+//! The loading plan renders as an indented tree so you can see, for a node type, exactly which
+//! associations eager loading will consider — including where it stops because a branch cycles
+//! back, and which branches a query trail doesn't select.
+
+use juniper_eager_loading::{AssociationType, LoadingPlan, LoadingPlanEdge, LoadingPlanned};
+
+// User ─ cars ─> Car ─ engine ─> Engine
+//                    └ owner ──> User (cycle)
+pub struct User;
+pub struct Car;
+pub struct Engine;
+
+impl LoadingPlanned for User {
+    fn loading_plan_into(visited: &mut Vec<&'static str>) -> LoadingPlan {
+        let mut plan = LoadingPlan::new("User");
+        visited.push("User");
+        plan.edges.push(LoadingPlanEdge {
+            field_name: "cars",
+            edge_kind: AssociationType::HasMany,
+            child_type: "Car",
+            foreign_key: Some("user_id"),
+            selected: None,
+            child: if visited.contains(&"Car") {
+                None
+            } else {
+                Some(Car::loading_plan_into(visited))
+            },
+        });
+        visited.pop();
+        plan
+    }
+}
+
+impl LoadingPlanned for Car {
+    fn loading_plan_into(visited: &mut Vec<&'static str>) -> LoadingPlan {
+        let mut plan = LoadingPlan::new("Car");
+        visited.push("Car");
+        plan.edges.push(LoadingPlanEdge {
+            field_name: "engine",
+            edge_kind: AssociationType::HasOne,
+            child_type: "Engine",
+            foreign_key: Some("engine_id"),
+            selected: None,
+            child: if visited.contains(&"Engine") {
+                None
+            } else {
+                Some(Engine::loading_plan_into(visited))
+            },
+        });
+        plan.edges.push(LoadingPlanEdge {
+            field_name: "owner",
+            edge_kind: AssociationType::HasOne,
+            child_type: "User",
+            foreign_key: Some("owner_id"),
+            selected: None,
+            child: if visited.contains(&"User") {
+                None
+            } else {
+                Some(User::loading_plan_into(visited))
+            },
+        });
+        visited.pop();
+        plan
+    }
+}
+
+impl LoadingPlanned for Engine {
+    fn loading_plan_into(_visited: &mut Vec<&'static str>) -> LoadingPlan {
+        LoadingPlan::new("Engine")
+    }
+}
+
+#[test]
+fn the_plan_renders_as_an_indented_tree_with_cycles_marked() {
+    let rendered = User::loading_plan().render();
+
+    assert_eq!(
+        rendered,
+        "\
+User
+└─ cars: HasMany<Car>
+   └─ engine: HasOne<Engine>
+   └─ owner: HasOne<User> (cycle)",
+    );
+}
+
+#[test]
+fn starting_lower_in_the_tree_follows_the_cycle_the_other_way() {
+    let rendered = Car::loading_plan().render();
+
+    assert_eq!(
+        rendered,
+        "\
+Car
+└─ engine: HasOne<Engine>
+└─ owner: HasOne<User>
+   └─ cars: HasMany<Car> (cycle)",
+    );
+}
+
+#[test]
+fn unselected_branches_are_annotated_and_not_followed() {
+    // What `loading_plan_for_trail` produces for `{ users { cars { engine } } }`: the `owner`
+    // branch isn't selected, so it's annotated and not followed.
+    let plan = LoadingPlan {
+        node_type: "User",
+        edges: vec![LoadingPlanEdge {
+            field_name: "cars",
+            edge_kind: AssociationType::HasMany,
+            child_type: "Car",
+            foreign_key: Some("user_id"),
+            selected: Some(true),
+            child: Some(LoadingPlan {
+                node_type: "Car",
+                edges: vec![
+                    LoadingPlanEdge {
+                        field_name: "engine",
+                        edge_kind: AssociationType::HasOne,
+                        child_type: "Engine",
+                        foreign_key: Some("engine_id"),
+                        selected: Some(true),
+                        child: Some(LoadingPlan::new("Engine")),
+                    },
+                    LoadingPlanEdge {
+                        field_name: "owner",
+                        edge_kind: AssociationType::HasOne,
+                        child_type: "User",
+                        foreign_key: Some("owner_id"),
+                        selected: Some(false),
+                        child: None,
+                    },
+                ],
+            }),
+        }],
+    };
+
+    assert_eq!(
+        plan.render(),
+        "\
+User
+└─ cars: HasMany<Car>
+   └─ engine: HasOne<Engine>
+   └─ owner: HasOne<User> (not selected)",
+    );
+}
+
+#[test]
+fn the_dot_output_covers_the_whole_graph_once() {
+    let dot = juniper_eager_loading::loading_plan_dot(&[User::loading_plan()]);
+
+    assert_eq!(
+        dot,
+        r#"digraph eager_loading {
+    "User";
+    "Car";
+    "Engine";
+    "User" -> "Car" [label="cars: HasMany (fk: user_id)"];
+    "Car" -> "Engine" [label="engine: HasOne (fk: engine_id)"];
+    "Car" -> "User" [label="owner: HasOne (fk: owner_id)"];
+}"#,
+    );
+}
+
+#[test]
+fn overlapping_roots_do_not_duplicate_nodes_or_edges() {
+    let dot = juniper_eager_loading::loading_plan_dot(&[User::loading_plan(), Car::loading_plan()]);
+
+    assert_eq!(dot.matches("\"Car\";").count(), 1);
+    assert_eq!(dot.matches("-> \"Engine\"").count(), 1);
+}
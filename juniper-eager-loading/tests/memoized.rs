@@ -0,0 +1,144 @@
+#![cfg(feature = "cached")]
+
+use juniper_eager_loading::{Cache, LoadFrom, MemoizedLoader};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub name: String,
+    }
+}
+
+pub struct Db {
+    users: Vec<models::User>,
+    user_loads: AtomicUsize,
+}
+
+impl Db {
+    fn new(users: Vec<models::User>) -> Self {
+        Db {
+            users,
+            user_loads: AtomicUsize::new(0),
+        }
+    }
+
+    fn user_loads(&self) -> usize {
+        self.user_loads.load(Ordering::SeqCst)
+    }
+}
+
+impl LoadFrom<i32> for models::User {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.user_loads.fetch_add(1, Ordering::SeqCst);
+        Ok(db
+            .users
+            .iter()
+            .filter(|user| ids.contains(&user.id))
+            .cloned()
+            .collect())
+    }
+}
+
+fn user(id: i32, name: &str) -> models::User {
+    models::User {
+        id,
+        name: name.to_string(),
+    }
+}
+
+fn loader() -> MemoizedLoader<i32, models::User, impl Fn(&models::User) -> i32> {
+    MemoizedLoader::new(Duration::from_secs(60), |user: &models::User| user.id)
+}
+
+#[test]
+fn hits_are_shared_across_requests() {
+    let db = Db::new(vec![user(1, "alice"), user(2, "bob")]);
+    let loader = loader();
+
+    // First "request".
+    let mut cache = Cache::new();
+    let users = loader.load(&[1, 2], &db, &mut cache).unwrap();
+    assert_eq!(users, vec![user(1, "alice"), user(2, "bob")]);
+    assert_eq!(db.user_loads(), 1);
+
+    // Second "request" with a fresh per-request cache hits the memoized cache.
+    let mut cache = Cache::new();
+    let users = loader.load(&[1, 2], &db, &mut cache).unwrap();
+    assert_eq!(users, vec![user(1, "alice"), user(2, "bob")]);
+    assert_eq!(db.user_loads(), 1);
+}
+
+#[test]
+fn only_unmemoized_ids_are_loaded() {
+    let db = Db::new(vec![user(1, "alice"), user(2, "bob")]);
+    let loader = loader();
+
+    let mut cache = Cache::new();
+    loader.load(&[1], &db, &mut cache).unwrap();
+    assert_eq!(db.user_loads(), 1);
+
+    let mut cache = Cache::new();
+    let users = loader.load(&[1, 2], &db, &mut cache).unwrap();
+    assert_eq!(users, vec![user(1, "alice"), user(2, "bob")]);
+    assert_eq!(db.user_loads(), 2);
+}
+
+#[test]
+fn the_per_request_cache_wins_within_a_request() {
+    let db = Db::new(vec![user(1, "alice")]);
+    let loader = loader();
+
+    let mut cache = Cache::new();
+    cache.insert(1, user(1, "not alice"));
+
+    let users = loader.load(&[1], &db, &mut cache).unwrap();
+    assert_eq!(users, vec![user(1, "not alice")]);
+    assert_eq!(db.user_loads(), 0);
+}
+
+#[test]
+fn invalidation_forces_a_fresh_load_for_that_id_only() {
+    let db = Db::new(vec![user(1, "alice"), user(2, "bob")]);
+    let loader = loader();
+
+    let mut cache = Cache::new();
+    loader.load(&[1, 2], &db, &mut cache).unwrap();
+    assert_eq!(db.user_loads(), 1);
+
+    loader.invalidate(&1);
+
+    let mut cache = Cache::new();
+    let users = loader.load(&[1, 2], &db, &mut cache).unwrap();
+    assert_eq!(users, vec![user(1, "alice"), user(2, "bob")]);
+    assert_eq!(db.user_loads(), 2);
+}
+
+#[test]
+fn primed_models_are_never_loaded() {
+    let db = Db::new(vec![user(1, "alice"), user(2, "bob")]);
+    let loader = loader();
+
+    // The root resolver already fetched the users through a join; prime them.
+    let mut cache = Cache::new();
+    cache.prime(vec![user(1, "alice"), user(2, "bob")], |user| user.id);
+
+    let users = loader.load(&[1, 2], &db, &mut cache).unwrap();
+    assert_eq!(users, vec![user(1, "alice"), user(2, "bob")]);
+    assert_eq!(db.user_loads(), 0);
+}
+
+#[test]
+fn missing_ids_are_skipped() {
+    let db = Db::new(vec![user(1, "alice")]);
+    let loader = loader();
+
+    let mut cache = Cache::new();
+    let users = loader.load(&[404, 1], &db, &mut cache).unwrap();
+    assert_eq!(users, vec![user(1, "alice")]);
+}
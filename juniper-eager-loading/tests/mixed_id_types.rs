@@ -1,8 +1,6 @@
-use assert_json_diff::{assert_json_eq, assert_json_include};
+use assert_json_diff::assert_json_include;
 use juniper::{EmptyMutation, Executor, FieldResult, ID};
-use juniper_eager_loading::{
-    prelude::*, EagerLoading, HasMany, HasManyThrough, HasOne, OptionHasOne,
-};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasManyThrough, HasOne};
 use juniper_from_schema::graphql_schema;
 use serde_json::{json, Value};
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -361,6 +359,7 @@ fn run_query(query: &str, db: Db) -> (Value, DbStats) {
 struct StatsHash<K: Hash + Eq, V> {
     map: HashMap<K, V>,
     count: AtomicUsize,
+    #[allow(dead_code)]
     name: &'static str,
 }
 
@@ -384,10 +383,10 @@ impl<K: Hash + Eq, V> StatsHash<K, V> {
     }
 
     #[allow(dead_code)]
-    pub fn get_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
+    pub fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut V>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: ?Sized + Hash + Eq,
     {
         self.increment_reads_count();
         self.map.get_mut(k)
@@ -395,7 +394,7 @@ impl<K: Hash + Eq, V> StatsHash<K, V> {
 
     fn all_values(&self) -> Vec<&V> {
         self.increment_reads_count();
-        self.map.iter().map(|(_, v)| v).collect()
+        self.map.values().collect()
     }
 
     fn reads_count(&self) -> usize {
@@ -411,6 +410,7 @@ impl<K: Hash + Eq, V> StatsHash<K, V> {
     }
 }
 
+#[allow(dead_code)]
 trait SortedExtension {
     fn sorted(self) -> Self;
 }
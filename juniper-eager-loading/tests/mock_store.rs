@@ -0,0 +1,187 @@
+//! `test_support::MockStore` should be a drop-in replacement for the bespoke `Db` fakes the
+//! other tests hand-roll: rows registered per model type, `LoadFrom` for free via `MockModel`,
+//! a log of every load call, and failure injection for error paths.
+
+use juniper_eager_loading::test_support::{LoadCall, MockConnection, MockStore};
+use juniper_eager_loading::{prelude::*, GenericQueryTrail, HasMany, LoadFrom, LoadResult};
+use juniper_from_schema::Walked;
+
+mod models {
+    use juniper_eager_loading::test_support::MockModel;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+
+    impl MockModel for Car {
+        type Id = i32;
+
+        fn id(&self) -> Self::Id {
+            self.id
+        }
+    }
+}
+
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    cars: HasMany<Car>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Car {
+    car: models::Car,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = MockConnection;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            cars: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Car {
+    type Model = models::Car;
+    type Id = i32;
+    type Connection = MockConnection;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self { car: model.clone() }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Car {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCarsContext;
+
+impl EagerLoadChildrenOfType<Car, EverythingTrail, UserCarsContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Car, ())>, Self::Error> {
+        Ok(LoadResult::Ids(
+            models.iter().map(|model| model.id).collect(),
+        ))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Car>, Self::Error> {
+        LoadFrom::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Car, &())) -> bool {
+        node.user.id == (child.0).car.user_id
+    }
+
+    fn loaded_child(node: &mut Self, child: Car) {
+        node.cars.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.cars.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Car, _, UserCarsContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+#[test]
+fn eager_loading_against_registered_rows() {
+    let store = MockStore::new();
+    store.insert(vec![
+        models::Car { id: 1, user_id: 1 },
+        models::Car { id: 2, user_id: 2 },
+    ]);
+    let db = store.connection();
+
+    let user_models = vec![models::User { id: 1 }, models::User { id: 2 }];
+    let mut users = User::from_db_models(&user_models);
+    User::eager_load_all_children_for_each(&mut users, &user_models, &db, &EverythingTrail)
+        .unwrap();
+
+    assert_eq!(users[0].cars.try_unwrap().unwrap()[0].car.id, 1);
+    assert_eq!(users[1].cars.try_unwrap().unwrap()[0].car.id, 2);
+}
+
+#[test]
+fn every_load_call_is_recorded() {
+    let store = MockStore::new();
+    store.insert(vec![models::Car { id: 1, user_id: 1 }]);
+    let db = store.connection();
+
+    let _cars: Vec<models::Car> = LoadFrom::load(&[1, 2], &db).unwrap();
+
+    assert_eq!(
+        store.load_log(),
+        [LoadCall {
+            model: "Car",
+            ids: vec!["1".to_owned(), "2".to_owned()],
+        }],
+    );
+}
+
+#[test]
+fn only_the_next_load_fails_after_injecting_a_failure() {
+    let store = MockStore::new();
+    store.insert(vec![models::Car { id: 1, user_id: 1 }]);
+    store.fail_next_load::<models::Car>("db went away");
+    let db = store.connection();
+
+    let err = <models::Car as LoadFrom<i32>>::load(&[1], &db).unwrap_err();
+    assert_eq!(err.to_string(), "db went away");
+
+    let cars: Vec<models::Car> = LoadFrom::load(&[1], &db).unwrap();
+    assert_eq!(cars.len(), 1);
+}
+
+#[test]
+fn loading_a_type_without_registered_rows_finds_nothing() {
+    let store = MockStore::new();
+    let db = store.connection();
+
+    let cars: Vec<models::Car> = LoadFrom::load(&[1], &db).unwrap();
+    assert!(cars.is_empty());
+}
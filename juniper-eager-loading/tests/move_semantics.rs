@@ -0,0 +1,185 @@
+//! Children with exactly one parent are moved into their edge instead of cloned. Only genuinely
+//! shared children — same child referenced by several parents — pay for clones, and only one per
+//! parent.
+
+use juniper_eager_loading::{prelude::*, unique, GenericQueryTrail, HasOne, LoadFrom, LoadResult};
+use juniper_from_schema::Walked;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static COUNTRY_NODE_CLONES: AtomicUsize = AtomicUsize::new(0);
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    country: HasOne<Country>,
+}
+
+#[derive(Debug)]
+pub struct Country {
+    country: models::Country,
+}
+
+// A counting `Clone` so the tests can observe exactly how often child nodes get cloned during
+// attachment.
+impl Clone for Country {
+    fn clone(&self) -> Self {
+        COUNTRY_NODE_CLONES.fetch_add(1, Ordering::SeqCst);
+        Country {
+            country: self.country.clone(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+        }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCountryContext;
+
+impl EagerLoadChildrenOfType<Country, EverythingTrail, UserCountryContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+        let ids = models
+            .iter()
+            .map(|model| model.country_id)
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Country>, Self::Error> {
+        <models::Country as LoadFrom<i32>>::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+        node.user.country_id == (child.0).country.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.country.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+fn user(id: i32, country_id: i32) -> models::User {
+    models::User { id, country_id }
+}
+
+fn load(user_models: &[models::User], db: &Db) -> Vec<User> {
+    let mut users = User::from_db_models(user_models);
+    User::eager_load_all_children_for_each(&mut users, user_models, db, &EverythingTrail).unwrap();
+    users
+}
+
+// One test so the clone counter isn't shared between concurrently running tests.
+#[test]
+fn children_with_one_parent_are_moved_not_cloned() {
+    let db = Db {
+        countries: vec![models::Country { id: 1 }, models::Country { id: 2 }],
+    };
+
+    // Every country belongs to exactly one user: attachment must not clone at all.
+    let users = load(&[user(1, 1), user(2, 2)], &db);
+    assert_eq!(users[0].country.try_unwrap().unwrap().country.id, 1);
+    assert_eq!(users[1].country.try_unwrap().unwrap().country.id, 2);
+    assert_eq!(COUNTRY_NODE_CLONES.load(Ordering::SeqCst), 0);
+
+    // Country 1 is shared by two users: one clone per parent, country 2 is still moved.
+    let users = load(&[user(1, 1), user(2, 1), user(3, 2)], &db);
+    assert_eq!(users[0].country.try_unwrap().unwrap().country.id, 1);
+    assert_eq!(users[1].country.try_unwrap().unwrap().country.id, 1);
+    assert_eq!(users[2].country.try_unwrap().unwrap().country.id, 2);
+    assert_eq!(COUNTRY_NODE_CLONES.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,119 @@
+//! The opt-in N+1 detector on `Cache` warns through the `log` crate when the same model type is
+//! lazily loaded more than the configured number of times in one request.
+//!
+//! The logger is a process-wide global, so every test uses its own model type and only looks at
+//! the warnings mentioning that type. That keeps the tests independent even though they run in
+//! parallel.
+
+use juniper_eager_loading::Cache;
+use std::sync::{Mutex, Once};
+
+static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct CapturingLogger;
+
+impl log::Log for CapturingLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        MESSAGES.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger;
+
+fn init_logger() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Warn);
+    });
+}
+
+fn warnings_mentioning(needle: &str) -> Vec<String> {
+    MESSAGES
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|message| message.contains(needle))
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn the_warning_fires_at_n_plus_one_and_not_before() {
+    init_logger();
+    struct WarnAtFour;
+
+    let mut cache = Cache::<i32>::new();
+    cache.detect_n_plus_one(3);
+
+    for _ in 0..3 {
+        cache.record_lazy_load::<WarnAtFour>();
+    }
+    assert_eq!(warnings_mentioning("WarnAtFour"), Vec::<String>::new());
+
+    cache.record_lazy_load::<WarnAtFour>();
+
+    let warnings = warnings_mentioning("WarnAtFour");
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("4 times"), "was: {}", warnings[0]);
+    assert!(
+        warnings[0].contains("threshold is 3"),
+        "was: {}",
+        warnings[0]
+    );
+}
+
+#[test]
+fn the_warning_fires_only_once_per_type() {
+    init_logger();
+    struct WarnsOnce;
+
+    let mut cache = Cache::<i32>::new();
+    cache.detect_n_plus_one(1);
+
+    for _ in 0..5 {
+        cache.record_lazy_load::<WarnsOnce>();
+    }
+
+    assert_eq!(warnings_mentioning("WarnsOnce").len(), 1);
+    assert_eq!(cache.lazy_load_count::<WarnsOnce>(), 5);
+}
+
+#[test]
+fn types_are_counted_independently() {
+    init_logger();
+    struct NoisyModel;
+    struct QuietModel;
+
+    let mut cache = Cache::<i32>::new();
+    cache.detect_n_plus_one(2);
+
+    for _ in 0..3 {
+        cache.record_lazy_load::<NoisyModel>();
+    }
+    cache.record_lazy_load::<QuietModel>();
+
+    assert_eq!(warnings_mentioning("NoisyModel").len(), 1);
+    assert_eq!(warnings_mentioning("QuietModel").len(), 0);
+}
+
+#[test]
+fn detection_is_opt_in() {
+    init_logger();
+    struct UndetectedModel;
+
+    let mut cache = Cache::<i32>::new();
+
+    for _ in 0..10 {
+        cache.record_lazy_load::<UndetectedModel>();
+    }
+
+    assert_eq!(warnings_mentioning("UndetectedModel").len(), 0);
+    assert_eq!(cache.lazy_load_count::<UndetectedModel>(), 0);
+}
@@ -0,0 +1,187 @@
+//! The container-level `id` attribute with a `Uuid`-style newtype id: anything `Hash + Eq +
+//! Clone` works, the conventional `i32` is only a default. `connection` and `error` are
+//! overridden alongside it, independently per struct.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasOne, LoadFrom};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: String! @juniper(ownership: "owned")
+        country: Country!
+    }
+
+    type Country {
+        id: String! @juniper(ownership: "owned")
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+}
+
+pub mod models {
+    /// A stand-in for `uuid::Uuid`: an opaque, non-integer id.
+    #[derive(Clone, Hash, Eq, PartialEq, Debug)]
+    pub struct Uuid(pub [u8; 16]);
+
+    impl Uuid {
+        pub fn from_seed(seed: u8) -> Self {
+            Uuid([seed; 16])
+        }
+
+        pub fn to_hex(&self) -> String {
+            self.0.iter().map(|byte| format!("{:02x}", byte)).collect()
+        }
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: Uuid,
+        pub country_id: Uuid,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: Uuid,
+    }
+}
+
+impl LoadFrom<models::Uuid> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[models::Uuid], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+        Ok(User::eager_load(&ctx.users, &ctx.db, trail)?)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(
+    connection = "Db",
+    error = "Box<dyn std::error::Error>",
+    id = "models::Uuid"
+)]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<String> {
+        Ok(self.user.id.to_hex())
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        Ok(self.country.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(
+    connection = "Db",
+    error = "Box<dyn std::error::Error>",
+    id = "models::Uuid"
+)]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<String> {
+        Ok(self.country.id.to_hex())
+    }
+}
+
+#[test]
+fn newtype_ids_load_like_integer_ones() {
+    let country_id = models::Uuid::from_seed(7);
+    let ctx = Context {
+        db: Db {
+            countries: vec![models::Country {
+                id: country_id.clone(),
+            }],
+        },
+        users: vec![models::User {
+            id: models::Uuid::from_seed(1),
+            country_id: country_id.clone(),
+        }],
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id country { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_eq!(
+        json!({
+            "users": [{
+                "id": models::Uuid::from_seed(1).to_hex(),
+                "country": { "id": country_id.to_hex() },
+            }],
+        }),
+        json,
+    );
+}
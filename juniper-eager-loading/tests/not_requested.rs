@@ -0,0 +1,50 @@
+//! An edge the query didn't select is intentionally skipped by eager loading. Touching it
+//! anyway should say so, instead of giving the same "not loaded" error you get when you forgot
+//! to eager load.
+
+use juniper_eager_loading::{set_strict_not_loaded_checks, HasOne};
+
+#[test]
+fn a_skipped_edge_says_the_field_was_not_selected() {
+    let mut edge = HasOne::<i32>::default();
+    edge.not_requested();
+
+    let err = edge.try_unwrap().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`HasOne` wasn't eager loaded because the GraphQL field wasn't selected by the query",
+    );
+}
+
+#[test]
+fn a_forgotten_edge_keeps_the_not_loaded_message() {
+    let edge = HasOne::<i32>::default();
+
+    let err = edge.try_unwrap().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`HasOne` should have been eager loaded, but wasn't",
+    );
+}
+
+#[test]
+fn marking_does_not_clobber_a_loaded_value() {
+    let mut edge = HasOne::<i32>::default();
+    edge.loaded(1);
+    edge.not_requested();
+
+    assert_eq!(edge.try_unwrap().unwrap(), &1);
+}
+
+#[test]
+fn strict_checks_do_not_panic_for_skipped_edges() {
+    // Strict checks target "forgot to walk the query trail". A skipped edge was skipped
+    // correctly, so it returns the error as usual. Each test runs on its own thread and the
+    // flag is per-thread, so this doesn't leak into other tests.
+    set_strict_not_loaded_checks(true);
+
+    let mut edge = HasOne::<i32>::default();
+    edge.not_requested();
+
+    assert!(edge.try_unwrap().is_err());
+}
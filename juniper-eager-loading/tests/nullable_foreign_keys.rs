@@ -0,0 +1,184 @@
+//! Null foreign keys on `option_has_one` associations are skipped entirely: they never reach
+//! the loader's id list, and when every row's FK is NULL no query is issued at all. The edges
+//! still come back as legitimate `None`s.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, LoadFrom, OptionHasOne};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+use std::sync::Mutex;
+
+static LOADED_IDS: Mutex<Vec<Vec<i32>>> = Mutex::new(Vec::new());
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        country: Country
+    }
+
+    type Country {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: Option<i32>,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        LOADED_IDS.lock().unwrap().push(ids.to_vec());
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+
+        let mut users = User::from_db_models(&ctx.users);
+        User::eager_load_all_children_for_each(&mut users, &ctx.users, &ctx.db, trail)?;
+
+        Ok(users)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[option_has_one(default)]
+    country: OptionHasOne<Country>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Option<Country>> {
+        Ok(self.country.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.country.id)
+    }
+}
+
+fn run(users: Vec<models::User>) -> serde_json::Value {
+    let ctx = Context {
+        db: Db {
+            countries: vec![models::Country { id: 1 }],
+        },
+        users,
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id country { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+
+    serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap()
+}
+
+fn user(id: i32, country_id: Option<i32>) -> models::User {
+    models::User { id, country_id }
+}
+
+// One test so the recorded id lists aren't interleaved by concurrently running tests.
+#[test]
+fn null_foreign_keys_never_reach_the_loader() {
+    // 9 of 10 rows have a NULL FK: the loader sees one id, once.
+    let mut users = (1..=9).map(|id| user(id, None)).collect::<Vec<_>>();
+    users.push(user(10, Some(1)));
+    let json = run(users);
+
+    assert_json_eq!(json!({ "id": 1 }), &json["users"][9]["country"]);
+    assert_json_eq!(json!(null), &json["users"][0]["country"]);
+    assert_eq!(*LOADED_IDS.lock().unwrap(), [vec![1]]);
+
+    // All NULL: no query at all, every edge a legitimate `None`.
+    LOADED_IDS.lock().unwrap().clear();
+    let json = run((1..=10).map(|id| user(id, None)).collect());
+
+    assert!(json["users"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|user| user["country"].is_null()));
+    assert!(LOADED_IDS.lock().unwrap().is_empty());
+}
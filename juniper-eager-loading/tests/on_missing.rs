@@ -0,0 +1,309 @@
+//! The `on_missing` policy: permissive by default, but an association can opt into failing the
+//! whole load when a foreign key points at a child that doesn't exist.
+//!
+//! The `User.country` edge here implements `on_missing_children` the way the derive does for
+//! `on_missing = "error"`; the `User.cars` edge keeps the permissive default.
+
+use juniper_eager_loading::{
+    prelude::*, GenericQueryTrail, HasMany, HasOne, LoadFrom, LoadResult, MissingChildren,
+};
+use juniper_from_schema::Walked;
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+    cars: Vec<models::Car>,
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LoadFrom<models::User> for models::Car {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(users: &[models::User], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .cars
+            .iter()
+            .filter(|car| users.iter().any(|user| user.id == car.user_id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    country: HasOne<Country>,
+    cars: HasMany<Car>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    country: models::Country,
+}
+
+#[derive(Clone, Debug)]
+pub struct Car {
+    #[allow(dead_code)]
+    car: models::Car,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+            cars: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Car {
+    type Model = models::Car;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self { car: model.clone() }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Car {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCountryContext;
+
+impl EagerLoadChildrenOfType<Country, EverythingTrail, UserCountryContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+        Ok(LoadResult::Ids(
+            models.iter().map(|model| model.country_id).collect(),
+        ))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Country>, Self::Error> {
+        LoadFrom::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+        node.user.country_id == (child.0).country.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.country.assert_loaded_otherwise_failed();
+    }
+
+    fn on_missing_children(unmatched_models: &[&Self::Model]) -> Result<(), Self::Error> {
+        let ids = unmatched_models
+            .iter()
+            .map(|model| format!("{:?}", model.country_id))
+            .collect::<Vec<_>>();
+
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        Err(
+            juniper_eager_loading::Error::MissingChildren(MissingChildren::new("Country", ids))
+                .into(),
+        )
+    }
+}
+
+pub struct UserCarsContext;
+
+impl EagerLoadChildrenOfType<Car, EverythingTrail, UserCarsContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Car, ())>, Self::Error> {
+        let models = <models::Car as LoadFrom<models::User>>::load(models, db)?;
+        Ok(LoadResult::Models(
+            models.into_iter().map(|model| (model, ())).collect(),
+        ))
+    }
+
+    fn load_children(
+        _ids: &[Self::ChildId],
+        _db: &Self::Connection,
+    ) -> Result<Vec<models::Car>, Self::Error> {
+        unreachable!("`child_ids` always returns models")
+    }
+
+    fn is_child_of(node: &Self, child: &(Car, &())) -> bool {
+        node.user.id == (child.0).car.user_id
+    }
+
+    fn loaded_child(node: &mut Self, child: Car) {
+        node.cars.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.cars.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        EagerLoadChildrenOfType::<Car, _, UserCarsContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+fn eager_load_users(
+    user_models: &[models::User],
+    db: &Db,
+) -> Result<Vec<User>, Box<dyn std::error::Error>> {
+    let mut users = User::from_db_models(user_models);
+    User::eager_load_all_children_for_each(&mut users, user_models, db, &EverythingTrail)?;
+    Ok(users)
+}
+
+fn user(id: i32, country_id: i32) -> models::User {
+    models::User { id, country_id }
+}
+
+#[test]
+fn one_dangling_foreign_key_among_valid_ones_fails_the_load() {
+    let user_models = vec![user(1, 10), user(2, 999), user(3, 11)];
+    let db = Db {
+        countries: vec![models::Country { id: 10 }, models::Country { id: 11 }],
+        cars: vec![],
+    };
+
+    let err = eager_load_users(&user_models, &db).unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "No `Country` found for ids `999`, but the association requires them to exist",
+    );
+}
+
+#[test]
+fn all_valid_foreign_keys_load_fine() {
+    let user_models = vec![user(1, 10), user(2, 11)];
+    let db = Db {
+        countries: vec![models::Country { id: 10 }, models::Country { id: 11 }],
+        cars: vec![
+            models::Car { id: 1, user_id: 1 },
+            models::Car { id: 2, user_id: 2 },
+        ],
+    };
+
+    let users = eager_load_users(&user_models, &db).unwrap();
+
+    assert_eq!(users[0].country.try_unwrap().unwrap().country.id, 10);
+    assert_eq!(users[1].country.try_unwrap().unwrap().country.id, 11);
+}
+
+#[test]
+fn the_permissive_default_keeps_list_edges_empty() {
+    // User 2 has no cars, but the cars edge keeps the permissive default, so the load
+    // succeeds and the edge is just empty.
+    let user_models = vec![user(1, 10), user(2, 10)];
+    let db = Db {
+        countries: vec![models::Country { id: 10 }],
+        cars: vec![models::Car { id: 1, user_id: 1 }],
+    };
+
+    let users = eager_load_users(&user_models, &db).unwrap();
+
+    assert_eq!(users[0].cars.try_unwrap().unwrap().len(), 1);
+    assert_eq!(users[1].cars.try_unwrap().unwrap().len(), 0);
+}
@@ -0,0 +1,191 @@
+//! `option_has_one(on_missing = "null")`: for schemas where the pointed-at rows legitimately
+//! get hard-deleted, a dangling foreign key resolves to `None` instead of failing the query.
+//! The strict behavior stays the default, so the same dangling key errors without the
+//! attribute.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, LoadFrom, OptionHasOne};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        country: Country
+        homeland: Country
+    }
+
+    type Country {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: Option<i32>,
+        pub homeland_id: Option<i32>,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+        Ok(User::eager_load(&ctx.users, &ctx.db, trail)?)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    // The strict default: a dangling foreign key fails the load.
+    #[option_has_one(default)]
+    country: OptionHasOne<Country>,
+
+    // The graceful policy: a dangling foreign key resolves to `None`.
+    #[option_has_one(root_model_field = "country", on_missing = "null")]
+    homeland: OptionHasOne<Country>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Option<Country>> {
+        Ok(self.country.try_unwrap()?)
+    }
+
+    fn field_homeland(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Option<Country>> {
+        Ok(self.homeland.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.country.id)
+    }
+}
+
+fn run(
+    query: &str,
+) -> (
+    serde_json::Value,
+    Vec<juniper::ExecutionError<juniper::DefaultScalarValue>>,
+) {
+    let ctx = Context {
+        db: Db {
+            countries: vec![models::Country { id: 1 }],
+        },
+        // Both foreign keys dangle: country 999 was hard-deleted.
+        users: vec![models::User {
+            id: 1,
+            country_id: Some(999),
+            homeland_id: Some(999),
+        }],
+    };
+
+    let (result, errors) = juniper::execute(
+        query,
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+
+    (
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap(),
+        errors,
+    )
+}
+
+#[test]
+fn a_dangling_foreign_key_resolves_to_null_with_the_attribute() {
+    let (json, errors) = run("{ users { id homeland { id } } }");
+
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+    assert_json_eq!(json!(null), &json["users"][0]["homeland"]);
+}
+
+#[test]
+fn the_same_dangling_foreign_key_errors_without_it() {
+    let (_, errors) = run("{ users { id country { id } } }");
+
+    assert!(!errors.is_empty(), "the strict default should have errored");
+}
@@ -0,0 +1,217 @@
+//! The `order_by_method` attribute orders children within each parent's list edge. The loader
+//! here deliberately returns cars scrambled; the derived `order_children` sorts them by the
+//! key the model method returns — descending `created_at`, via `Reverse` — before they're
+//! attached to their users.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasMany};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        cars: [Car!]! @juniper(ownership: "owned")
+    }
+
+    type Car {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    cars: Vec<models::Car>,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+        pub created_at: i64,
+    }
+
+    impl Car {
+        // The sort key for `order_by_method`: newest car first.
+        pub fn created_at(&self) -> std::cmp::Reverse<i64> {
+            std::cmp::Reverse(self.created_at)
+        }
+    }
+
+    // Required by the `EagerLoadChildrenOfType` impl, but the has-many flow never calls it:
+    // children are loaded from the parent models below.
+    impl juniper_eager_loading::LoadFrom<i32> for Car {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            Ok(db
+                .cars
+                .iter()
+                .filter(|car| ids.contains(&car.id))
+                .cloned()
+                .collect())
+        }
+    }
+
+    impl juniper_eager_loading::LoadFrom<User> for Car {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(users: &[User], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            let user_ids = users.iter().map(|user| user.id).collect::<Vec<_>>();
+            Ok(db
+                .cars
+                .iter()
+                .filter(|car| user_ids.contains(&car.user_id))
+                .cloned()
+                .collect())
+        }
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+
+        let mut users = User::from_db_models(&ctx.users);
+        User::eager_load_all_children_for_each(&mut users, &ctx.users, &ctx.db, trail)?;
+
+        Ok(users)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_many(root_model_field = "car", order_by_method = "created_at")]
+    cars: HasMany<Car>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_cars(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Car, Walked>,
+    ) -> FieldResult<Vec<Car>> {
+        Ok(self.cars.try_unwrap()?.clone())
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Car {
+    car: models::Car,
+}
+
+impl CarFields for Car {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.car.id)
+    }
+}
+
+#[test]
+fn children_come_back_in_the_chosen_order() {
+    let users = vec![models::User { id: 1 }, models::User { id: 2 }];
+    // Stored neither in id order nor in `created_at` order, and interleaved between the two
+    // users, so any order surviving to the response is the sort's doing.
+    let cars = vec![
+        models::Car {
+            id: 10,
+            user_id: 1,
+            created_at: 50,
+        },
+        models::Car {
+            id: 20,
+            user_id: 2,
+            created_at: 10,
+        },
+        models::Car {
+            id: 11,
+            user_id: 1,
+            created_at: 200,
+        },
+        models::Car {
+            id: 21,
+            user_id: 2,
+            created_at: 30,
+        },
+        models::Car {
+            id: 12,
+            user_id: 1,
+            created_at: 100,
+        },
+    ];
+    let ctx = Context {
+        db: Db { cars },
+        users,
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id cars { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_eq!(
+        json!({
+            "users": [
+                { "id": 1, "cars": [{ "id": 11 }, { "id": 12 }, { "id": 10 }] },
+                { "id": 2, "cars": [{ "id": 21 }, { "id": 20 }] },
+            ],
+        }),
+        json,
+    );
+}
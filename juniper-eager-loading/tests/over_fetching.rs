@@ -0,0 +1,270 @@
+//! Loaders are allowed to over-fetch: a scoped query can return rows for ids that weren't asked
+//! about. Those orphan rows must not be turned into nodes or have their own children eager
+//! loaded, since no parent will ever reference them.
+
+use juniper_eager_loading::{prelude::*, unique, GenericQueryTrail, HasOne, LoadFrom, LoadResult};
+use juniper_from_schema::Walked;
+use std::cell::RefCell;
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+        pub continent_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Continent {
+        pub id: i32,
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+    continents: Vec<models::Continent>,
+    // The country ids the continent loader was asked about, i.e. which countries made it into
+    // the nested eager loading pass.
+    continent_loads_for_countries: RefCell<Vec<i32>>,
+}
+
+// Deliberately over-fetches: returns every country regardless of the requested ids.
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(_ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db.countries.clone())
+    }
+}
+
+impl LoadFrom<i32> for models::Continent {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .continents
+            .iter()
+            .filter(|continent| ids.contains(&continent.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    country: HasOne<Country>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    country: models::Country,
+    continent: HasOne<Continent>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Continent {
+    continent: models::Continent,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+            continent: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Continent {
+    type Model = models::Continent;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            continent: model.clone(),
+        }
+    }
+}
+
+pub struct UserCountryContext;
+
+impl EagerLoadChildrenOfType<Country, EverythingTrail, UserCountryContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+        let ids = models
+            .iter()
+            .map(|model| model.country_id)
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Country>, Self::Error> {
+        <models::Country as LoadFrom<i32>>::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+        node.user.country_id == (child.0).country.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.country.assert_loaded_otherwise_failed();
+    }
+}
+
+pub struct CountryContinentContext;
+
+impl EagerLoadChildrenOfType<Continent, EverythingTrail, CountryContinentContext, ()> for Country {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Continent, ())>, Self::Error> {
+        db.continent_loads_for_countries
+            .borrow_mut()
+            .extend(models.iter().map(|model| model.id));
+
+        let ids = models
+            .iter()
+            .map(|model| model.continent_id)
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Continent>, Self::Error> {
+        <models::Continent as LoadFrom<i32>>::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Continent, &())) -> bool {
+        node.country.continent_id == (child.0).continent.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Continent) {
+        node.continent.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.continent.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Continent, _, CountryContinentContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Continent {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn orphan_rows_never_reach_nested_loaders() {
+    let db = Db {
+        countries: vec![
+            models::Country {
+                id: 1,
+                continent_id: 1,
+            },
+            // No user points at this country; the over-fetching loader returns it anyway.
+            models::Country {
+                id: 2,
+                continent_id: 2,
+            },
+        ],
+        continents: vec![models::Continent { id: 1 }, models::Continent { id: 2 }],
+        continent_loads_for_countries: RefCell::new(Vec::new()),
+    };
+    let user_models = vec![models::User {
+        id: 1,
+        country_id: 1,
+    }];
+
+    let mut users = User::from_db_models(&user_models);
+    User::eager_load_all_children_for_each(&mut users, &user_models, &db, &EverythingTrail)
+        .unwrap();
+
+    // The referenced country is fully loaded, chain included.
+    let country = users[0].country.try_unwrap().unwrap();
+    assert_eq!(country.country.id, 1);
+    assert_eq!(country.continent.try_unwrap().unwrap().continent.id, 1);
+
+    // The orphan country never made it into the nested eager loading pass.
+    assert_eq!(*db.continent_loads_for_countries.borrow(), [1]);
+}
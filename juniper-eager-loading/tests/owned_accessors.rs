@@ -0,0 +1,107 @@
+//! `into_inner` and `take` move loaded values out of edges instead of borrowing them, so
+//! resolvers that need ownership — handing a model into another node, returning it from a
+//! mutation — don't have to clone. The error cases mirror `try_unwrap` exactly.
+
+use juniper_eager_loading::{
+    AssociationType, Error, HasMany, HasManyThrough, HasOne, OptionHasOne,
+};
+
+#[test]
+fn into_inner_moves_the_loaded_value_out() {
+    let mut edge = HasOne::default();
+    edge.loaded("country".to_owned());
+
+    let value: String = edge.into_inner().unwrap();
+    assert_eq!(value, "country");
+}
+
+#[test]
+fn into_inner_mirrors_try_unwrap_errors() {
+    let not_loaded = HasOne::<i32>::default();
+    assert!(matches!(
+        not_loaded.into_inner(),
+        Err(Error::NotLoaded(AssociationType::HasOne, None)),
+    ));
+
+    let mut failed = HasOne::<i32>::default();
+    failed.assert_loaded_otherwise_failed();
+    assert!(matches!(
+        failed.into_inner(),
+        Err(Error::LoadFailed(AssociationType::HasOne, None)),
+    ));
+}
+
+#[test]
+fn take_leaves_a_not_loaded_edge_behind() {
+    let mut edge = HasOne::default();
+    edge.loaded(1);
+
+    assert_eq!(edge.take().unwrap(), 1);
+    assert!(edge.is_not_loaded());
+    assert!(matches!(
+        edge.try_unwrap(),
+        Err(Error::NotLoaded(AssociationType::HasOne, None)),
+    ));
+}
+
+#[test]
+fn taking_a_failed_edge_errors_and_keeps_the_failure() {
+    let mut edge = HasOne::<i32>::default();
+    edge.assert_loaded_otherwise_failed();
+
+    assert!(matches!(
+        edge.take(),
+        Err(Error::LoadFailed(AssociationType::HasOne, None)),
+    ));
+    // The failure isn't erased by the attempted take.
+    assert!(edge.is_failed());
+}
+
+#[test]
+fn option_has_one_owned_accessors() {
+    let mut edge = OptionHasOne::default();
+    edge.loaded("city".to_owned());
+    assert_eq!(edge.into_inner().unwrap(), Some("city".to_owned()));
+
+    let mut edge = OptionHasOne::default();
+    edge.loaded(1);
+    assert_eq!(edge.take().unwrap(), Some(1));
+    assert!(edge.is_not_loaded());
+
+    let mut failed = OptionHasOne::<i32>::default();
+    failed.assert_loaded_otherwise_failed();
+    assert!(failed.take().is_err());
+    assert!(failed.is_failed());
+}
+
+#[test]
+fn has_many_owned_accessors() {
+    let mut edge = HasMany::default();
+    edge.loaded(1);
+    edge.loaded(2);
+    assert_eq!(edge.into_inner().unwrap(), vec![1, 2]);
+
+    let mut edge = HasMany::default();
+    edge.loaded(1);
+    assert_eq!(edge.take().unwrap(), vec![1]);
+    assert!(edge.is_not_loaded());
+
+    let mut failed = HasMany::<i32>::default();
+    failed.load_failed();
+    assert!(matches!(
+        failed.into_inner(),
+        Err(Error::LoadFailed(AssociationType::HasMany, None)),
+    ));
+}
+
+#[test]
+fn has_many_through_owned_accessors() {
+    let mut edge = HasManyThrough::default();
+    edge.loaded(1);
+    assert_eq!(edge.into_inner().unwrap(), vec![1]);
+
+    let mut failed = HasManyThrough::<i32>::default();
+    failed.load_failed();
+    assert!(failed.take().is_err());
+    assert!(failed.is_failed());
+}
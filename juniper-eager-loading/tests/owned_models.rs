@@ -0,0 +1,180 @@
+//! The owned construction pathway: `new_from_owned_model` and `from_db_model_vec` move models
+//! into nodes instead of cloning them, and `eager_load_children` moves freshly loaded child
+//! models straight into the child nodes when the child has no nested selections. For models
+//! with large columns that's where most of the eager loading time went.
+
+use juniper_eager_loading::{prelude::*, unique, GenericQueryTrail, HasOne, LoadResult};
+use juniper_from_schema::Walked;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static COUNTRY_MODEL_CLONES: AtomicUsize = AtomicUsize::new(0);
+
+mod models {
+    use super::COUNTRY_MODEL_CLONES;
+    use std::sync::atomic::Ordering;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    // A counting `Clone` standing in for a model with large text/JSON columns.
+    #[derive(Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    impl Clone for Country {
+        fn clone(&self) -> Self {
+            COUNTRY_MODEL_CLONES.fetch_add(1, Ordering::SeqCst);
+            Country { id: self.id }
+        }
+    }
+}
+
+pub struct Db;
+
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    country: HasOne<Country>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    country: models::Country,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+        }
+    }
+
+    // Move the model in, like the derive generates.
+    fn new_from_owned_model(model: Self::Model) -> Self {
+        Self { country: model }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    // `Country` has no associations, so the flow can move loaded models into the nodes
+    // instead of keeping them around for a recursion that wouldn't load anything.
+    fn has_nested_selections(_trail: &EverythingTrail) -> bool {
+        false
+    }
+}
+
+pub struct UserCountryContext;
+
+impl EagerLoadChildrenOfType<Country, EverythingTrail, UserCountryContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+        let ids = models
+            .iter()
+            .map(|model| model.country_id)
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    // Freshly built models, like rows coming out of a query — nothing else references them.
+    fn load_children(
+        ids: &[Self::ChildId],
+        _db: &Self::Connection,
+    ) -> Result<Vec<models::Country>, Self::Error> {
+        Ok(ids.iter().map(|&id| models::Country { id }).collect())
+    }
+
+    fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+        node.user.country_id == (child.0).country.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.country.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+// One test so the clone counter isn't shared between concurrently running tests.
+#[test]
+fn owned_pathways_move_models_instead_of_cloning() {
+    // `from_db_model_vec` goes through `new_from_owned_model`: no clones.
+    let countries =
+        Country::from_db_model_vec(vec![models::Country { id: 1 }, models::Country { id: 2 }]);
+    assert_eq!(countries.len(), 2);
+    assert_eq!(COUNTRY_MODEL_CLONES.load(Ordering::SeqCst), 0);
+
+    // Freshly loaded leaf children are moved into their nodes: still no clones.
+    let user_models = [
+        models::User {
+            id: 1,
+            country_id: 1,
+        },
+        models::User {
+            id: 2,
+            country_id: 2,
+        },
+    ];
+    let mut users = User::from_db_models(&user_models);
+    User::eager_load_all_children_for_each(&mut users, &user_models, &Db, &EverythingTrail)
+        .unwrap();
+
+    assert_eq!(users[0].country.try_unwrap().unwrap().country.id, 1);
+    assert_eq!(users[1].country.try_unwrap().unwrap().country.id, 2);
+    assert_eq!(COUNTRY_MODEL_CLONES.load(Ordering::SeqCst), 0);
+}
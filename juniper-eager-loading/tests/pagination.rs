@@ -0,0 +1,239 @@
+//! The `paginate_with` attribute applies a per-parent window when children are attached. The
+//! query trail doesn't expose GraphQL field arguments, so the named function gets the window
+//! from wherever the resolver stashed it — a thread local here — and each parent independently
+//! keeps at most `first` children after skipping `offset`.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasMany, Pagination};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+use std::cell::Cell;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        cars: [Car!]! @juniper(ownership: "owned")
+    }
+
+    type Car {
+        id: Int!
+    }
+}
+
+thread_local! {
+    static CARS_PAGINATION: Cell<Option<Pagination>> = const { Cell::new(None) };
+}
+
+fn cars_pagination(_trail: &QueryTrail<'_, Car, Walked>) -> Option<Pagination> {
+    CARS_PAGINATION.with(Cell::get)
+}
+
+pub struct Db {
+    cars: Vec<models::Car>,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+
+    // Required by the `EagerLoadChildrenOfType` impl, but the has-many flow never calls it:
+    // children are loaded from the parent models below.
+    impl juniper_eager_loading::LoadFrom<i32> for Car {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            Ok(db
+                .cars
+                .iter()
+                .filter(|car| ids.contains(&car.id))
+                .cloned()
+                .collect())
+        }
+    }
+
+    impl juniper_eager_loading::LoadFrom<User> for Car {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(users: &[User], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            let user_ids = users.iter().map(|user| user.id).collect::<Vec<_>>();
+            Ok(db
+                .cars
+                .iter()
+                .filter(|car| user_ids.contains(&car.user_id))
+                .cloned()
+                .collect())
+        }
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+
+        let mut users = User::from_db_models(&ctx.users);
+        User::eager_load_all_children_for_each(&mut users, &ctx.users, &ctx.db, trail)?;
+
+        Ok(users)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_many(root_model_field = "car", paginate_with = "cars_pagination")]
+    cars: HasMany<Car>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_cars(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Car, Walked>,
+    ) -> FieldResult<Vec<Car>> {
+        Ok(self.cars.try_unwrap()?.clone())
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Car {
+    car: models::Car,
+}
+
+impl CarFields for Car {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.car.id)
+    }
+}
+
+// User 1 has three cars, user 2 has one, user 3 has none.
+fn context() -> Context {
+    let users = (1..=3).map(|id| models::User { id }).collect();
+    let cars = vec![
+        models::Car { id: 10, user_id: 1 },
+        models::Car { id: 11, user_id: 1 },
+        models::Car { id: 12, user_id: 1 },
+        models::Car { id: 20, user_id: 2 },
+    ];
+    Context {
+        db: Db { cars },
+        users,
+    }
+}
+
+fn run_with(pagination: Option<Pagination>) -> serde_json::Value {
+    CARS_PAGINATION.with(|cell| cell.set(pagination));
+
+    let (result, errors) = juniper::execute(
+        "{ users { id cars { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &context(),
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+
+    serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap()
+}
+
+#[test]
+fn first_truncates_per_parent() {
+    let json = run_with(Some(Pagination {
+        first: Some(2),
+        offset: None,
+    }));
+
+    // Each parent is truncated independently: parents with fewer children than `first` keep
+    // what they have.
+    assert_json_eq!(
+        json!({
+            "users": [
+                { "id": 1, "cars": [{ "id": 10 }, { "id": 11 }] },
+                { "id": 2, "cars": [{ "id": 20 }] },
+                { "id": 3, "cars": [] },
+            ],
+        }),
+        json,
+    );
+}
+
+#[test]
+fn offset_skips_per_parent() {
+    let json = run_with(Some(Pagination {
+        first: Some(1),
+        offset: Some(1),
+    }));
+
+    assert_json_eq!(
+        json!({
+            "users": [
+                { "id": 1, "cars": [{ "id": 11 }] },
+                { "id": 2, "cars": [] },
+                { "id": 3, "cars": [] },
+            ],
+        }),
+        json,
+    );
+}
+
+#[test]
+fn no_pagination_keeps_every_child() {
+    let json = run_with(None);
+
+    assert_json_eq!(
+        json!([{ "id": 10 }, { "id": 11 }, { "id": 12 }]),
+        &json["users"][0]["cars"],
+    );
+}
@@ -0,0 +1,414 @@
+//! A polymorphic association: `Attachment.subject` is a GraphQL union of `Post` and `Comment`,
+//! backed by `subject_type` + `subject_id` columns. The derive can't express an edge to a
+//! union, but the manual `EagerLoadChildrenOfType` extension point can: the child id is the
+//! `(type, id)` pair, `load_children` groups the ids by the type column and issues one query
+//! per concrete type, and `is_child_of` matches the variant back to its parent. One posts
+//! query and one comments query for any number of attachments.
+//!
+//! The union's query trail exposes the member types' fields jointly (juniper-from-schema
+//! doesn't have per-fragment trails), so nested eager loading under a union descends for all
+//! variants the query could produce.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{
+    prelude::*, EagerLoading, GraphqlNodeForModel, HasOne, LoadFrom, LoadResult,
+};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+use std::cell::RefCell;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      attachments: [Attachment!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type Attachment {
+        id: Int!
+        subject: Subject!
+    }
+
+    union Subject = Post | Comment
+
+    type Post {
+        id: Int!
+        title: String! @juniper(ownership: "owned")
+    }
+
+    type Comment {
+        id: Int!
+        body: String! @juniper(ownership: "owned")
+    }
+}
+
+pub struct Db {
+    posts: Vec<models::Post>,
+    comments: Vec<models::Comment>,
+    post_loads: RefCell<Vec<Vec<i32>>>,
+    comment_loads: RefCell<Vec<Vec<i32>>>,
+}
+
+pub mod models {
+    #[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+    pub enum SubjectType {
+        Post,
+        Comment,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Attachment {
+        pub id: i32,
+        pub subject_type: SubjectType,
+        pub subject_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Post {
+        pub id: i32,
+        pub title: String,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Comment {
+        pub id: i32,
+        pub body: String,
+    }
+
+    /// The model-side union: what one `(subject_type, subject_id)` pair resolved to.
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub enum Subject {
+        Post(Post),
+        Comment(Comment),
+    }
+}
+
+impl LoadFrom<i32> for models::Post {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.post_loads.borrow_mut().push(ids.to_vec());
+        Ok(db
+            .posts
+            .iter()
+            .filter(|post| ids.contains(&post.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LoadFrom<i32> for models::Comment {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.comment_loads.borrow_mut().push(ids.to_vec());
+        Ok(db
+            .comments
+            .iter()
+            .filter(|comment| ids.contains(&comment.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    attachments: Vec<models::Attachment>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_attachments<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, Attachment, Walked>,
+    ) -> FieldResult<Vec<Attachment>> {
+        let ctx = executor.context();
+        Ok(Attachment::eager_load(&ctx.attachments, &ctx.db, trail)?)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Attachment {
+    attachment: models::Attachment,
+
+    // An edge to a union needs a hand-written implementation, below.
+    #[has_one(skip)]
+    subject: HasOne<Subject>,
+}
+
+impl AttachmentFields for Attachment {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.attachment.id)
+    }
+
+    fn field_subject(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Subject, Walked>,
+    ) -> FieldResult<&Subject> {
+        Ok(self.subject.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Post {
+    post: models::Post,
+}
+
+impl PostFields for Post {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.post.id)
+    }
+
+    fn field_title(&self, _executor: &Executor<'_, Context>) -> FieldResult<String> {
+        Ok(self.post.title.clone())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Comment {
+    comment: models::Comment,
+}
+
+impl CommentFields for Comment {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.comment.id)
+    }
+
+    fn field_body(&self, _executor: &Executor<'_, Context>) -> FieldResult<String> {
+        Ok(self.comment.body.clone())
+    }
+}
+
+// `graphql_schema!` generates the `Subject` enum without any derives, so the impls the eager
+// loading machinery needs are written out.
+impl Clone for Subject {
+    fn clone(&self) -> Self {
+        match self {
+            Subject::Post(post) => Subject::Post(post.clone()),
+            Subject::Comment(comment) => Subject::Comment(comment.clone()),
+        }
+    }
+}
+
+impl std::fmt::Debug for Subject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Subject::Post(post) => f.debug_tuple("Post").field(post).finish(),
+            Subject::Comment(comment) => f.debug_tuple("Comment").field(comment).finish(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Subject {
+    type Model = models::Subject;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        match model {
+            models::Subject::Post(post) => Subject::Post(Post { post: post.clone() }),
+            models::Subject::Comment(comment) => Subject::Comment(Comment {
+                comment: comment.clone(),
+            }),
+        }
+    }
+}
+
+impl<'a> EagerLoadAllChildren<QueryTrail<'a, Subject, Walked>> for Subject {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &QueryTrail<'a, Subject, Walked>,
+    ) -> Result<(), Self::Error> {
+        // Neither variant has associations of its own. With nested associations this would
+        // partition the nodes and models by variant and recurse per concrete type.
+        Ok(())
+    }
+
+    fn has_nested_selections(_trail: &QueryTrail<'a, Subject, Walked>) -> bool {
+        false
+    }
+}
+
+#[allow(missing_docs, dead_code)]
+struct EagerLoadingContextAttachmentForSubject;
+
+impl<'a>
+    EagerLoadChildrenOfType<
+        Subject,
+        QueryTrail<'a, Subject, juniper_from_schema::Walked>,
+        EagerLoadingContextAttachmentForSubject,
+        (),
+    > for Attachment
+{
+    type ChildId = (models::SubjectType, i32);
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Subject, ())>, Self::Error> {
+        let ids = models
+            .iter()
+            .map(|model| (model.subject_type, model.subject_id))
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(ids))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Subject>, Self::Error> {
+        // Group by the type discriminant and load each concrete type in one batch.
+        let mut post_ids = Vec::new();
+        let mut comment_ids = Vec::new();
+        for (subject_type, id) in ids {
+            match subject_type {
+                models::SubjectType::Post => post_ids.push(*id),
+                models::SubjectType::Comment => comment_ids.push(*id),
+            }
+        }
+
+        let mut subjects = Vec::new();
+        if !post_ids.is_empty() {
+            let posts = <models::Post as LoadFrom<i32>>::load(&post_ids, db)?;
+            subjects.extend(posts.into_iter().map(models::Subject::Post));
+        }
+        if !comment_ids.is_empty() {
+            let comments = <models::Comment as LoadFrom<i32>>::load(&comment_ids, db)?;
+            subjects.extend(comments.into_iter().map(models::Subject::Comment));
+        }
+        Ok(subjects)
+    }
+
+    fn is_child_of(node: &Self, child: &(Subject, &())) -> bool {
+        match &child.0 {
+            Subject::Post(post) => {
+                node.attachment.subject_type == models::SubjectType::Post
+                    && node.attachment.subject_id == post.post.id
+            }
+            Subject::Comment(comment) => {
+                node.attachment.subject_type == models::SubjectType::Comment
+                    && node.attachment.subject_id == comment.comment.id
+            }
+        }
+    }
+
+    fn loaded_child(node: &mut Self, child: Subject) {
+        node.subject.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.subject.assert_loaded_otherwise_failed();
+    }
+}
+
+#[test]
+fn a_union_edge_loads_each_concrete_type_in_one_query() {
+    let ctx = Context {
+        db: Db {
+            posts: vec![
+                models::Post {
+                    id: 10,
+                    title: "first".to_string(),
+                },
+                models::Post {
+                    id: 11,
+                    title: "second".to_string(),
+                },
+            ],
+            comments: vec![models::Comment {
+                id: 20,
+                body: "nice".to_string(),
+            }],
+            post_loads: RefCell::new(Vec::new()),
+            comment_loads: RefCell::new(Vec::new()),
+        },
+        attachments: vec![
+            models::Attachment {
+                id: 1,
+                subject_type: models::SubjectType::Post,
+                subject_id: 10,
+            },
+            models::Attachment {
+                id: 2,
+                subject_type: models::SubjectType::Comment,
+                subject_id: 20,
+            },
+            models::Attachment {
+                id: 3,
+                subject_type: models::SubjectType::Post,
+                subject_id: 11,
+            },
+        ],
+    };
+
+    let (result, errors) = juniper::execute(
+        r#"{
+            attachments {
+                id
+                subject {
+                    __typename
+                    ... on Post { id title }
+                    ... on Comment { id body }
+                }
+            }
+        }"#,
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_eq!(
+        json!({
+            "attachments": [
+                {
+                    "id": 1,
+                    "subject": { "__typename": "Post", "id": 10, "title": "first" },
+                },
+                {
+                    "id": 2,
+                    "subject": { "__typename": "Comment", "id": 20, "body": "nice" },
+                },
+                {
+                    "id": 3,
+                    "subject": { "__typename": "Post", "id": 11, "title": "second" },
+                },
+            ],
+        }),
+        json,
+    );
+
+    assert_eq!(*ctx.db.post_loads.borrow(), vec![vec![10, 11]]);
+    assert_eq!(*ctx.db.comment_loads.borrow(), vec![vec![20]]);
+}
@@ -0,0 +1,102 @@
+use juniper_eager_loading::{load_from_pool, AsyncLoadFrom, ConnectionPool};
+use std::cell::Cell;
+use std::future::{ready, Ready};
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct CheckoutError(&'static str);
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct AppError(String);
+
+impl From<CheckoutError> for AppError {
+    fn from(error: CheckoutError) -> Self {
+        AppError(format!("checkout failed: {}", error.0))
+    }
+}
+
+#[derive(Clone)]
+pub struct FakeConnection {
+    users: Vec<models::User>,
+}
+
+pub struct FakePool {
+    connection: FakeConnection,
+    checkouts: Cell<usize>,
+    fail_checkout: bool,
+}
+
+impl FakePool {
+    fn new(users: Vec<models::User>) -> Self {
+        FakePool {
+            connection: FakeConnection { users },
+            checkouts: Cell::new(0),
+            fail_checkout: false,
+        }
+    }
+}
+
+impl ConnectionPool for FakePool {
+    type Connection = FakeConnection;
+    type Error = CheckoutError;
+    type Future = Ready<Result<Self::Connection, Self::Error>>;
+
+    fn checkout(&self) -> Self::Future {
+        self.checkouts.set(self.checkouts.get() + 1);
+        ready(if self.fail_checkout {
+            Err(CheckoutError("pool exhausted"))
+        } else {
+            Ok(self.connection.clone())
+        })
+    }
+}
+
+impl AsyncLoadFrom<i32> for models::User {
+    type Error = AppError;
+    type Pool = FakePool;
+    type Future = Ready<Result<Vec<Self>, Self::Error>>;
+
+    fn load(ids: &[i32], connection: FakeConnection) -> Self::Future {
+        ready(Ok(connection
+            .users
+            .iter()
+            .filter(|user| ids.contains(&user.id))
+            .cloned()
+            .collect()))
+    }
+}
+
+#[test]
+fn checks_out_one_connection_per_loader_call() {
+    let pool = FakePool::new(vec![models::User { id: 1 }, models::User { id: 2 }]);
+
+    let users: Vec<models::User> =
+        futures::executor::block_on(load_from_pool(&[1, 2], &pool)).unwrap();
+    assert_eq!(users, vec![models::User { id: 1 }, models::User { id: 2 }]);
+    assert_eq!(pool.checkouts.get(), 1);
+
+    let users: Vec<models::User> =
+        futures::executor::block_on(load_from_pool(&[1], &pool)).unwrap();
+    assert_eq!(users, vec![models::User { id: 1 }]);
+    assert_eq!(pool.checkouts.get(), 2);
+}
+
+#[test]
+fn checkout_errors_are_mapped_into_the_loader_error() {
+    let mut pool = FakePool::new(vec![models::User { id: 1 }]);
+    pool.fail_checkout = true;
+
+    let error =
+        futures::executor::block_on(load_from_pool::<models::User, _>(&[1], &pool)).unwrap_err();
+
+    assert_eq!(
+        error,
+        AppError("checkout failed: pool exhausted".to_string())
+    );
+}
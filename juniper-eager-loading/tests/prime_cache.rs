@@ -0,0 +1,125 @@
+//! The derive generates a `prime_cache` method on each node type so resolvers can pre-populate
+//! the eager loading cache with rows they already fetched, without knowing how entries are
+//! keyed internally.
+
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, Cache, EagerLoading, HasOne};
+use juniper_from_schema::graphql_schema;
+
+graphql_schema! {
+    schema { query: Query }
+
+    type Query { noop: Boolean! @juniper(ownership: "owned") }
+
+    type User {
+        id: Int!
+        country: Country!
+    }
+
+    type Country {
+        id: Int!
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<bool> {
+        unimplemented!()
+    }
+}
+
+pub struct Db;
+
+pub struct Context;
+
+impl juniper::Context for Context {}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    impl juniper_eager_loading::LoadFrom<i32> for Country {
+        type Error = Box<dyn std::error::Error>;
+        type Connection = super::Db;
+
+        fn load(_ids: &[i32], _db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+            unimplemented!()
+        }
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        unimplemented!()
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        unimplemented!()
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        unimplemented!()
+    }
+}
+
+#[test]
+fn primed_models_are_found_under_their_ids() {
+    let countries = vec![models::Country { id: 10 }, models::Country { id: 20 }];
+
+    let mut cache = Cache::<i32>::new();
+    Country::prime_cache(&countries, &mut cache);
+
+    assert_eq!(
+        cache.get::<models::Country>(10),
+        Some(models::Country { id: 10 })
+    );
+    assert_eq!(
+        cache.get::<models::Country>(20),
+        Some(models::Country { id: 20 })
+    );
+    assert_eq!(cache.get::<models::Country>(30), None);
+
+    // Each node type primes under its own model type.
+    let users = vec![models::User {
+        id: 10,
+        country_id: 10,
+    }];
+    User::prime_cache(&users, &mut cache);
+    assert_eq!(
+        cache.get::<models::User>(10),
+        Some(models::User {
+            id: 10,
+            country_id: 10,
+        })
+    );
+}
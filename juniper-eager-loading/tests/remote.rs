@@ -0,0 +1,378 @@
+use juniper_eager_loading::{
+    load_remote, prelude::*, unique, DbAndRemote, GenericQueryTrail, HasOne, LoadFrom, LoadResult,
+    RemoteCallHooks, RemoteLoadFrom, RetryLimit,
+};
+use juniper_from_schema::Walked;
+use std::cell::{Cell, RefCell};
+use std::future::{ready, Ready};
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Profile {
+        pub user_id: i32,
+        pub bio: &'static str,
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+}
+
+pub struct ProfileServiceClient {
+    profiles: Vec<models::Profile>,
+    calls: RefCell<Vec<Vec<i32>>>,
+}
+
+type Connection = DbAndRemote<Db, ProfileServiceClient>;
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Connection;
+
+    fn load(ids: &[i32], connection: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(connection
+            .db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl RemoteLoadFrom<i32> for models::Profile {
+    type Error = Box<dyn std::error::Error>;
+    type Client = ProfileServiceClient;
+    type Future = Ready<Result<Vec<Self>, Self::Error>>;
+
+    fn load(ids: &[i32], client: &Self::Client) -> Self::Future {
+        client.calls.borrow_mut().push(ids.to_vec());
+        ready(Ok(client
+            .profiles
+            .iter()
+            .filter(|profile| ids.contains(&profile.user_id))
+            .cloned()
+            .collect()))
+    }
+}
+
+// A stand-in for a walked `QueryTrail` that selects every association.
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    country: HasOne<Country>,
+    profile: HasOne<Profile>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    country: models::Country,
+}
+
+#[derive(Clone, Debug)]
+pub struct Profile {
+    profile: models::Profile,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Connection;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+            profile: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Connection;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Profile {
+    type Model = models::Profile;
+    type Id = i32;
+    type Connection = Connection;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            profile: model.clone(),
+        }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Profile {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+struct UserCountryContext;
+struct UserProfileContext;
+
+impl EagerLoadChildrenOfType<Country, EverythingTrail, UserCountryContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+        let ids = models
+            .iter()
+            .map(|model| model.country_id)
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Country>, Self::Error> {
+        <models::Country as LoadFrom<i32>>::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+        node.user.country_id == (child.0).country.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.country.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadChildrenOfType<Profile, EverythingTrail, UserProfileContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Profile, ())>, Self::Error> {
+        let ids = models.iter().map(|model| model.id).collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Profile>, Self::Error> {
+        futures::executor::block_on(load_remote(ids, &db.remote, &RetryLimit::new(1)))
+    }
+
+    fn is_child_of(node: &Self, child: &(Profile, &())) -> bool {
+        node.user.id == (child.0).profile.user_id
+    }
+
+    fn loaded_child(node: &mut Self, child: Profile) {
+        node.profile.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.profile.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        EagerLoadChildrenOfType::<Profile, _, UserProfileContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+fn connection() -> Connection {
+    DbAndRemote::new(
+        Db {
+            countries: vec![models::Country { id: 10 }],
+        },
+        ProfileServiceClient {
+            profiles: vec![
+                models::Profile {
+                    user_id: 1,
+                    bio: "one",
+                },
+                models::Profile {
+                    user_id: 2,
+                    bio: "two",
+                },
+            ],
+            calls: RefCell::new(Vec::new()),
+        },
+    )
+}
+
+#[test]
+fn mixes_db_backed_and_remote_backed_associations() {
+    let connection = connection();
+    let user_models = vec![
+        models::User {
+            id: 1,
+            country_id: 10,
+        },
+        models::User {
+            id: 2,
+            country_id: 10,
+        },
+    ];
+
+    let mut users = User::from_db_models(&user_models);
+    User::eager_load_all_children_for_each(&mut users, &user_models, &connection, &EverythingTrail)
+        .unwrap();
+
+    assert_eq!(users[0].country.try_unwrap().unwrap().country.id, 10);
+    assert_eq!(users[0].profile.try_unwrap().unwrap().profile.bio, "one");
+    assert_eq!(users[1].profile.try_unwrap().unwrap().profile.bio, "two");
+
+    // One batched remote call for the distinct id set.
+    let calls = connection.remote.calls.borrow();
+    assert_eq!(calls.len(), 1);
+    let mut ids = calls[0].clone();
+    ids.sort_unstable();
+    assert_eq!(ids, vec![1, 2]);
+}
+
+struct FlakyClient {
+    attempts: Cell<usize>,
+    failures_before_success: usize,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct Widget {
+    id: i32,
+}
+
+impl RemoteLoadFrom<i32> for Widget {
+    type Error = String;
+    type Client = FlakyClient;
+    type Future = Ready<Result<Vec<Self>, Self::Error>>;
+
+    fn load(ids: &[i32], client: &Self::Client) -> Self::Future {
+        let attempt = client.attempts.get() + 1;
+        client.attempts.set(attempt);
+        ready(if attempt <= client.failures_before_success {
+            Err(format!("attempt {} timed out", attempt))
+        } else {
+            Ok(ids.iter().map(|&id| Widget { id }).collect())
+        })
+    }
+}
+
+#[test]
+fn retries_up_to_the_attempt_limit() {
+    let client = FlakyClient {
+        attempts: Cell::new(0),
+        failures_before_success: 2,
+    };
+
+    let widgets: Vec<Widget> =
+        futures::executor::block_on(load_remote(&[1], &client, &RetryLimit::new(3))).unwrap();
+
+    assert_eq!(widgets, vec![Widget { id: 1 }]);
+    assert_eq!(client.attempts.get(), 3);
+}
+
+#[test]
+fn gives_up_when_the_attempt_limit_is_reached() {
+    let client = FlakyClient {
+        attempts: Cell::new(0),
+        failures_before_success: 2,
+    };
+
+    let error = futures::executor::block_on(load_remote::<Widget, _, _>(
+        &[1],
+        &client,
+        &RetryLimit::new(2),
+    ))
+    .unwrap_err();
+
+    assert_eq!(error, "attempt 2 timed out");
+    assert_eq!(client.attempts.get(), 2);
+}
+
+struct RecordingHooks {
+    attempts_seen: RefCell<Vec<usize>>,
+}
+
+impl RemoteCallHooks<String> for RecordingHooks {
+    fn on_attempt(&self, attempt: usize) {
+        self.attempts_seen.borrow_mut().push(attempt);
+    }
+
+    fn retry_after_error(&self, _attempt: usize, error: &String) -> bool {
+        error.contains("timed out")
+    }
+}
+
+#[test]
+fn custom_hooks_see_each_attempt_and_decide_on_retries() {
+    let client = FlakyClient {
+        attempts: Cell::new(0),
+        failures_before_success: 1,
+    };
+    let hooks = RecordingHooks {
+        attempts_seen: RefCell::new(Vec::new()),
+    };
+
+    let widgets: Vec<Widget> =
+        futures::executor::block_on(load_remote(&[1, 2], &client, &hooks)).unwrap();
+
+    assert_eq!(widgets.len(), 2);
+    assert_eq!(*hooks.attempts_seen.borrow(), vec![1, 2]);
+}
@@ -0,0 +1,249 @@
+//! Two associations from one parent to the same child type. The derive generates a zero-sized
+//! context type per field, so `home_country` and `work_country` each get their own
+//! `EagerLoadChildrenOfType` impl with their own foreign key, while the loading pass still
+//! batches both edges' ids — `Country` is loaded once per query, not once per field.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasOne, LoadFrom};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+use std::cell::RefCell;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        homeCountry: Country!
+        workCountry: Country!
+    }
+
+    type Country {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+    country_loads: RefCell<Vec<Vec<i32>>>,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub home_country_id: i32,
+        pub work_country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.country_loads.borrow_mut().push(ids.to_vec());
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+        Ok(User::eager_load(&ctx.users, &ctx.db, trail)?)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(foreign_key_field = "home_country_id", root_model_field = "country")]
+    home_country: HasOne<Country>,
+
+    #[has_one(foreign_key_field = "work_country_id", root_model_field = "country")]
+    work_country: HasOne<Country>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_home_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        Ok(self.home_country.try_unwrap()?)
+    }
+
+    fn field_work_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        Ok(self.work_country.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.country.id)
+    }
+}
+
+#[test]
+fn both_edges_resolve_from_a_single_countries_query() {
+    let ctx = Context {
+        db: Db {
+            countries: vec![
+                models::Country { id: 10 },
+                models::Country { id: 20 },
+                models::Country { id: 30 },
+            ],
+            country_loads: RefCell::new(Vec::new()),
+        },
+        users: vec![
+            models::User {
+                id: 1,
+                home_country_id: 10,
+                work_country_id: 20,
+            },
+            models::User {
+                id: 2,
+                home_country_id: 30,
+                // Shared with user 1's home country; still just one row to fetch.
+                work_country_id: 10,
+            },
+        ],
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id homeCountry { id } workCountry { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_eq!(
+        json!({
+            "users": [
+                {
+                    "id": 1,
+                    "homeCountry": { "id": 10 },
+                    "workCountry": { "id": 20 },
+                },
+                {
+                    "id": 2,
+                    "homeCountry": { "id": 30 },
+                    "workCountry": { "id": 10 },
+                },
+            ],
+        }),
+        json,
+    );
+
+    let loads = ctx.db.country_loads.borrow();
+    assert_eq!(1, loads.len(), "expected one countries query: {:?}", loads);
+    let mut ids = loads[0].clone();
+    ids.sort_unstable();
+    assert_eq!(vec![10, 20, 30], ids);
+}
+
+#[test]
+fn selecting_one_edge_loads_only_its_ids() {
+    let ctx = Context {
+        db: Db {
+            countries: vec![models::Country { id: 10 }, models::Country { id: 20 }],
+            country_loads: RefCell::new(Vec::new()),
+        },
+        users: vec![models::User {
+            id: 1,
+            home_country_id: 10,
+            work_country_id: 20,
+        }],
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id homeCountry { id } } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_eq!(
+        json!({
+            "users": [{
+                "id": 1,
+                "homeCountry": { "id": 10 },
+            }],
+        }),
+        json,
+    );
+
+    // With just one of the edges selected there's nothing to union; the unselected edge's
+    // foreign key must not leak into the query.
+    let loads = ctx.db.country_loads.borrow();
+    assert_eq!(vec![vec![10]], *loads);
+}
@@ -0,0 +1,184 @@
+#![cfg(feature = "scylla")]
+
+use juniper_eager_loading::scylla::{load_partitions, ScyllaSession};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct Event {
+    parent_id: i32,
+    name: &'static str,
+}
+
+fn event(parent_id: i32, name: &'static str) -> Event {
+    Event { parent_id, name }
+}
+
+#[derive(Default)]
+struct Stats {
+    active: Cell<usize>,
+    max_active: Cell<usize>,
+}
+
+// Completes on its second poll so several queries can be in flight at once, letting the tests
+// observe how many partitions are queried concurrently.
+struct FakeQuery {
+    outcome: Option<Result<Vec<Event>, String>>,
+    started: bool,
+    stats: Rc<Stats>,
+}
+
+impl Future for FakeQuery {
+    type Output = Result<Vec<Event>, String>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if !self.started {
+            self.started = true;
+            let active = self.stats.active.get() + 1;
+            self.stats.active.set(active);
+            if active > self.stats.max_active.get() {
+                self.stats.max_active.set(active);
+            }
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        self.stats.active.set(self.stats.active.get() - 1);
+        Poll::Ready(self.outcome.take().expect("polled after completion"))
+    }
+}
+
+struct FakeSession {
+    partitions: HashMap<i32, Vec<Event>>,
+    failing_partitions: Vec<i32>,
+    statements: RefCell<Vec<String>>,
+    stats: Rc<Stats>,
+}
+
+impl FakeSession {
+    fn new(partitions: HashMap<i32, Vec<Event>>) -> Self {
+        FakeSession {
+            partitions,
+            failing_partitions: Vec::new(),
+            statements: RefCell::new(Vec::new()),
+            stats: Rc::new(Stats::default()),
+        }
+    }
+}
+
+impl ScyllaSession<i32> for FakeSession {
+    type Model = Event;
+    type Error = String;
+    type Future = FakeQuery;
+
+    fn execute(&self, statement: &str, parent_id: &i32) -> Self::Future {
+        self.statements.borrow_mut().push(statement.to_string());
+
+        let outcome = if self.failing_partitions.contains(parent_id) {
+            Err(format!("partition {} unavailable", parent_id))
+        } else {
+            Ok(self.partitions.get(parent_id).cloned().unwrap_or_default())
+        };
+
+        FakeQuery {
+            outcome: Some(outcome),
+            started: false,
+            stats: Rc::clone(&self.stats),
+        }
+    }
+}
+
+fn partitions() -> HashMap<i32, Vec<Event>> {
+    let mut partitions = HashMap::new();
+    partitions.insert(1, vec![event(1, "a"), event(1, "b")]);
+    partitions.insert(2, vec![event(2, "c")]);
+    partitions.insert(3, vec![event(3, "d")]);
+    partitions
+}
+
+#[test]
+fn merges_partition_results_in_parent_id_order() {
+    let session = FakeSession::new(partitions());
+
+    let results = futures::executor::block_on(load_partitions(
+        &session,
+        "SELECT * FROM events WHERE {parent_id_column} = ?",
+        "user_id",
+        &[2, 1, 4],
+        2,
+    ));
+
+    assert_eq!(
+        results.models,
+        vec![event(2, "c"), event(1, "a"), event(1, "b")]
+    );
+    assert!(results.failures.is_empty());
+    assert_eq!(results.into_result().unwrap().len(), 3);
+
+    let statements = session.statements.borrow();
+    assert_eq!(statements.len(), 3);
+    for statement in statements.iter() {
+        assert_eq!(statement, "SELECT * FROM events WHERE user_id = ?");
+    }
+}
+
+#[test]
+fn respects_the_concurrency_bound() {
+    let session = FakeSession::new(partitions());
+
+    futures::executor::block_on(load_partitions(
+        &session,
+        "SELECT * FROM events WHERE {parent_id_column} = ?",
+        "user_id",
+        &[1, 2, 3],
+        2,
+    ));
+
+    assert_eq!(session.stats.max_active.get(), 2);
+}
+
+#[test]
+fn failed_partitions_are_reported_per_parent() {
+    let mut session = FakeSession::new(partitions());
+    session.failing_partitions = vec![2];
+
+    let results = futures::executor::block_on(load_partitions(
+        &session,
+        "SELECT * FROM events WHERE {parent_id_column} = ?",
+        "user_id",
+        &[1, 2, 3],
+        2,
+    ));
+
+    assert_eq!(
+        results.models,
+        vec![event(1, "a"), event(1, "b"), event(3, "d")]
+    );
+    assert_eq!(
+        results.failures,
+        vec![(2, "partition 2 unavailable".to_string())]
+    );
+}
+
+#[test]
+fn into_result_surfaces_the_first_failure() {
+    let mut session = FakeSession::new(partitions());
+    session.failing_partitions = vec![1, 2];
+
+    let results = futures::executor::block_on(load_partitions(
+        &session,
+        "SELECT * FROM events WHERE {parent_id_column} = ?",
+        "user_id",
+        &[1, 2],
+        1,
+    ));
+
+    assert_eq!(
+        results.into_result().unwrap_err(),
+        "partition 1 unavailable"
+    );
+}
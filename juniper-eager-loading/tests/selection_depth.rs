@@ -0,0 +1,315 @@
+//! Asserts loader call counts for queries of varying selection depth: associations that aren't
+//! walked are never loaded, and when a child's own associations aren't selected the nested eager
+//! loading pass is skipped entirely.
+
+use juniper_eager_loading::{prelude::*, unique, GenericQueryTrail, HasOne, LoadFrom, LoadResult};
+use juniper_from_schema::Walked;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+        pub continent_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Continent {
+        pub id: i32,
+    }
+}
+
+#[derive(Default)]
+pub struct Db {
+    countries: Vec<models::Country>,
+    continents: Vec<models::Continent>,
+    country_loads: AtomicUsize,
+    continent_loads: AtomicUsize,
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.country_loads.fetch_add(1, Ordering::SeqCst);
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LoadFrom<i32> for models::Continent {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.continent_loads.fetch_add(1, Ordering::SeqCst);
+        Ok(db
+            .continents
+            .iter()
+            .filter(|continent| ids.contains(&continent.id))
+            .cloned()
+            .collect())
+    }
+}
+
+// Stand-ins for walked `QueryTrail`s of different selection depths. `DeepTrail` selects the full
+// user → country → continent chain, `ShallowTrail` selects `country` but none of the country's
+// own associations, like `user { country { id } }` would.
+pub struct DeepTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for DeepTrail {}
+
+pub struct ShallowTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for ShallowTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    country: HasOne<Country>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    country: models::Country,
+    continent: HasOne<Continent>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Continent {
+    continent: models::Continent,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+            continent: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Continent {
+    type Model = models::Continent;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            continent: model.clone(),
+        }
+    }
+}
+
+pub struct UserCountryContext;
+
+macro_rules! user_country_impl {
+    ($trail:ty) => {
+        impl EagerLoadChildrenOfType<Country, $trail, UserCountryContext, ()> for User {
+            type ChildId = i32;
+
+            fn child_ids(
+                models: &[Self::Model],
+                _db: &Self::Connection,
+            ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+                let ids = models
+                    .iter()
+                    .map(|model| model.country_id)
+                    .collect::<Vec<_>>();
+                Ok(LoadResult::Ids(unique(ids)))
+            }
+
+            fn load_children(
+                ids: &[Self::ChildId],
+                db: &Self::Connection,
+            ) -> Result<Vec<models::Country>, Self::Error> {
+                <models::Country as LoadFrom<i32>>::load(ids, db)
+            }
+
+            fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+                node.user.country_id == (child.0).country.id
+            }
+
+            fn loaded_child(node: &mut Self, child: Country) {
+                node.country.loaded(child)
+            }
+
+            fn assert_loaded_otherwise_failed(node: &mut Self) {
+                node.country.assert_loaded_otherwise_failed();
+            }
+        }
+
+        impl EagerLoadAllChildren<$trail> for User {
+            fn eager_load_all_children_for_each(
+                nodes: &mut [Self],
+                models: &[Self::Model],
+                db: &Self::Connection,
+                trail: &$trail,
+            ) -> Result<(), Self::Error> {
+                EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+                    nodes, models, db, trail,
+                )?;
+                Ok(())
+            }
+        }
+    };
+}
+
+user_country_impl!(DeepTrail);
+user_country_impl!(ShallowTrail);
+
+pub struct CountryContinentContext;
+
+impl EagerLoadChildrenOfType<Continent, DeepTrail, CountryContinentContext, ()> for Country {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Continent, ())>, Self::Error> {
+        let ids = models
+            .iter()
+            .map(|model| model.continent_id)
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Continent>, Self::Error> {
+        <models::Continent as LoadFrom<i32>>::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Continent, &())) -> bool {
+        node.country.continent_id == (child.0).continent.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Continent) {
+        node.continent.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.continent.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<DeepTrail> for Country {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &DeepTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Continent, _, CountryContinentContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+// Under the shallow trail none of the country's own associations are selected, which is what
+// the derive's `has_nested_selections` override reports for `user { country { id } }`.
+impl EagerLoadAllChildren<ShallowTrail> for Country {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        db: &Self::Connection,
+        _trail: &ShallowTrail,
+    ) -> Result<(), Self::Error> {
+        // The nested pass must never run at all when nothing nested is selected; loading here
+        // would show up in the loader call counts.
+        <models::Continent as LoadFrom<i32>>::load(&[], db)?;
+        Ok(())
+    }
+
+    fn has_nested_selections(_trail: &ShallowTrail) -> bool {
+        false
+    }
+}
+
+impl EagerLoadAllChildren<DeepTrail> for Continent {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &DeepTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+fn db() -> Db {
+    Db {
+        countries: vec![models::Country {
+            id: 1,
+            continent_id: 1,
+        }],
+        continents: vec![models::Continent { id: 1 }],
+        ..Default::default()
+    }
+}
+
+fn user_models() -> Vec<models::User> {
+    vec![models::User {
+        id: 1,
+        country_id: 1,
+    }]
+}
+
+#[test]
+fn a_deep_selection_loads_every_level_once() {
+    let db = db();
+    let user_models = user_models();
+
+    let mut users = User::from_db_models(&user_models);
+    User::eager_load_all_children_for_each(&mut users, &user_models, &db, &DeepTrail).unwrap();
+
+    assert_eq!(db.country_loads.load(Ordering::SeqCst), 1);
+    assert_eq!(db.continent_loads.load(Ordering::SeqCst), 1);
+    assert!(users[0].country.try_unwrap().is_ok());
+}
+
+#[test]
+fn a_shallow_selection_skips_the_nested_pass_entirely() {
+    let db = db();
+    let user_models = user_models();
+
+    let mut users = User::from_db_models(&user_models);
+    User::eager_load_all_children_for_each(&mut users, &user_models, &db, &ShallowTrail).unwrap();
+
+    assert_eq!(db.country_loads.load(Ordering::SeqCst), 1);
+    assert_eq!(db.continent_loads.load(Ordering::SeqCst), 0);
+    assert!(users[0].country.try_unwrap().is_ok());
+}
@@ -0,0 +1,104 @@
+//! With the `serde` feature the edge types serialize and deserialize (when `T` does), so
+//! fully-resolved node trees can be cached between requests. The variant is what matters: a
+//! round-tripped `NotLoaded` stays `NotLoaded` and a failed edge stays failed, it doesn't come
+//! back pretending to be loaded.
+
+#![cfg(feature = "serde")]
+
+use juniper_eager_loading::{
+    AssociationType, Error, HasMany, HasManyThrough, HasOne, OptionHasOne,
+};
+
+fn round_trip<T>(value: &T) -> T
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+}
+
+#[test]
+fn loaded_has_one_round_trips() {
+    let mut edge = HasOne::default();
+    edge.loaded("Denmark".to_owned());
+
+    let back: HasOne<String> = round_trip(&edge);
+
+    assert_eq!(back, edge);
+    assert_eq!(back.try_unwrap().unwrap(), "Denmark");
+}
+
+#[test]
+fn not_loaded_stays_not_loaded() {
+    let edge = HasOne::<i32>::default();
+
+    let back: HasOne<i32> = round_trip(&edge);
+
+    assert!(back.is_not_loaded());
+}
+
+#[test]
+fn a_failed_edge_stays_failed() {
+    let mut edge = HasOne::<i32>::default();
+    edge.assert_loaded_otherwise_failed();
+
+    let back: HasOne<i32> = round_trip(&edge);
+
+    assert!(back.is_failed());
+    assert!(matches!(
+        back.try_unwrap(),
+        Err(Error::LoadFailed(AssociationType::HasOne, None)),
+    ));
+}
+
+#[test]
+fn option_has_one_round_trips_all_states() {
+    let mut loaded = OptionHasOne::default();
+    loaded.loaded(1);
+    assert_eq!(round_trip(&loaded), loaded);
+
+    let mut none = OptionHasOne::<i32>::default();
+    none.loaded_none();
+    let back = round_trip(&none);
+    assert!(back.is_loaded());
+    assert_eq!(back.try_unwrap().unwrap(), &None);
+
+    let mut failed = OptionHasOne::<i32>::default();
+    failed.assert_loaded_otherwise_failed();
+    assert!(round_trip(&failed).is_failed());
+}
+
+#[test]
+fn list_edges_round_trip() {
+    let mut cars = HasMany::default();
+    cars.loaded(1);
+    cars.loaded(2);
+    assert_eq!(round_trip(&cars), cars);
+
+    let mut failed = HasMany::<i32>::default();
+    failed.load_failed();
+    assert!(round_trip(&failed).is_failed());
+
+    let mut companies = HasManyThrough::default();
+    companies.loaded("acme".to_owned());
+    assert_eq!(round_trip(&companies), companies);
+}
+
+#[test]
+fn a_whole_node_struct_can_be_serialized() {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct User {
+        id: i32,
+        country: HasOne<String>,
+        cars: HasMany<i32>,
+    }
+
+    let mut user = User {
+        id: 1,
+        country: HasOne::default(),
+        cars: HasMany::default(),
+    };
+    user.country.loaded("Denmark".to_owned());
+    user.cars.loaded(2);
+
+    assert_eq!(round_trip(&user), user);
+}
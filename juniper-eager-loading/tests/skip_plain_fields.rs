@@ -0,0 +1,195 @@
+//! `#[eager_loading(skip)]` on plain fields that aren't associations — computed values and the
+//! like. The generated constructors initialize them with `Default::default()` and the eager
+//! loading pass ignores them entirely.
+
+use assert_json_diff::assert_json_eq;
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasOne, LoadFrom};
+use juniper_from_schema::graphql_schema;
+use serde_json::json;
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        country: Country!
+        displayName: String! @juniper(ownership: "owned")
+    }
+
+    type Country {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+        Ok(User::eager_load(&ctx.users, &ctx.db, trail)?)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+
+    // Not an association and not the model: the derive leaves these alone.
+    #[eager_loading(skip)]
+    display_name: Option<String>,
+
+    #[eager_loading(skip)]
+    resolve_count: std::cell::Cell<i32>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        Ok(self.country.try_unwrap()?)
+    }
+
+    fn field_display_name(&self, _executor: &Executor<'_, Context>) -> FieldResult<String> {
+        self.resolve_count.set(self.resolve_count.get() + 1);
+        Ok(self
+            .display_name
+            .clone()
+            .unwrap_or_else(|| format!("user #{}", self.user.id)))
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.country.id)
+    }
+}
+
+#[test]
+fn skipped_fields_default_in_the_generated_constructors() {
+    let model = models::User {
+        id: 1,
+        country_id: 10,
+    };
+
+    let user = User::new_from_model(&model);
+    assert_eq!(user.display_name, None);
+    assert_eq!(user.resolve_count.get(), 0);
+
+    let user = User::new_from_owned_model(model);
+    assert_eq!(user.display_name, None);
+    assert_eq!(user.resolve_count.get(), 0);
+}
+
+#[test]
+fn a_struct_mixing_edges_and_plain_fields_loads_correctly() {
+    let ctx = Context {
+        db: Db {
+            countries: vec![models::Country { id: 10 }],
+        },
+        users: vec![models::User {
+            id: 1,
+            country_id: 10,
+        }],
+    };
+
+    let (result, errors) = juniper::execute(
+        "{ users { id country { id } displayName } }",
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+    let json: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_json_eq!(
+        json!({
+            "users": [{
+                "id": 1,
+                "country": { "id": 10 },
+                "displayName": "user #1",
+            }],
+        }),
+        json,
+    );
+}
@@ -0,0 +1,72 @@
+//! Strict not-loaded checks are a per-thread development aid: accessing a `HasOne` that was
+//! never eager loaded panics with the field name instead of returning `Error::NotLoaded`.
+//!
+//! The flag is thread local and each test runs on its own thread, so the tests don't have to
+//! reset it.
+
+use juniper_eager_loading::{set_strict_not_loaded_checks, HasOne};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    id: i32,
+}
+
+fn panic_message(result: std::thread::Result<()>) -> String {
+    let payload = result.unwrap_err();
+    payload
+        .downcast_ref::<String>()
+        .expect("panic payload should be a `String`")
+        .clone()
+}
+
+#[test]
+fn strict_mode_panics_with_the_field_name() {
+    let edge = HasOne::<Country>::new_for_field("User", "country");
+    set_strict_not_loaded_checks(true);
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let _ = edge.try_unwrap();
+    }));
+
+    let message = panic_message(result);
+    assert!(message.contains("`country`"), "message was: {}", message);
+    assert!(message.contains("HasOne<"), "message was: {}", message);
+    assert!(message.contains("Country"), "message was: {}", message);
+}
+
+#[test]
+fn strict_mode_names_edges_without_a_recorded_field() {
+    let edge = HasOne::<Country>::default();
+    set_strict_not_loaded_checks(true);
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let _ = edge.try_unwrap();
+    }));
+
+    let message = panic_message(result);
+    assert!(
+        message.contains("<unknown field>"),
+        "message was: {}",
+        message
+    );
+}
+
+#[test]
+fn strict_mode_leaves_loaded_edges_alone() {
+    let mut edge = HasOne::<Country>::new_for_field("User", "country");
+    edge.loaded(Country { id: 1 });
+    set_strict_not_loaded_checks(true);
+
+    assert_eq!(edge.try_unwrap().unwrap().id, 1);
+}
+
+#[test]
+fn without_strict_mode_the_error_is_returned_as_before() {
+    let edge = HasOne::<Country>::new_for_field("User", "country");
+
+    assert_eq!(
+        edge.try_unwrap().unwrap_err().to_string(),
+        "`User.country` should have been eager loaded, but wasn't",
+    );
+}
@@ -0,0 +1,248 @@
+use juniper_eager_loading::{
+    prelude::*, unique, GenericQueryTrail, HasOne, LoadFrom, LoadResult, SharedCache,
+    SubscriptionLoader,
+};
+use juniper_from_schema::Walked;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+    country_loads: AtomicUsize,
+}
+
+impl Db {
+    fn new(countries: Vec<models::Country>) -> Self {
+        Db {
+            countries,
+            country_loads: AtomicUsize::new(0),
+        }
+    }
+
+    fn country_loads(&self) -> usize {
+        self.country_loads.load(Ordering::SeqCst)
+    }
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.country_loads.fetch_add(1, Ordering::SeqCst);
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+// A stand-in for a walked `QueryTrail` that selects every association.
+pub struct EverythingTrail;
+
+impl<T> GenericQueryTrail<T, Walked> for EverythingTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    country: HasOne<Country>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Country {
+    country: models::Country,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            country: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Country {
+    type Model = models::Country;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            country: model.clone(),
+        }
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for Country {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+struct UserCountryContext;
+
+impl EagerLoadChildrenOfType<Country, EverythingTrail, UserCountryContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Country, ())>, Self::Error> {
+        let ids = models
+            .iter()
+            .map(|model| model.country_id)
+            .collect::<Vec<_>>();
+        Ok(LoadResult::Ids(unique(ids)))
+    }
+
+    fn load_children(
+        ids: &[Self::ChildId],
+        db: &Self::Connection,
+    ) -> Result<Vec<models::Country>, Self::Error> {
+        <models::Country as LoadFrom<i32>>::load(ids, db)
+    }
+
+    fn is_child_of(node: &Self, child: &(Country, &())) -> bool {
+        node.user.country_id == (child.0).country.id
+    }
+
+    fn loaded_child(node: &mut Self, child: Country) {
+        node.country.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.country.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<EverythingTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &EverythingTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Country, _, UserCountryContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+fn user(id: i32, country_id: i32) -> models::User {
+    models::User { id, country_id }
+}
+
+fn country_id_of(node: &User) -> i32 {
+    node.country.try_unwrap().unwrap().country.id
+}
+
+#[test]
+fn loads_batches_from_a_channel_backed_stream() {
+    let db = Db::new(vec![models::Country { id: 1 }]);
+    let loader: SubscriptionLoader<'_, User, _> =
+        SubscriptionLoader::new(&db, SharedCache::new(), |user: &models::User| user.id);
+
+    let (tx, rx) = mpsc::channel();
+    tx.send(vec![user(1, 1), user(2, 1)]).unwrap();
+    tx.send(vec![user(1, 1)]).unwrap();
+    drop(tx);
+
+    let batches = loader
+        .load_batches(rx, &EverythingTrail)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[0].len(), 2);
+    assert_eq!(batches[1].len(), 1);
+    for node in batches.iter().flatten() {
+        assert_eq!(country_id_of(node), 1);
+    }
+
+    // The second batch only contained already loaded nodes, so one load total.
+    assert_eq!(db.country_loads(), 1);
+}
+
+#[test]
+fn ttl_expiry_forces_a_fresh_load() {
+    let db = Db::new(vec![models::Country { id: 1 }]);
+    let cache = SharedCache::with_ttl(Duration::from_secs(0));
+    let loader: SubscriptionLoader<'_, User, _> =
+        SubscriptionLoader::new(&db, cache, |user: &models::User| user.id);
+
+    let first = loader.load_batch(&[user(1, 1)], &EverythingTrail).unwrap();
+    let second = loader.load_batch(&[user(1, 1)], &EverythingTrail).unwrap();
+
+    assert_eq!(country_id_of(&first[0]), 1);
+    assert_eq!(country_id_of(&second[0]), 1);
+    assert_eq!(db.country_loads(), 2);
+}
+
+#[test]
+fn invalidation_forces_a_fresh_load_for_that_id_only() {
+    let db = Db::new(vec![models::Country { id: 1 }]);
+    let loader: SubscriptionLoader<'_, User, _> =
+        SubscriptionLoader::new(&db, SharedCache::new(), |user: &models::User| user.id);
+
+    loader
+        .load_batch(&[user(1, 1), user(2, 1)], &EverythingTrail)
+        .unwrap();
+    assert_eq!(db.country_loads(), 1);
+
+    loader.invalidate(1);
+
+    let batch = loader
+        .load_batch(&[user(1, 1), user(2, 1)], &EverythingTrail)
+        .unwrap();
+    assert_eq!(batch.len(), 2);
+    assert_eq!(db.country_loads(), 2);
+}
+
+#[test]
+fn cached_nodes_are_returned_in_input_order() {
+    let db = Db::new(vec![models::Country { id: 1 }, models::Country { id: 2 }]);
+    let loader: SubscriptionLoader<'_, User, _> =
+        SubscriptionLoader::new(&db, SharedCache::new(), |user: &models::User| user.id);
+
+    loader.load_batch(&[user(1, 1)], &EverythingTrail).unwrap();
+
+    // A mix of cached (1) and fresh (2, 3) models.
+    let batch = loader
+        .load_batch(&[user(2, 2), user(1, 1), user(3, 2)], &EverythingTrail)
+        .unwrap();
+
+    let ids = batch.iter().map(|node| node.user.id).collect::<Vec<_>>();
+    assert_eq!(ids, vec![2, 1, 3]);
+    assert_eq!(country_id_of(&batch[0]), 2);
+    assert_eq!(country_id_of(&batch[1]), 1);
+    assert_eq!(country_id_of(&batch[2]), 2);
+}
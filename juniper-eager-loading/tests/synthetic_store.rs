@@ -0,0 +1,54 @@
+//! Large-scale integration tests over the synthetic store shared with the criterion benchmarks
+//! in `benches/support`. The benchmarks only measure; the correctness of what they build is
+//! verified here.
+
+#[allow(dead_code)]
+#[path = "../benches/support/mod.rs"]
+mod support;
+
+use juniper_eager_loading::Cache;
+use support::{load_all_users, load_countries_through_cache, Db};
+
+#[test]
+fn loads_the_full_chain_for_every_user() {
+    let db = Db::synthetic(1_000, 100, 10);
+
+    let users = load_all_users(&db);
+
+    assert_eq!(users.len(), 1_000);
+    for user in &users {
+        let country = user.country.try_unwrap().unwrap();
+        assert_eq!(country.country.id, user.user.country_id);
+
+        let continent = country.continent.try_unwrap().unwrap();
+        assert_eq!(continent.continent.id, country.country.continent_id);
+    }
+}
+
+#[test]
+fn wide_fan_out_gives_every_user_a_distinct_country() {
+    let db = Db::synthetic(500, 500, 10);
+
+    let users = load_all_users(&db);
+
+    for user in &users {
+        let country = user.country.try_unwrap().unwrap();
+        assert_eq!(country.country.id, user.user.id);
+    }
+}
+
+#[test]
+fn warm_cache_serves_countries_without_hitting_the_store() {
+    let db = Db::synthetic(10, 100, 10);
+    let ids = (0..100).collect::<Vec<_>>();
+    let mut cache = Cache::new();
+
+    let cold = load_countries_through_cache(&ids, &db, &mut cache);
+    assert_eq!(cache.misses(), 100);
+    assert_eq!(cache.hits(), 0);
+
+    let warm = load_countries_through_cache(&ids, &db, &mut cache);
+    assert_eq!(cache.hits(), 100);
+
+    assert_eq!(cold, warm);
+}
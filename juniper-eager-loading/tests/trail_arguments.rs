@@ -0,0 +1,214 @@
+//! `child_ids_with_trail` and `load_children_with_trail` give manual implementations access to
+//! the query trail during loading, so arguments the client supplied on the child field — here
+//! `comments(onlyPublished: true)`, carried by a custom trail type — can narrow the query
+//! instead of being filtered in memory afterwards. Associations that don't care about the
+//! trail keep implementing plain `child_ids`/`load_children`; the defaults delegate.
+
+use juniper_eager_loading::{prelude::*, HasMany, LoadResult};
+
+mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Comment {
+        pub id: i32,
+        pub user_id: i32,
+        pub published: bool,
+    }
+}
+
+pub struct Db {
+    comments: Vec<models::Comment>,
+}
+
+// The stand-in for a query trail that carries typed field arguments. Since the generated
+// trails don't expose arguments, a custom trail (or the context) has to hold them.
+pub struct CommentsTrail {
+    only_published: bool,
+}
+
+impl<T> juniper_eager_loading::GenericQueryTrail<T, juniper_from_schema::Walked> for CommentsTrail {}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    user: models::User,
+    comments: HasMany<Comment>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Comment {
+    comment: models::Comment,
+}
+
+impl GraphqlNodeForModel for User {
+    type Model = models::User;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            user: model.clone(),
+            comments: Default::default(),
+        }
+    }
+}
+
+impl GraphqlNodeForModel for Comment {
+    type Model = models::Comment;
+    type Id = i32;
+    type Connection = Db;
+    type Error = Box<dyn std::error::Error>;
+
+    fn new_from_model(model: &Self::Model) -> Self {
+        Self {
+            comment: model.clone(),
+        }
+    }
+}
+
+impl EagerLoadAllChildren<CommentsTrail> for Comment {
+    fn eager_load_all_children_for_each(
+        _nodes: &mut [Self],
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+        _trail: &CommentsTrail,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub struct UserCommentsContext;
+
+impl EagerLoadChildrenOfType<Comment, CommentsTrail, UserCommentsContext, ()> for User {
+    type ChildId = i32;
+
+    fn child_ids(
+        _models: &[Self::Model],
+        _db: &Self::Connection,
+    ) -> Result<LoadResult<Self::ChildId, (models::Comment, ())>, Self::Error> {
+        unreachable!("the trail-aware variant below is what the loading flow calls")
+    }
+
+    // The foreign key is on `Comment`, so the load goes through `child_ids`, and that's where
+    // the argument narrows the query.
+    fn child_ids_with_trail(
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &CommentsTrail,
+    ) -> Result<LoadResult<Self::ChildId, (models::Comment, ())>, Self::Error> {
+        let user_ids = models.iter().map(|model| model.id).collect::<Vec<_>>();
+        let comments = db
+            .comments
+            .iter()
+            .filter(|comment| user_ids.contains(&comment.user_id))
+            .filter(|comment| !trail.only_published || comment.published)
+            .cloned()
+            .map(|comment| (comment, ()))
+            .collect();
+        Ok(LoadResult::Models(comments))
+    }
+
+    fn load_children(
+        _ids: &[Self::ChildId],
+        _db: &Self::Connection,
+    ) -> Result<Vec<models::Comment>, Self::Error> {
+        unreachable!("`child_ids_with_trail` always returns models")
+    }
+
+    fn is_child_of(node: &Self, child: &(Comment, &())) -> bool {
+        node.user.id == (child.0).comment.user_id
+    }
+
+    fn loaded_child(node: &mut Self, child: Comment) {
+        node.comments.loaded(child)
+    }
+
+    fn assert_loaded_otherwise_failed(node: &mut Self) {
+        node.comments.assert_loaded_otherwise_failed();
+    }
+}
+
+impl EagerLoadAllChildren<CommentsTrail> for User {
+    fn eager_load_all_children_for_each(
+        nodes: &mut [Self],
+        models: &[Self::Model],
+        db: &Self::Connection,
+        trail: &CommentsTrail,
+    ) -> Result<(), Self::Error> {
+        EagerLoadChildrenOfType::<Comment, _, UserCommentsContext, _>::eager_load_children(
+            nodes, models, db, trail,
+        )?;
+        Ok(())
+    }
+}
+
+fn db() -> Db {
+    Db {
+        comments: vec![
+            models::Comment {
+                id: 10,
+                user_id: 1,
+                published: true,
+            },
+            models::Comment {
+                id: 11,
+                user_id: 1,
+                published: false,
+            },
+            models::Comment {
+                id: 20,
+                user_id: 2,
+                published: false,
+            },
+        ],
+    }
+}
+
+fn comment_ids(user: &User) -> Vec<i32> {
+    user.comments
+        .try_unwrap()
+        .unwrap()
+        .iter()
+        .map(|comment| comment.comment.id)
+        .collect()
+}
+
+#[test]
+fn arguments_on_the_trail_narrow_the_load() {
+    let user_models = [models::User { id: 1 }, models::User { id: 2 }];
+    let mut users = User::from_db_models(&user_models);
+    User::eager_load_all_children_for_each(
+        &mut users,
+        &user_models,
+        &db(),
+        &CommentsTrail {
+            only_published: true,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(comment_ids(&users[0]), [10]);
+    assert_eq!(comment_ids(&users[1]), Vec::<i32>::new());
+}
+
+#[test]
+fn without_the_argument_everything_loads() {
+    let user_models = [models::User { id: 1 }, models::User { id: 2 }];
+    let mut users = User::from_db_models(&user_models);
+    User::eager_load_all_children_for_each(
+        &mut users,
+        &user_models,
+        &db(),
+        &CommentsTrail {
+            only_published: false,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(comment_ids(&users[0]), [10, 11]);
+    assert_eq!(comment_ids(&users[1]), [20]);
+}
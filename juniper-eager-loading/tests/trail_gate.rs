@@ -0,0 +1,277 @@
+//! The trail gate: `eager_load_all_children_for_each` only descends into associations the
+//! query actually selected, consulting `EagerLoadChildrenOfType::association_selected` with
+//! the walked trail first. A table backing an unselected association is never queried.
+
+use juniper::{Executor, FieldResult};
+use juniper_eager_loading::{prelude::*, EagerLoading, HasMany, HasOne, LoadFrom};
+use juniper_from_schema::graphql_schema;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+static SELECTED: Mutex<Vec<(&str, bool)>> = Mutex::new(Vec::new());
+
+graphql_schema! {
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+
+    type Query {
+      users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+      noop: Boolean!
+    }
+
+    type User {
+        id: Int!
+        country: Country!
+        cars: [Car!]!
+    }
+
+    type Country {
+        id: Int!
+    }
+
+    type Car {
+        id: Int!
+    }
+}
+
+pub struct Db {
+    countries: Vec<models::Country>,
+    cars: Vec<models::Car>,
+    country_loads: AtomicUsize,
+    car_loads: AtomicUsize,
+}
+
+pub mod models {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct User {
+        pub id: i32,
+        pub country_id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Country {
+        pub id: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    pub struct Car {
+        pub id: i32,
+        pub user_id: i32,
+    }
+}
+
+impl LoadFrom<i32> for models::Country {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.country_loads.fetch_add(1, Ordering::SeqCst);
+        Ok(db
+            .countries
+            .iter()
+            .filter(|country| ids.contains(&country.id))
+            .cloned()
+            .collect())
+    }
+}
+
+// Required by the generated `load_children` even though `has_many` loads through the parent
+// models in practice.
+impl LoadFrom<i32> for models::Car {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(ids: &[i32], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.car_loads.fetch_add(1, Ordering::SeqCst);
+        Ok(db
+            .cars
+            .iter()
+            .filter(|car| ids.contains(&car.id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LoadFrom<models::User> for models::Car {
+    type Error = Box<dyn std::error::Error>;
+    type Connection = Db;
+
+    fn load(users: &[models::User], db: &Self::Connection) -> Result<Vec<Self>, Self::Error> {
+        db.car_loads.fetch_add(1, Ordering::SeqCst);
+        let user_ids = users.iter().map(|user| user.id).collect::<Vec<_>>();
+        Ok(db
+            .cars
+            .iter()
+            .filter(|car| user_ids.contains(&car.user_id))
+            .cloned()
+            .collect())
+    }
+}
+
+pub struct Context {
+    db: Db,
+    users: Vec<models::User>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users<'a>(
+        &self,
+        executor: &Executor<'a, Context>,
+        trail: &QueryTrail<'a, User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        let ctx = executor.context();
+
+        // The hook is callable directly against a walked trail, so generated impls can be
+        // unit tested. Record what it reports for each association.
+        let country_selected = <User as EagerLoadChildrenOfType<
+            Country,
+            _,
+            EagerLoadingContextUserForCountry,
+            _,
+        >>::association_selected(trail.country().walk().as_ref());
+        let cars_selected = <User as EagerLoadChildrenOfType<
+            Car,
+            _,
+            EagerLoadingContextUserForCars,
+            _,
+        >>::association_selected(trail.cars().walk().as_ref());
+        let mut selected = SELECTED.lock().unwrap();
+        selected.push(("country", country_selected));
+        selected.push(("cars", cars_selected));
+        drop(selected);
+
+        let mut users = User::from_db_models(&ctx.users);
+        User::eager_load_all_children_for_each(&mut users, &ctx.users, &ctx.db, trail)?;
+
+        Ok(users)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_noop(&self, _executor: &Executor<'_, Context>) -> FieldResult<&bool> {
+        Ok(&true)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct User {
+    user: models::User,
+
+    #[has_one(default)]
+    country: HasOne<Country>,
+
+    #[has_many(root_model_field = "car")]
+    cars: HasMany<Car>,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.user.id)
+    }
+
+    fn field_country(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Country, Walked>,
+    ) -> FieldResult<&Country> {
+        Ok(self.country.try_unwrap()?)
+    }
+
+    fn field_cars(
+        &self,
+        _executor: &Executor<'_, Context>,
+        _trail: &QueryTrail<'_, Car, Walked>,
+    ) -> FieldResult<&Vec<Car>> {
+        Ok(self.cars.try_unwrap()?)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Country {
+    country: models::Country,
+}
+
+impl CountryFields for Country {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.country.id)
+    }
+}
+
+#[derive(Clone, Debug, EagerLoading)]
+#[eager_loading(connection = "Db", error = "Box<dyn std::error::Error>")]
+pub struct Car {
+    car: models::Car,
+}
+
+impl CarFields for Car {
+    fn field_id(&self, _executor: &Executor<'_, Context>) -> FieldResult<&i32> {
+        Ok(&self.car.id)
+    }
+}
+
+fn run(query: &str) -> (usize, usize) {
+    let ctx = Context {
+        db: Db {
+            countries: vec![models::Country { id: 10 }],
+            cars: vec![models::Car { id: 20, user_id: 1 }],
+            country_loads: AtomicUsize::new(0),
+            car_loads: AtomicUsize::new(0),
+        },
+        users: vec![models::User {
+            id: 1,
+            country_id: 10,
+        }],
+    };
+
+    let (_, errors) = juniper::execute(
+        query,
+        None,
+        &Schema::new(Query, Mutation),
+        &juniper::Variables::new(),
+        &ctx,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "unexpected GraphQL errors: {:?}", errors);
+
+    (
+        ctx.db.country_loads.load(Ordering::SeqCst),
+        ctx.db.car_loads.load(Ordering::SeqCst),
+    )
+}
+
+// One test so the recorded hook calls aren't interleaved by concurrently running tests.
+#[test]
+fn only_selected_associations_are_loaded() {
+    // Only one of the two associations is selected: the other's table is never queried,
+    // and the hook reports exactly what the trail says.
+    let (country_loads, car_loads) = run("{ users { id cars { id } } }");
+    assert_eq!(country_loads, 0);
+    assert_eq!(car_loads, 1);
+    assert_eq!(
+        *SELECTED.lock().unwrap(),
+        [("country", false), ("cars", true)]
+    );
+
+    // Selecting both loads both, once each.
+    SELECTED.lock().unwrap().clear();
+    let (country_loads, car_loads) = run("{ users { id country { id } cars { id } } }");
+    assert_eq!(country_loads, 1);
+    assert_eq!(car_loads, 1);
+    assert_eq!(
+        *SELECTED.lock().unwrap(),
+        [("country", true), ("cars", true)]
+    );
+}
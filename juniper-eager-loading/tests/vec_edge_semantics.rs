@@ -0,0 +1,81 @@
+//! The vec edges used to have surprising loading semantics: the per-child `loaded` appends, so
+//! whether an edge ended up with duplicates depended on how many times a load ran. These tests
+//! pin down the behavior of both paths.
+
+use juniper_eager_loading::{HasMany, HasManyThrough};
+
+#[test]
+fn loaded_appends_one_child_at_a_time() {
+    let mut edge = HasMany::<i32>::default();
+    edge.loaded(1);
+    edge.loaded(2);
+
+    assert_eq!(edge.try_unwrap().unwrap(), &[1, 2]);
+}
+
+#[test]
+fn loading_twice_through_loaded_accumulates_duplicates() {
+    // This is the footgun `loaded_all` exists to avoid.
+    let mut edge = HasMany::<i32>::default();
+    for _ in 0..2 {
+        edge.loaded(1);
+        edge.loaded(2);
+    }
+
+    assert_eq!(edge.try_unwrap().unwrap(), &[1, 2, 1, 2]);
+}
+
+#[test]
+fn loaded_all_replaces_anything_loaded_before() {
+    let mut edge = HasMany::<i32>::default();
+    edge.loaded(99);
+
+    edge.loaded_all(vec![1, 2]);
+    edge.loaded_all(vec![1, 2]);
+
+    assert_eq!(edge.try_unwrap().unwrap(), &[1, 2]);
+}
+
+#[test]
+fn loaded_all_with_an_empty_vec_means_loaded_empty() {
+    let mut edge = HasMany::<i32>::default();
+    edge.loaded(1);
+
+    edge.loaded_all(Vec::new());
+
+    assert_eq!(edge.try_unwrap().unwrap(), &Vec::<i32>::new());
+}
+
+#[test]
+fn append_extends_what_was_loaded_before() {
+    let mut edge = HasMany::<i32>::default();
+    edge.loaded_all(vec![1, 2]);
+
+    edge.append(vec![3, 4]);
+    edge.append(std::iter::once(5));
+
+    assert_eq!(edge.try_unwrap().unwrap(), &[1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn append_does_not_clear_a_failure() {
+    // Unlike `loaded_all`, appending a batch isn't evidence the whole load succeeded.
+    let mut edge = HasMany::<i32>::default();
+    edge.load_failed();
+
+    edge.append(vec![1]);
+
+    assert!(edge.try_unwrap().is_err());
+}
+
+#[test]
+fn has_many_through_behaves_the_same() {
+    let mut edge = HasManyThrough::<i32>::default();
+    edge.loaded(99);
+
+    edge.loaded_all(vec![1, 2]);
+    edge.loaded_all(vec![1, 2]);
+    edge.append(vec![3]);
+
+    assert_eq!(edge.try_unwrap().unwrap(), &[1, 2, 3]);
+}